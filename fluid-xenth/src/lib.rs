@@ -240,6 +240,10 @@ impl TunableSynth for TunableFluid {
         GroupBy::Note
     }
 
+    fn note_velocity(&self, velocity: &u8) -> u8 {
+        *velocity
+    }
+
     fn notes_detune(
         &mut self,
         channel: usize,
@@ -312,6 +316,11 @@ impl TunableSynth for TunableFluid {
         Ok(())
     }
 
+    fn channel_attr(&mut self, channel: usize, mut command: ChannelCommand) -> SendCommandResult {
+        let channel = self.get_channel(channel);
+        self.send_command(move |s| command(s, channel))
+    }
+
     fn global_attr(&mut self, mut command: ChannelCommand) -> SendCommandResult {
         let channels = (self.get_channel(0)..).take(self.polyphony);
         self.send_command(move |s| {
@@ -760,6 +760,7 @@ impl BackendInfo for MidiOutInfo {
             Some(TuningMethod::Octave2Rt) => "Scale/Octave Tuning (2-Byte) (realtime)",
             Some(TuningMethod::ChannelFineTuning) => "Channel Fine Tuning",
             Some(TuningMethod::PitchBend) => "Pitch Bend",
+            Some(TuningMethod::Mpe) => "MPE",
             None => "None. Tuning channels exceeded! Change tuning mode.",
         };
 
@@ -186,6 +186,7 @@ pub fn get_default_profile() -> MicrowaveProfile {
             out_device: "<midi-device>".to_owned(),
             out_args: Default::default(),
             tuning_method: TuningMethod::Octave1,
+            release_time: None,
         }),
         AudioStageSpec::NoAudio,
         AudioStageSpec::StereoProcessor(StereoProcessorSpec {
@@ -300,6 +301,7 @@ pub fn get_default_profile() -> MicrowaveProfile {
         envelopes,
         stages,
         color_palette,
+        tuning: None,
     }
 }
 
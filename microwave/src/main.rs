@@ -41,6 +41,7 @@ use tune_cli::{
         error::ResultExt,
         midi::MidiInArgs,
         scala::{KbmOptions, SclCommand},
+        spec::TuningSpec,
     },
     CliError, CliResult,
 };
@@ -74,7 +75,7 @@ enum MainCommand {
     /// Use a kbm file
     #[command(name = "kbm-file")]
     UseKbmFile {
-        /// The location of the kbm file to import
+        /// The location of the kbm file to import (or a YAML/JSON file written by `tune --format yaml kbm`)
         kbm_file_location: PathBuf,
 
         #[command(flatten)]
@@ -136,6 +137,11 @@ struct RunOptions {
     #[arg(long = "lim", default_value = "11")]
     odd_limit: u16,
 
+    /// Tuning expression replacing the keyboard mapping and scale, e.g. 31edo@D4 or "harm 8 16 @ C4".
+    /// Cannot be combined with ref-note, kbm-file or a scale subcommand.
+    #[arg(long = "tuning")]
+    tuning_spec: Option<TuningSpec>,
+
     #[command(subcommand)]
     scl: Option<SclCommand>,
 }
@@ -333,38 +339,23 @@ fn main() {
 impl MainCommand {
     async fn run(self) -> CliResult {
         match self {
-            MainCommand::Run(options) => {
-                options
-                    .run(
-                        Kbm::builder(NoteLetter::D.in_octave(4)).build().unwrap(),
-                        None,
-                    )
-                    .await
-            }
+            MainCommand::Run(options) => options.run(None, None).await,
             MainCommand::Lumatone {
                 midi_lumatone_device: lumatone_device,
                 options,
-            } => {
-                options
-                    .run(
-                        Kbm::builder(NoteLetter::D.in_octave(4))
-                            .range(
-                                PianoKey::from_midi_number(-lumatone::RANGE_RADIUS)
-                                    ..PianoKey::from_midi_number(lumatone::RANGE_RADIUS),
-                            )
-                            .build()
-                            .unwrap(),
-                        Some(lumatone_device),
-                    )
-                    .await
+            } => options.run(None, Some(lumatone_device)).await,
+            MainCommand::WithRefNote { kbm, options } => {
+                options.run(Some(kbm.to_kbm()?), None).await
             }
-            MainCommand::WithRefNote { kbm, options } => options.run(kbm.to_kbm()?, None).await,
             MainCommand::UseKbmFile {
                 kbm_file_location,
                 options,
             } => {
                 options
-                    .run(shared::scala::import_kbm_file(&kbm_file_location)?, None)
+                    .run(
+                        Some(shared::scala::import_kbm_file(&kbm_file_location)?),
+                        None,
+                    )
                     .await
             }
             MainCommand::Devices => {
@@ -386,21 +377,59 @@ impl MainCommand {
 }
 
 impl RunOptions {
-    async fn run(self, kbm: Kbm, lumatone_device: Option<String>) -> CliResult {
-        let scl = self
-            .scl
-            .as_ref()
-            .map(|command| command.to_scl(None))
-            .transpose()?
-            .unwrap_or_else(|| {
-                Scl::builder()
-                    .push_ratio(Ratio::from_semitones(1))
-                    .build()
-                    .unwrap()
-            });
+    async fn run(self, kbm: Option<Kbm>, lumatone_device: Option<String>) -> CliResult {
+        if self.tuning_spec.is_some() && kbm.is_some() {
+            return Err("--tuning cannot be combined with ref-note or kbm-file"
+                .to_owned()
+                .into());
+        }
+        if self.tuning_spec.is_some() && self.scl.is_some() {
+            return Err("--tuning cannot be combined with a scale subcommand"
+                .to_owned()
+                .into());
+        }
 
         let profile = MicrowaveProfile::load(&self.profile_location).await?;
 
+        // The profile's tuning is only a default and does not override an explicitly given scale or keyboard mapping
+        let tuning_spec = match (&self.tuning_spec, &profile.tuning) {
+            (Some(tuning_spec), _) => Some(tuning_spec.clone()),
+            (None, Some(tuning_spec)) if self.scl.is_none() && kbm.is_none() => {
+                Some(tuning_spec.parse()?)
+            }
+            _ => None,
+        };
+
+        let (scl, kbm) = match tuning_spec {
+            Some(tuning_spec) => (tuning_spec.to_scl()?, tuning_spec.to_kbm()?),
+            None => {
+                let scl = self
+                    .scl
+                    .as_ref()
+                    .map(|command| command.to_scl(None))
+                    .transpose()?
+                    .unwrap_or_else(|| {
+                        Scl::builder()
+                            .push_ratio(Ratio::from_semitones(1))
+                            .build()
+                            .unwrap()
+                    });
+                let kbm = kbm.unwrap_or_else(|| {
+                    let builder = Kbm::builder(NoteLetter::D.in_octave(4));
+                    match lumatone_device {
+                        Some(_) => builder.range(
+                            PianoKey::from_midi_number(-lumatone::RANGE_RADIUS)
+                                ..PianoKey::from_midi_number(lumatone::RANGE_RADIUS),
+                        ),
+                        None => builder,
+                    }
+                    .build()
+                    .unwrap()
+                });
+                (scl, kbm)
+            }
+        };
+
         let virtual_keyboard =
             VirtualKeyboardResource::new(&scl, self.custom_keyboard, &profile.color_palette);
 
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, hash::Hash, sync::Arc};
+use std::{fmt::Debug, hash::Hash, sync::Arc, time::Duration};
 
 use flume::Sender;
 use midi::MultiChannelOffset;
@@ -37,6 +37,9 @@ pub struct MidiOutSpec {
     pub out_args: MidiOutArgs,
 
     pub tuning_method: TuningMethod,
+
+    /// Time in seconds that the channel of a released note is kept reserved when tuning just-in-time.
+    pub release_time: Option<f64>,
 }
 
 impl MidiOutSpec {
@@ -50,7 +53,7 @@ impl MidiOutSpec {
     ) -> CliResult {
         let (midi_send, midi_recv) = flume::unbounded();
 
-        let (device, mut midi_out, target) =
+        let (device, mut midi_out, synth) =
             match midi::connect_to_out_device("microwave", &self.out_device)
                 .handle_error("Could not connect to MIDI output device")
                 .and_then(|(device, midi_out)| {
@@ -59,6 +62,11 @@ impl MidiOutSpec {
                             midi_events: midi_send,
                         })
                         .map(|target| (device, midi_out, target))
+                })
+                .and_then(|(device, midi_out, target)| {
+                    self.out_args
+                        .create_synth(target, self.tuning_method)
+                        .map(|synth| (device, midi_out, synth))
                 }) {
                 Ok(ok) => ok,
                 Err(error_message) => {
@@ -77,7 +85,8 @@ impl MidiOutSpec {
             }
         });
 
-        let synth = self.out_args.create_synth(target, self.tuning_method);
+        let mut backend = TunableBackend::new(synth);
+        backend.set_release_time(self.release_time.map(Duration::from_secs_f64));
 
         let backend = MidiOutBackend {
             note_input: self.note_input,
@@ -85,7 +94,7 @@ impl MidiOutSpec {
             device: device.into(),
             tuning_method: self.tuning_method,
             curr_program: 0,
-            backend: TunableBackend::new(synth),
+            backend,
         };
 
         backends.push(Box::new(backend));
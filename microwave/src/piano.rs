@@ -8,8 +8,9 @@ use flume::Sender;
 use tune::{
     key::PianoKey,
     midi::ChannelMessageType,
-    pitch::Pitch,
+    pitch::{Pitch, Ratio},
     scala::{Kbm, Scl},
+    tuner::AdaptiveTuningModel,
     tuning::Tuning,
 };
 use tune_cli::shared::midi::MultiChannelOffset;
@@ -40,13 +41,15 @@ pub struct PianoEngineState {
 pub enum TuningMode {
     Fixed,
     Continuous,
+    Adaptive,
 }
 
 impl TuningMode {
     fn toggle(&mut self) {
         *self = match *self {
             TuningMode::Fixed => TuningMode::Continuous,
-            TuningMode::Continuous => TuningMode::Fixed,
+            TuningMode::Continuous => TuningMode::Adaptive,
+            TuningMode::Adaptive => TuningMode::Fixed,
         }
     }
 }
@@ -59,6 +62,8 @@ pub struct KeyInfo {
 struct PianoEngineModel {
     state: PianoEngineState,
     backends: Toggle<DynBackend<SourceId>>,
+    adaptive_tuning: AdaptiveTuningModel<SourceId>,
+    degrees: HashMap<SourceId, i32>,
     storage_updates: Sender<LiveParameterStorage>,
 }
 
@@ -99,6 +104,11 @@ impl PianoEngine {
         let mut model = PianoEngineModel {
             state: state.clone(),
             backends: backends.into(),
+            adaptive_tuning: AdaptiveTuningModel::new(
+                ADAPTIVE_ODD_LIMIT,
+                Ratio::from_cents(ADAPTIVE_MAX_DRIFT_IN_CENTS),
+            ),
+            degrees: HashMap::new(),
             storage_updates,
         };
 
@@ -271,6 +281,7 @@ impl PianoEngineModel {
         match event {
             Event::Pressed(id, location, velocity) => {
                 let (degree, pitch) = self.degree_and_pitch(location);
+                let (pitch, adapted_pitches) = self.adapt_pitch(id, degree, pitch);
                 let curr_backend = self.backends.curr_index();
                 for (backend_id, backend) in self.backends.into_iter().enumerate() {
                     let is_curr_backend = backend_id == curr_backend;
@@ -283,10 +294,12 @@ impl PianoEngineModel {
                         self.state.keys_updated = true;
                     }
                 }
+                self.update_adapted_pitches(adapted_pitches);
             }
             Event::Moved(id, location) => {
                 if self.storage.is_active(LiveParameter::Legato) {
                     let (degree, pitch) = self.degree_and_pitch(location);
+                    let (pitch, adapted_pitches) = self.adapt_pitch(id, degree, pitch);
                     for (backend_id, backend) in self.backends.into_iter().enumerate() {
                         if let Some(key_info) = self.state.pressed_keys.get_mut(&(id, backend_id)) {
                             backend.update_pitch(id, degree, pitch, 100);
@@ -296,6 +309,7 @@ impl PianoEngineModel {
                             self.state.keys_updated = true;
                         }
                     }
+                    self.update_adapted_pitches(adapted_pitches);
                 }
             }
             Event::Released(id, velocity) => {
@@ -304,6 +318,43 @@ impl PianoEngineModel {
                     self.state.pressed_keys.remove(&(id, backend_id));
                     self.state.keys_updated = true;
                 }
+                self.degrees.remove(&id);
+                let adapted_pitches = self.adaptive_tuning.deregister_key(id);
+                self.update_adapted_pitches(adapted_pitches);
+            }
+        }
+    }
+
+    /// Returns the adapted pitch of the given key and the adapted pitches of all other held keys.
+    fn adapt_pitch(
+        &mut self,
+        id: SourceId,
+        degree: i32,
+        mut pitch: Pitch,
+    ) -> (Pitch, Vec<(SourceId, Pitch)>) {
+        if !matches!(self.tuning_mode, TuningMode::Adaptive) {
+            return (pitch, Vec::new());
+        }
+
+        self.degrees.insert(id, degree);
+        let mut adapted_pitches = self.adaptive_tuning.register_key(id, pitch);
+        if let Some(position) = adapted_pitches.iter().position(|&(key, _)| key == id) {
+            pitch = adapted_pitches.remove(position).1;
+        }
+        (pitch, adapted_pitches)
+    }
+
+    fn update_adapted_pitches(&mut self, adapted_pitches: Vec<(SourceId, Pitch)>) {
+        for (id, pitch) in adapted_pitches {
+            let degree = self.degrees[&id];
+            for (backend_id, backend) in self.backends.into_iter().enumerate() {
+                if let Some(key_info) = self.state.pressed_keys.get_mut(&(id, backend_id)) {
+                    backend.update_pitch(id, degree, pitch, 100);
+                    if let Some(key_info) = key_info {
+                        key_info.pitch = pitch;
+                    }
+                    self.state.keys_updated = true;
+                }
             }
         }
     }
@@ -316,7 +367,7 @@ impl PianoEngineModel {
 
                 match self.tuning_mode {
                     TuningMode::Continuous => (degree, pitch),
-                    TuningMode::Fixed => (degree, tuning.pitch_of(degree)),
+                    TuningMode::Fixed | TuningMode::Adaptive => (degree, tuning.pitch_of(degree)),
                 }
             }
             Location::Degree(degree) => (degree, tuning.pitch_of(degree)),
@@ -382,7 +433,7 @@ impl PianoEngineModel {
                 TuningMode::Fixed => {
                     backend.set_tuning((&self.state.scl, self.state.kbm.kbm_root()))
                 }
-                TuningMode::Continuous => backend.set_no_tuning(),
+                TuningMode::Continuous | TuningMode::Adaptive => backend.set_no_tuning(),
             }
             self.state.tuning_updated = true;
         }
@@ -390,6 +441,9 @@ impl PianoEngineModel {
     }
 }
 
+const ADAPTIVE_ODD_LIMIT: u16 = 11;
+const ADAPTIVE_MAX_DRIFT_IN_CENTS: f64 = 20.0;
+
 pub enum Event {
     Pressed(SourceId, Location, u8),
     Moved(SourceId, Location),
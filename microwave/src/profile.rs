@@ -33,6 +33,9 @@ pub struct MicrowaveProfile {
     pub envelopes: Vec<NamedEnvelopeSpec<WaveformAutomatableValue>>,
     pub stages: Vec<AudioStageSpec>,
     pub color_palette: ColorPalette,
+    /// Default tuning expression, e.g. `31edo@D4`. Used if no scale is given on the command line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tuning: Option<String>,
 }
 
 impl MicrowaveProfile {
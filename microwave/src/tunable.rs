@@ -1,4 +1,4 @@
-use std::{fmt::Debug, hash::Hash, mem, ops::RangeInclusive};
+use std::{fmt::Debug, hash::Hash, mem, ops::RangeInclusive, time::Duration};
 
 use tune::{
     note::Note,
@@ -12,6 +12,7 @@ use crate::keypress::{IllegalState, KeypressTracker, LiftAction, PlaceAction};
 
 pub struct TunableBackend<K, S> {
     tuner: Tuner<K, S>,
+    release_time: Option<Duration>,
 }
 
 impl<K, S: TunableSynth> TunableBackend<K, S> {
@@ -21,6 +22,7 @@ impl<K, S: TunableSynth> TunableBackend<K, S> {
                 aot_tuner: AotTuner::start(synth),
                 keypress_tracker: KeypressTracker::new(),
             },
+            release_time: None,
         }
     }
 }
@@ -40,6 +42,14 @@ impl<K: Copy + Eq + Hash + Debug + Send, S: TunableSynth> TunableBackend<K, S>
 where
     S::Result: Debug,
 {
+    /// Sets the time for which channels of released notes are kept reserved when tuning just-in-time.
+    pub fn set_release_time(&mut self, release_time: Option<Duration>) {
+        self.release_time = release_time;
+        if let Tuner::Jit { jit_tuner } = &mut self.tuner {
+            jit_tuner.set_release_time(release_time);
+        }
+    }
+
     pub fn set_tuning(&mut self, tuning: (&Scl, KbmRoot)) {
         let synth = self.destroy_tuning();
         let mut aot_tuner = AotTuner::start(synth);
@@ -72,7 +82,8 @@ where
 
     pub fn set_no_tuning(&mut self) {
         let synth = self.destroy_tuning();
-        let jit_tuner = JitTuner::start(synth, PoolingMode::Stop);
+        let mut jit_tuner = JitTuner::start(synth, PoolingMode::Stop);
+        jit_tuner.set_release_time(self.release_time);
         self.tuner = Tuner::Jit { jit_tuner };
     }
 
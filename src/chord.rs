@@ -0,0 +1,275 @@
+//! Find chords in tunings.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{
+    math,
+    pitch::Ratio,
+    temperament::{Comma, Val},
+    tuning::Tuning,
+};
+
+/// A chord given by the harmonics of its notes, e.g. 4:5:6:7 for the harmonic seventh chord.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::chord::Chord;
+/// let harmonic_seventh = "4:5:6:7".parse::<Chord>().unwrap();
+///
+/// assert_eq!(harmonic_seventh.harmonics(), [4, 5, 6, 7]);
+/// assert_eq!(harmonic_seventh.to_string(), "4:5:6:7");
+///
+/// let intervals: Vec<_> = harmonic_seventh.intervals().map(|ratio| ratio.as_float()).collect();
+/// assert_approx_eq!(intervals[0], 1.0);
+/// assert_approx_eq!(intervals[1], 1.25);
+/// assert_approx_eq!(intervals[2], 1.5);
+/// assert_approx_eq!(intervals[3], 1.75);
+///
+/// assert!("4:5:0".parse::<Chord>().is_err());
+/// assert!("4".parse::<Chord>().is_err());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chord {
+    harmonics: Vec<u16>,
+}
+
+impl Chord {
+    /// Creates a [`Chord`] from the given harmonics.
+    ///
+    /// [`None`] is returned if there are fewer than two notes or a harmonic is zero.
+    pub fn from_harmonics(harmonics: impl Into<Vec<u16>>) -> Option<Self> {
+        let harmonics = harmonics.into();
+        (harmonics.len() >= 2 && !harmonics.contains(&0)).then_some(Self { harmonics })
+    }
+
+    pub fn harmonics(&self) -> &[u16] {
+        &self.harmonics
+    }
+
+    /// The intervals of all notes relative to the first note.
+    pub fn intervals(&self) -> impl Iterator<Item = Ratio> + '_ {
+        let root = f64::from(self.harmonics[0]);
+        self.harmonics
+            .iter()
+            .map(move |&harmonic| Ratio::from_float(f64::from(harmonic) / root))
+    }
+
+    /// Voices the chord in the given [`Tuning`] starting at `root`.
+    ///
+    /// Every note of the chord is mapped to the closest key or degree of the tuning.
+    /// [`None`] is returned if two notes of the chord are mapped to the same key or degree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::chord::Chord;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// let tet12 = Scl::builder().push_ratio(Ratio::from_semitones(1)).build().unwrap();
+    /// let tuning = (tet12, KbmRoot::from(NoteLetter::C.in_octave(4)));
+    ///
+    /// let harmonic_seventh = "4:5:6:7".parse::<Chord>().unwrap();
+    /// let voicing = harmonic_seventh.voicing_in::<i32>(&tuning, 0).unwrap();
+    ///
+    /// assert_eq!(voicing.notes, [0, 4, 7, 10]);
+    ///
+    /// // The deviations are relative to the optimally transposed ideal chord
+    /// assert_approx_eq!(voicing.deviations[0].as_cents(), -10.726, 1e-3);
+    /// assert_approx_eq!(voicing.deviations[1].as_cents(), 2.960, 1e-3);
+    /// assert_approx_eq!(voicing.deviations[2].as_cents(), -12.681, 1e-3);
+    /// assert_approx_eq!(voicing.deviations[3].as_cents(), 20.448, 1e-3);
+    /// assert_approx_eq!(voicing.max_deviation().as_cents(), 20.448, 1e-3);
+    ///
+    /// // A chord with a step smaller than the tuning's resolution cannot be voiced
+    /// let cluster = "24:25:26".parse::<Chord>().unwrap();
+    /// assert!(cluster.voicing_in::<i32>(&tuning, 0).is_none());
+    /// ```
+    pub fn voicing_in<K: Copy + PartialEq>(
+        &self,
+        tuning: &impl Tuning<K>,
+        root: K,
+    ) -> Option<ChordVoicing<K>> {
+        let root_pitch = tuning.pitch_of(root);
+
+        let mut notes = Vec::<K>::new();
+        let mut deviations = Vec::new();
+        for interval in self.intervals() {
+            let ideal_pitch = root_pitch * interval;
+            let note = match notes.is_empty() {
+                true => root,
+                false => tuning.find_by_pitch(ideal_pitch).approx_value,
+            };
+            if notes.contains(&note) {
+                return None;
+            }
+            notes.push(note);
+            deviations.push(Ratio::between_pitches(ideal_pitch, tuning.pitch_of(note)));
+        }
+
+        Some(ChordVoicing::centered(notes, deviations))
+    }
+
+    /// Finds all voicings of the chord in the given [`Tuning`] whose deviations do not exceed `tolerance`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::chord::Chord;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::note::NoteLetter;
+    /// let pythagorean_major =
+    ///     scala::create_rank2_temperament_scale(None, Ratio::from_float(1.5), 5, 1, Ratio::octave())
+    ///         .unwrap();
+    /// let tuning = (pythagorean_major, KbmRoot::from(NoteLetter::C.in_octave(4)));
+    ///
+    /// // Pythagorean thirds are too wide for a pure major triad
+    /// let major = "4:5:6".parse::<Chord>().unwrap();
+    /// assert!(major.find_in::<i32>(&tuning, 0..7, Ratio::from_cents(5.0)).is_empty());
+    ///
+    /// // A tolerance of 15 cents is sufficient to find the major triads on C, F and G
+    /// let voicings = major.find_in::<i32>(&tuning, 0..7, Ratio::from_cents(15.0));
+    /// let roots: Vec<_> = voicings.iter().map(|voicing| voicing.notes[0]).collect();
+    /// assert_eq!(roots, [0, 3, 4]);
+    /// ```
+    pub fn find_in<K: Copy + PartialEq>(
+        &self,
+        tuning: &impl Tuning<K>,
+        roots: impl IntoIterator<Item = K>,
+        tolerance: Ratio,
+    ) -> Vec<ChordVoicing<K>> {
+        roots
+            .into_iter()
+            .filter_map(|root| self.voicing_in(tuning, root))
+            .filter(|voicing| voicing.max_deviation() <= tolerance.abs())
+            .collect()
+    }
+
+    /// Voices the chord in the rank-1 temperament defined by the given [`Val`].
+    ///
+    /// In contrast to [`Chord::voicing_in`], the notes are not mapped to their closest steps but to the steps defined by the [`Val`].
+    /// [`None`] is returned if the chord exceeds the prime limit of the [`Val`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::chord::Chord;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::temperament::Val;
+    /// let val_of_31edo = Val::patent(Ratio::octave().divided_into_equal_steps(31), 7);
+    ///
+    /// let harmonic_seventh = "4:5:6:7".parse::<Chord>().unwrap();
+    /// let voicing = harmonic_seventh.voicing_in_val(&val_of_31edo).unwrap();
+    ///
+    /// assert_eq!(voicing.notes, [0, 10, 18, 25]);
+    /// assert_approx_eq!(voicing.max_deviation().as_cents(), 3.810, 1e-3);
+    ///
+    /// // 11-limit intervals cannot be represented by a 7-limit val
+    /// let otonal_tetrad = "8:9:10:11".parse::<Chord>().unwrap();
+    /// assert!(otonal_tetrad.voicing_in_val(&val_of_31edo).is_none());
+    /// ```
+    pub fn voicing_in_val(&self, val: &Val) -> Option<ChordVoicing<i32>> {
+        let root_steps = val.map(&harmonic_as_comma(self.harmonics[0])?)?;
+
+        let mut notes = Vec::new();
+        let mut deviations = Vec::new();
+        for (&harmonic, interval) in self.harmonics.iter().zip(self.intervals()) {
+            let steps = val.map(&harmonic_as_comma(harmonic)?)? - root_steps;
+            notes.push(steps);
+            deviations.push(val.step_size().repeated(steps).deviation_from(interval));
+        }
+
+        Some(ChordVoicing::centered(notes, deviations))
+    }
+}
+
+fn harmonic_as_comma(mut harmonic: u16) -> Option<Comma> {
+    let mut prime_factors = Vec::new();
+    for &prime in math::U8_PRIMES {
+        if harmonic == 1 {
+            break;
+        }
+        let mut power = 0;
+        while harmonic % u16::from(prime) == 0 {
+            harmonic /= u16::from(prime);
+            power += 1;
+        }
+        prime_factors.push(power);
+    }
+    (harmonic == 1).then(|| Comma::new("", prime_factors))
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let harmonics: Vec<_> = self.harmonics.iter().map(u16::to_string).collect();
+        write!(f, "{}", harmonics.join(":"))
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let harmonics = s
+            .split(':')
+            .map(|harmonic| {
+                harmonic
+                    .trim()
+                    .parse::<u16>()
+                    .map_err(|_| format!("Invalid harmonic '{harmonic}'"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Chord::from_harmonics(harmonics).ok_or_else(|| {
+            "Must be a list of at least two nonzero harmonics, e.g. 4:5:6".to_owned()
+        })
+    }
+}
+
+/// The notes of a [`Chord`] in a specific tuning created by [`Chord::voicing_in`] or [`Chord::voicing_in_val`].
+#[derive(Clone, Debug)]
+pub struct ChordVoicing<K> {
+    /// The keys or degrees of the notes of the chord. The first note is the root.
+    pub notes: Vec<K>,
+
+    /// The deviations of the notes from the ideal chord where positive values mean sharp.
+    /// The ideal chord is transposed s.t. the deviations average to zero.
+    pub deviations: Vec<Ratio>,
+}
+
+impl<K> ChordVoicing<K> {
+    fn centered(notes: Vec<K>, deviations: Vec<Ratio>) -> Self {
+        let mean_deviation = Ratio::from_cents(
+            deviations
+                .iter()
+                .map(|deviation| deviation.as_cents())
+                .sum::<f64>()
+                / deviations.len() as f64,
+        );
+        Self {
+            notes,
+            deviations: deviations
+                .into_iter()
+                .map(|deviation| deviation.deviation_from(mean_deviation))
+                .collect(),
+        }
+    }
+
+    /// The largest absolute deviation of all notes.
+    pub fn max_deviation(&self) -> Ratio {
+        self.deviations
+            .iter()
+            .map(|deviation| deviation.abs())
+            .max_by(Ratio::total_cmp)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,437 @@
+//! Measure the consonance of intervals and spectra.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{
+    math,
+    pitch::{Pitch, Ratio},
+    scala::Scl,
+};
+
+/// Harmonic entropy model by Paul Erlich.
+///
+/// The model assumes that the ear tries to match any heard interval against a set of candidate fractions.
+/// The less certain the match, the higher the harmonic entropy and the more dissonant the interval.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance::HarmonicEntropy;
+/// # use tune::pitch::Ratio;
+/// let harmonic_entropy = HarmonicEntropy::new(1000, Ratio::from_cents(17.0)).unwrap();
+///
+/// let fifth = harmonic_entropy.of(Ratio::from_float(1.5));
+/// let tritone = harmonic_entropy.of(Ratio::from_semitones(6));
+/// let et_fifth = harmonic_entropy.of(Ratio::from_semitones(7));
+///
+/// assert!(fifth < et_fifth);
+/// assert!(et_fifth < tritone);
+///
+/// // No candidate fraction of a model with low complexity is close to 5 octaves
+/// let low_complexity = HarmonicEntropy::new(10, Ratio::from_cents(17.0)).unwrap();
+/// assert!(low_complexity.of(Ratio::from_octaves(5)).is_nan());
+/// ```
+#[derive(Clone, Debug)]
+pub struct HarmonicEntropy {
+    candidates: Vec<Candidate>,
+    spread_in_cents: f64,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Candidate {
+    cents: f64,
+    weight: f64,
+}
+
+impl HarmonicEntropy {
+    /// Creates a new [`HarmonicEntropy`] model.
+    ///
+    /// The candidate fractions are all reduced fractions *n*/*d* and *d*/*n* with *n*·*d* ≤ `max_complexity`.
+    /// Each candidate is weighted by 1/√(*n*·*d*).
+    /// `spread` is the standard deviation of the Gaussian distribution modelling the inaccuracy of the ear.
+    ///
+    /// Returns [`None`] if `max_complexity` is 0 or `spread` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::consonance::HarmonicEntropy;
+    /// # use tune::pitch::Ratio;
+    /// assert!(HarmonicEntropy::new(1000, Ratio::from_cents(17.0)).is_some());
+    /// assert!(HarmonicEntropy::new(0, Ratio::from_cents(17.0)).is_none());
+    /// assert!(HarmonicEntropy::new(1000, Ratio::from_cents(0.0)).is_none());
+    /// assert!(HarmonicEntropy::new(1000, Ratio::from_cents(-17.0)).is_none());
+    /// ```
+    pub fn new(max_complexity: u32, spread: Ratio) -> Option<Self> {
+        let spread_in_cents = spread.as_cents();
+        if max_complexity == 0 || spread_in_cents.is_nan() || spread_in_cents <= 0.0 {
+            return None;
+        }
+
+        let mut candidates = Vec::new();
+        for denom in 1..=max_complexity {
+            for numer in denom..=max_complexity / denom {
+                if math::gcd_u64(numer.into(), denom.into()) != 1 {
+                    continue;
+                }
+                let cents = Ratio::from_float(f64::from(numer) / f64::from(denom)).as_cents();
+                let weight = 1.0 / f64::from(numer * denom).sqrt();
+                candidates.push(Candidate { cents, weight });
+                if numer != denom {
+                    candidates.push(Candidate {
+                        cents: -cents,
+                        weight,
+                    });
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.cents.total_cmp(&b.cents));
+
+        Some(Self {
+            candidates,
+            spread_in_cents,
+        })
+    }
+
+    /// Calculates the harmonic entropy of the given interval in nats.
+    ///
+    /// Returns NaN if no candidate fraction is close enough to the interval to be matched.
+    pub fn of(&self, interval: Ratio) -> f64 {
+        let cents = interval.as_cents();
+
+        // Candidates further away than 6 standard deviations have a negligible probability
+        let window = 6.0 * self.spread_in_cents;
+        let lower = self
+            .candidates
+            .partition_point(|candidate| candidate.cents < cents - window);
+        let upper = self
+            .candidates
+            .partition_point(|candidate| candidate.cents <= cents + window);
+
+        let probabilities: Vec<_> = self.candidates[lower..upper]
+            .iter()
+            .map(|candidate| {
+                let distance = (cents - candidate.cents) / self.spread_in_cents;
+                candidate.weight * (-0.5 * distance * distance).exp()
+            })
+            .collect();
+        let total: f64 = probabilities.iter().sum();
+        if total == 0.0 {
+            return f64::NAN;
+        }
+
+        probabilities
+            .iter()
+            .map(|probability| probability / total)
+            .filter(|&probability| probability > 0.0)
+            .map(|probability| -probability * probability.ln())
+            .sum()
+    }
+}
+
+/// A single sinusoidal component of a [`Spectrum`].
+#[derive(Copy, Clone, Debug)]
+pub struct Partial {
+    /// The frequency of the partial relative to the fundamental.
+    pub ratio: Ratio,
+
+    /// The amplitude of the partial.
+    pub amplitude: f64,
+}
+
+/// The partials of a timbre, used to calculate sensory dissonance curves following William Sethares.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::consonance::Spectrum;
+/// let spectrum = "1,2.76@0.5,5.4@0.25".parse::<Spectrum>().unwrap();
+///
+/// assert_eq!(spectrum.partials().len(), 3);
+/// assert_approx_eq!(spectrum.partials()[1].ratio.as_float(), 2.76);
+/// assert_approx_eq!(spectrum.partials()[1].amplitude, 0.5);
+/// assert_eq!(spectrum.to_string(), "1.0000@1,2.7600@0.5,5.4000@0.25");
+///
+/// assert!("".parse::<Spectrum>().is_err());
+/// assert!("1,2@x".parse::<Spectrum>().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    partials: Vec<Partial>,
+}
+
+impl Spectrum {
+    /// Creates a [`Spectrum`] from the given partials.
+    ///
+    /// [`None`] is returned if the list of partials is empty.
+    pub fn from_partials(partials: impl Into<Vec<Partial>>) -> Option<Self> {
+        let partials = partials.into();
+        (!partials.is_empty()).then_some(Self { partials })
+    }
+
+    /// Creates a harmonic [`Spectrum`] where the amplitude of each partial is 0.88 times the amplitude of the preceding partial.
+    pub fn harmonic(num_partials: u16) -> Self {
+        Self {
+            partials: (1..=num_partials.max(1))
+                .map(|harmonic| Partial {
+                    ratio: Ratio::from_float(f64::from(harmonic)),
+                    amplitude: 0.88f64.powi(i32::from(harmonic) - 1),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn partials(&self) -> &[Partial] {
+        &self.partials
+    }
+
+    /// Calculates the sensory dissonance of two tones with this spectrum played at the given interval.
+    ///
+    /// The dissonance is the sum of the roughness of all pairs of partials using the parametrization by Sethares.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::consonance::Spectrum;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// let spectrum = Spectrum::harmonic(7);
+    /// let fundamental = Pitch::from_hz(261.626);
+    ///
+    /// let unison = spectrum.dissonance_of(fundamental, Ratio::default());
+    /// let minor_second = spectrum.dissonance_of(fundamental, Ratio::from_semitones(1));
+    /// let fifth = spectrum.dissonance_of(fundamental, Ratio::from_float(1.5));
+    /// let tritone = spectrum.dissonance_of(fundamental, Ratio::from_semitones(6));
+    ///
+    /// assert!(unison < fifth);
+    /// assert!(fifth < tritone);
+    /// assert!(tritone < minor_second);
+    /// ```
+    pub fn dissonance_of(&self, fundamental: Pitch, interval: Ratio) -> f64 {
+        let lower_tone = self.partials.iter().map(|partial| {
+            (
+                (fundamental * partial.ratio).as_hz(),
+                partial.amplitude.abs(),
+            )
+        });
+        let upper_tone = self.partials.iter().map(|partial| {
+            (
+                (fundamental * interval * partial.ratio).as_hz(),
+                partial.amplitude.abs(),
+            )
+        });
+        let all_partials: Vec<_> = lower_tone.chain(upper_tone).collect();
+
+        let mut dissonance = 0.0;
+        for (index, &partial) in all_partials.iter().enumerate() {
+            for &other_partial in &all_partials[index + 1..] {
+                dissonance += roughness(partial, other_partial);
+            }
+        }
+        dissonance
+    }
+
+    /// Finds the local minima of the dissonance curve of this spectrum between the unison and `period` (exclusive).
+    ///
+    /// The curve is sampled in steps of 1 cent and each minimum is refined to a precision of less than 0.001 cents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::consonance::Spectrum;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// let spectrum = Spectrum::harmonic(7);
+    /// let minima = spectrum.dissonance_minima(Pitch::from_hz(261.626), Ratio::octave());
+    ///
+    /// // 7/6, 6/5, 5/4, 4/3, 7/5, 3/2, 5/3 and 7/4
+    /// let minima_in_cents: Vec<_> = minima.iter().map(|minimum| minimum.as_cents().round()).collect();
+    /// assert_eq!(minima_in_cents, [267.0, 316.0, 386.0, 498.0, 583.0, 702.0, 884.0, 969.0]);
+    /// ```
+    pub fn dissonance_minima(&self, fundamental: Pitch, period: Ratio) -> Vec<Ratio> {
+        let dissonance_at = |cents: f64| self.dissonance_of(fundamental, Ratio::from_cents(cents));
+
+        let num_samples = period.abs().as_cents().ceil() as u32;
+        let samples: Vec<_> = (0..num_samples)
+            .map(|cents| dissonance_at(f64::from(cents)))
+            .collect();
+
+        let mut minima = Vec::new();
+        for (cents, window) in (1..).zip(samples.windows(3)) {
+            if window[1] < window[0] && window[1] <= window[2] {
+                let (mut lower, mut upper) = (f64::from(cents) - 1.0, f64::from(cents) + 1.0);
+                while upper - lower > 1e-4 {
+                    let lower_third = lower + (upper - lower) / 3.0;
+                    let upper_third = upper - (upper - lower) / 3.0;
+                    if dissonance_at(lower_third) < dissonance_at(upper_third) {
+                        upper = upper_third;
+                    } else {
+                        lower = lower_third;
+                    }
+                }
+                minima.push(Ratio::from_cents((lower + upper) / 2.0));
+            }
+        }
+        minima
+    }
+}
+
+fn roughness((freq_a, amp_a): (f64, f64), (freq_b, amp_b): (f64, f64)) -> f64 {
+    const D_STAR: f64 = 0.24;
+    const S1: f64 = 0.0207;
+    const S2: f64 = 18.96;
+    const B1: f64 = 3.51;
+    const B2: f64 = 5.75;
+
+    let scale = D_STAR / (S1 * freq_a.min(freq_b) + S2);
+    let distance = scale * (freq_a - freq_b).abs();
+
+    amp_a.min(amp_b) * ((-B1 * distance).exp() - (-B2 * distance).exp())
+}
+
+impl Display for Spectrum {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let partials: Vec<_> = self
+            .partials
+            .iter()
+            .map(|partial| format!("{:.4}@{}", partial.ratio.as_float(), partial.amplitude))
+            .collect();
+        write!(f, "{}", partials.join(","))
+    }
+}
+
+impl FromStr for Spectrum {
+    type Err = String;
+
+    /// Parses a comma-separated list of partials given as `<ratio>[@<amplitude>]`, e.g. `1,2.76@0.5`.
+    /// The amplitude defaults to 1.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let partials = s
+            .split(',')
+            .map(|partial| {
+                let (ratio, amplitude) = match partial.split_once('@') {
+                    Some((ratio, amplitude)) => (
+                        ratio,
+                        amplitude
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("Invalid amplitude '{amplitude}'"))?,
+                    ),
+                    None => (partial, 1.0),
+                };
+                Ok(Partial {
+                    ratio: ratio
+                        .parse()
+                        .map_err(|err| format!("Invalid partial '{ratio}': {err}"))?,
+                    amplitude,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Spectrum::from_partials(partials)
+            .ok_or_else(|| "Must contain at least one partial".to_owned())
+    }
+}
+
+/// The score of a specific interval of a scale created by [`score_scl`].
+#[derive(Copy, Clone, Debug)]
+pub struct IntervalScore {
+    /// The size of the interval.
+    pub size: Ratio,
+
+    /// The number of occurrences within a period.
+    pub count: u16,
+
+    /// The score calculated by the measure passed to [`score_scl`].
+    pub score: f64,
+}
+
+/// Scores every specific interval of the given [`Scl`] by the given measure, e.g. [`HarmonicEntropy::of`].
+///
+/// The intervals are taken from [`SclAnalysis::interval_vector`](crate::scala::SclAnalysis::interval_vector) and are sorted in ascending order.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::consonance::{self, HarmonicEntropy};
+/// # use tune::pitch::Ratio;
+/// # use tune::scala;
+/// let pythagorean_major =
+///     scala::create_rank2_temperament_scale(None, Ratio::from_float(1.5), 5, 1, Ratio::octave())
+///         .unwrap();
+///
+/// let harmonic_entropy = HarmonicEntropy::new(1000, Ratio::from_cents(17.0)).unwrap();
+/// let scores = consonance::score_scl(&pythagorean_major, |interval| harmonic_entropy.of(interval));
+///
+/// assert_eq!(scores.len(), 12);
+///
+/// // The fifth (6x) is more consonant than the diminished fifth (1x)
+/// assert_approx_eq!(scores[5].size.as_cents(), 588.269995);
+/// assert_eq!(scores[5].count, 1);
+/// assert_approx_eq!(scores[7].size.as_cents(), 701.955001, 1e-5);
+/// assert_eq!(scores[7].count, 6);
+/// assert!(scores[7].score < scores[5].score);
+/// ```
+pub fn score_scl(scl: &Scl, mut measure: impl FnMut(Ratio) -> f64) -> Vec<IntervalScore> {
+    scl.analyze()
+        .interval_vector()
+        .into_iter()
+        .map(|interval| IntervalScore {
+            size: interval.size,
+            count: interval.count,
+            score: measure(interval.size),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_minimum_near(curve: impl Fn(Ratio) -> f64, cents: f64) -> f64 {
+        (-30..=30)
+            .map(|offset| cents + f64::from(offset))
+            .min_by(|&a, &b| curve(Ratio::from_cents(a)).total_cmp(&curve(Ratio::from_cents(b))))
+            .unwrap()
+    }
+
+    #[test]
+    fn harmonic_entropy_minima_at_simple_fractions() {
+        let harmonic_entropy = HarmonicEntropy::new(10000, Ratio::from_cents(17.0)).unwrap();
+
+        for (interval, expected_minimum) in [(1.5, 702.0), (4.0 / 3.0, 498.0), (2.0, 1200.0)] {
+            let minimum = local_minimum_near(
+                |interval| harmonic_entropy.of(interval),
+                Ratio::from_float(interval).as_cents().round(),
+            );
+            assert!((minimum - expected_minimum).abs() <= 2.0, "{minimum}");
+        }
+
+        assert!(
+            (harmonic_entropy.of(Ratio::from_cents(-702.0))
+                - harmonic_entropy.of(Ratio::from_cents(702.0)))
+            .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn sethares_minima_at_harmonic_intervals() {
+        let spectrum = Spectrum::harmonic(7);
+        let fundamental = Pitch::from_hz(261.626);
+
+        for (interval, expected_minimum) in [(1.5, 702.0), (5.0 / 4.0, 386.0), (2.0, 1200.0)] {
+            let minimum = local_minimum_near(
+                |interval| spectrum.dissonance_of(fundamental, interval),
+                Ratio::from_float(interval).as_cents().round() + 10.0,
+            );
+            assert!((minimum - expected_minimum).abs() <= 1.0, "{minimum}");
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 mod parse;
 
+pub mod chord;
+pub mod consonance;
 pub mod key;
 pub mod layout;
 pub mod math;
@@ -11,6 +13,7 @@ pub mod note;
 pub mod pergen;
 pub mod pitch;
 pub mod scala;
+pub mod smf;
 pub mod temperament;
 pub mod tuner;
 pub mod tuning;
@@ -2,6 +2,12 @@
 //!
 //! References:
 //! - [MIDI messages](https://www.midi.org/specifications-old/item/table-1-summary-of-midi-message)
+//! - [Universal MIDI Packet (UMP) Format and MIDI 2.0 Protocol](https://midi.org/universal-midi-packet-ump-and-midi-2-0-protocol-specification)
+
+use crate::{
+    note::Note,
+    pitch::{Pitch, Pitched, Ratio},
+};
 
 /// Status bits for "Note Off event".
 pub const NOTE_OFF: u8 = 0b1000;
@@ -18,6 +24,26 @@ pub const CHANNEL_PRESSURE: u8 = 0b1101;
 /// Status bits for "Channel Pressure (After-touch)".
 pub const PITCH_BEND_CHANGE: u8 = 0b1110;
 
+/// Status bits for MIDI 2.0 "Registered Per-Note Controller".
+pub const REGISTERED_PER_NOTE_CONTROLLER: u8 = 0b0000;
+/// Status bits for MIDI 2.0 "Assignable Per-Note Controller".
+pub const ASSIGNABLE_PER_NOTE_CONTROLLER: u8 = 0b0001;
+/// Status bits for MIDI 2.0 "Registered Controller (RPN)".
+pub const REGISTERED_CONTROLLER: u8 = 0b0010;
+/// Status bits for MIDI 2.0 "Assignable Controller (NRPN)".
+pub const ASSIGNABLE_CONTROLLER: u8 = 0b0011;
+/// Status bits for MIDI 2.0 "Per-Note Pitch Bend".
+pub const PER_NOTE_PITCH_BEND: u8 = 0b0110;
+/// Status bits for MIDI 2.0 "Per-Note Management".
+pub const PER_NOTE_MANAGEMENT: u8 = 0b1111;
+
+/// Registered Per-Note Controller index for "Pitch 7.25".
+pub const PITCH_7_25: u8 = 0x03;
+/// Note On/Off attribute type for "Pitch 7.9".
+pub const ATTRIBUTE_PITCH_7_9: u8 = 0x03;
+
+const UMP_MIDI_2_CHANNEL_VOICE: u8 = 0x4;
+
 /// A type-safe representation of MIDI messages that aren't System Common messages.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct ChannelMessage {
@@ -176,6 +202,85 @@ pub enum ChannelMessageType {
 }
 
 impl ChannelMessageType {
+    /// Translates the MIDI 1.0 message into its MIDI 2.0 counterpart.
+    ///
+    /// All values are upscaled according to the *Min-Center-Max* rule of the MIDI 2.0 specification.
+    /// A Note On message with velocity 0 is translated into a Note Off message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// assert_eq!(
+    ///     ChannelMessageType::NoteOn { key: 77, velocity: 127 }.to_midi_2(),
+    ///     Midi2ChannelMessageType::NoteOn {
+    ///         key: 77,
+    ///         velocity: 0xffff,
+    ///         attribute_type: 0,
+    ///         attribute: 0
+    ///     }
+    /// );
+    /// assert_eq!(
+    ///     ChannelMessageType::ControlChange { controller: 7, value: 64 }.to_midi_2(),
+    ///     Midi2ChannelMessageType::ControlChange { controller: 7, value: 0x8000_0000 }
+    /// );
+    /// assert_eq!(
+    ///     ChannelMessageType::PitchBendChange { value: -8192 }.to_midi_2(),
+    ///     Midi2ChannelMessageType::PitchBendChange { value: i32::MIN }
+    /// );
+    /// ```
+    pub fn to_midi_2(self) -> Midi2ChannelMessageType {
+        match self {
+            ChannelMessageType::NoteOff { key, velocity } => Midi2ChannelMessageType::NoteOff {
+                key,
+                velocity: upscale_velocity(velocity),
+                attribute_type: 0,
+                attribute: 0,
+            },
+            ChannelMessageType::NoteOn { key, velocity: 0 } => Midi2ChannelMessageType::NoteOff {
+                key,
+                velocity: upscale_velocity(64),
+                attribute_type: 0,
+                attribute: 0,
+            },
+            ChannelMessageType::NoteOn { key, velocity } => Midi2ChannelMessageType::NoteOn {
+                key,
+                velocity: upscale_velocity(velocity),
+                attribute_type: 0,
+                attribute: 0,
+            },
+            ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                Midi2ChannelMessageType::PolyphonicKeyPressure {
+                    key,
+                    pressure: upscale(pressure.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::ControlChange { controller, value } => {
+                Midi2ChannelMessageType::ControlChange {
+                    controller,
+                    value: upscale(value.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::ProgramChange { program } => {
+                Midi2ChannelMessageType::ProgramChange {
+                    program,
+                    bank: None,
+                }
+            }
+            ChannelMessageType::ChannelPressure { pressure } => {
+                Midi2ChannelMessageType::ChannelPressure {
+                    pressure: upscale(pressure.into(), 7, 32),
+                }
+            }
+            ChannelMessageType::PitchBendChange { value } => {
+                Midi2ChannelMessageType::PitchBendChange {
+                    value: to_signed(upscale((i32::from(value) + 8192) as u32, 14, 32)),
+                }
+            }
+        }
+    }
+
     /// Creates a new [`ChannelMessage`] from `self` with the given `channel`.
     ///
     /// [`None`] is returned if the channel value is outside the range [0..16).
@@ -207,6 +312,449 @@ impl ChannelMessageType {
     }
 }
 
+/// A type-safe representation of MIDI 2.0 Channel Voice messages transmitted as 64-bit Universal MIDI Packets.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Midi2ChannelMessage {
+    group: u8,
+    channel: u8,
+    message_type: Midi2ChannelMessageType,
+}
+
+impl Midi2ChannelMessage {
+    /// Parses a Universal MIDI Packet.
+    ///
+    /// When no valid MIDI 2.0 Channel Voice message is provided [`None`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::Midi2ChannelMessage;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// let message = Midi2ChannelMessage::from_ump(&[0x4398_4d00, 0xc000_0000]).unwrap();
+    /// assert_eq!(message.group(), 3);
+    /// assert_eq!(message.channel(), 8);
+    /// assert_eq!(
+    ///     message.message_type(),
+    ///     Midi2ChannelMessageType::NoteOn {
+    ///         key: 77,
+    ///         velocity: 0xc000,
+    ///         attribute_type: 0,
+    ///         attribute: 0,
+    ///     }
+    /// );
+    ///
+    /// // MIDI 1.0 Channel Voice message
+    /// let invalid_message = [0x2398_4d58];
+    /// assert_eq!(Midi2ChannelMessage::from_ump(&invalid_message), None);
+    /// ```
+    pub fn from_ump(packet: &[u32]) -> Option<Midi2ChannelMessage> {
+        let [status_word, data_word] = [*packet.first()?, *packet.get(1)?];
+        let [message_type_and_group, status_and_channel, index_1, index_2] =
+            status_word.to_be_bytes();
+
+        if message_type_and_group >> 4 != UMP_MIDI_2_CHANNEL_VOICE {
+            return None;
+        }
+
+        let group = message_type_and_group & 0b0000_1111;
+        let channel = status_and_channel & 0b0000_1111;
+        let action = status_and_channel >> 4;
+        let message_type = match action {
+            REGISTERED_PER_NOTE_CONTROLLER => {
+                Midi2ChannelMessageType::RegisteredPerNoteController {
+                    key: index_1,
+                    index: index_2,
+                    value: data_word,
+                }
+            }
+            ASSIGNABLE_PER_NOTE_CONTROLLER => {
+                Midi2ChannelMessageType::AssignablePerNoteController {
+                    key: index_1,
+                    index: index_2,
+                    value: data_word,
+                }
+            }
+            REGISTERED_CONTROLLER => Midi2ChannelMessageType::RegisteredController {
+                bank: index_1,
+                index: index_2,
+                value: data_word,
+            },
+            ASSIGNABLE_CONTROLLER => Midi2ChannelMessageType::AssignableController {
+                bank: index_1,
+                index: index_2,
+                value: data_word,
+            },
+            PER_NOTE_PITCH_BEND => Midi2ChannelMessageType::PerNotePitchBend {
+                key: index_1,
+                value: to_signed(data_word),
+            },
+            NOTE_OFF => Midi2ChannelMessageType::NoteOff {
+                key: index_1,
+                velocity: (data_word >> 16) as u16,
+                attribute_type: index_2,
+                attribute: data_word as u16,
+            },
+            NOTE_ON => Midi2ChannelMessageType::NoteOn {
+                key: index_1,
+                velocity: (data_word >> 16) as u16,
+                attribute_type: index_2,
+                attribute: data_word as u16,
+            },
+            POLYPHONIC_KEY_PRESSURE => Midi2ChannelMessageType::PolyphonicKeyPressure {
+                key: index_1,
+                pressure: data_word,
+            },
+            CONTROL_CHANGE => Midi2ChannelMessageType::ControlChange {
+                controller: index_1,
+                value: data_word,
+            },
+            PROGRAM_CHANGE => {
+                let [program, _, bank_msb, bank_lsb] = data_word.to_be_bytes();
+                Midi2ChannelMessageType::ProgramChange {
+                    program,
+                    bank: (index_2 & 0b0000_0001 != 0).then_some((bank_msb, bank_lsb)),
+                }
+            }
+            CHANNEL_PRESSURE => Midi2ChannelMessageType::ChannelPressure {
+                pressure: data_word,
+            },
+            PITCH_BEND_CHANGE => Midi2ChannelMessageType::PitchBendChange {
+                value: to_signed(data_word),
+            },
+            PER_NOTE_MANAGEMENT => Midi2ChannelMessageType::PerNoteManagement {
+                key: index_1,
+                detach: index_2 & 0b0000_0010 != 0,
+                reset: index_2 & 0b0000_0001 != 0,
+            },
+            _ => return None,
+        };
+
+        Some(Midi2ChannelMessage {
+            group,
+            channel,
+            message_type,
+        })
+    }
+
+    /// Returns the Universal MIDI Packet representation of a MIDI 2.0 message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// let message = Midi2ChannelMessageType::PerNotePitchBend {
+    ///         key: 77,
+    ///         value: 0x1000_0000,
+    ///     }
+    ///     .in_channel(2, 7)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(message.to_ump(), [0x4267_4d00, 0x9000_0000]);
+    /// ```
+    pub fn to_ump(&self) -> [u32; 2] {
+        let (status, index_1, index_2, data_word) = match self.message_type {
+            Midi2ChannelMessageType::RegisteredPerNoteController { key, index, value } => {
+                (REGISTERED_PER_NOTE_CONTROLLER, key, index, value)
+            }
+            Midi2ChannelMessageType::AssignablePerNoteController { key, index, value } => {
+                (ASSIGNABLE_PER_NOTE_CONTROLLER, key, index, value)
+            }
+            Midi2ChannelMessageType::RegisteredController { bank, index, value } => {
+                (REGISTERED_CONTROLLER, bank, index, value)
+            }
+            Midi2ChannelMessageType::AssignableController { bank, index, value } => {
+                (ASSIGNABLE_CONTROLLER, bank, index, value)
+            }
+            Midi2ChannelMessageType::PerNotePitchBend { key, value } => {
+                (PER_NOTE_PITCH_BEND, key, 0, to_unsigned(value))
+            }
+            Midi2ChannelMessageType::NoteOff {
+                key,
+                velocity,
+                attribute_type,
+                attribute,
+            } => (
+                NOTE_OFF,
+                key,
+                attribute_type,
+                u32::from(velocity) << 16 | u32::from(attribute),
+            ),
+            Midi2ChannelMessageType::NoteOn {
+                key,
+                velocity,
+                attribute_type,
+                attribute,
+            } => (
+                NOTE_ON,
+                key,
+                attribute_type,
+                u32::from(velocity) << 16 | u32::from(attribute),
+            ),
+            Midi2ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                (POLYPHONIC_KEY_PRESSURE, key, 0, pressure)
+            }
+            Midi2ChannelMessageType::ControlChange { controller, value } => {
+                (CONTROL_CHANGE, controller, 0, value)
+            }
+            Midi2ChannelMessageType::ProgramChange { program, bank } => {
+                let (bank_msb, bank_lsb) = bank.unwrap_or_default();
+                (
+                    PROGRAM_CHANGE,
+                    0,
+                    u8::from(bank.is_some()),
+                    u32::from_be_bytes([program, 0, bank_msb, bank_lsb]),
+                )
+            }
+            Midi2ChannelMessageType::ChannelPressure { pressure } => {
+                (CHANNEL_PRESSURE, 0, 0, pressure)
+            }
+            Midi2ChannelMessageType::PitchBendChange { value } => {
+                (PITCH_BEND_CHANGE, 0, 0, to_unsigned(value))
+            }
+            Midi2ChannelMessageType::PerNoteManagement { key, detach, reset } => (
+                PER_NOTE_MANAGEMENT,
+                key,
+                u8::from(detach) << 1 | u8::from(reset),
+                0,
+            ),
+        };
+
+        [
+            u32::from_be_bytes([
+                UMP_MIDI_2_CHANNEL_VOICE << 4 | self.group,
+                status << 4 | self.channel,
+                index_1,
+                index_2,
+            ]),
+            data_word,
+        ]
+    }
+
+    /// Returns the group of a MIDI 2.0 message.
+    pub fn group(&self) -> u8 {
+        self.group
+    }
+
+    /// Returns the channel of a MIDI 2.0 message.
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Returns the group- and channel-agnostic part of a MIDI 2.0 message.
+    pub fn message_type(&self) -> Midi2ChannelMessageType {
+        self.message_type
+    }
+}
+
+/// A parsed representation of the group- and channel-agnostic part of a MIDI 2.0 message.
+///
+/// Bipolar values, i.e. pitch bends, are represented as signed integers centered around 0.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Midi2ChannelMessageType {
+    RegisteredPerNoteController {
+        key: u8,
+        index: u8,
+        value: u32,
+    },
+    AssignablePerNoteController {
+        key: u8,
+        index: u8,
+        value: u32,
+    },
+    RegisteredController {
+        bank: u8,
+        index: u8,
+        value: u32,
+    },
+    AssignableController {
+        bank: u8,
+        index: u8,
+        value: u32,
+    },
+    PerNotePitchBend {
+        key: u8,
+        value: i32,
+    },
+    NoteOff {
+        key: u8,
+        velocity: u16,
+        attribute_type: u8,
+        attribute: u16,
+    },
+    NoteOn {
+        key: u8,
+        velocity: u16,
+        attribute_type: u8,
+        attribute: u16,
+    },
+    PolyphonicKeyPressure {
+        key: u8,
+        pressure: u32,
+    },
+    ControlChange {
+        controller: u8,
+        value: u32,
+    },
+    ProgramChange {
+        program: u8,
+        bank: Option<(u8, u8)>,
+    },
+    ChannelPressure {
+        pressure: u32,
+    },
+    PitchBendChange {
+        value: i32,
+    },
+    PerNoteManagement {
+        key: u8,
+        detach: bool,
+        reset: bool,
+    },
+}
+
+impl Midi2ChannelMessageType {
+    /// Creates a *Registered Per-Note Controller* message that sets the absolute pitch of the given `key`.
+    ///
+    /// [`None`] is returned if the `pitch` is outside the range of MIDI notes [0..128).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Pitched;
+    /// # use tune::pitch::Ratio;
+    /// let quarter_tone_above_a4 = NoteLetter::A.in_octave(4).pitch() * Ratio::from_cents(50.0);
+    ///
+    /// assert_eq!(
+    ///     Midi2ChannelMessageType::per_note_pitch(60, quarter_tone_above_a4),
+    ///     Some(Midi2ChannelMessageType::RegisteredPerNoteController {
+    ///         key: 60,
+    ///         index: 3,
+    ///         value: 69 << 25 | 1 << 24,
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(Midi2ChannelMessageType::per_note_pitch(60, Pitch::from_hz(1.0)), None);
+    /// ```
+    pub fn per_note_pitch(key: u8, pitch: Pitch) -> Option<Self> {
+        Some(Self::RegisteredPerNoteController {
+            key,
+            index: PITCH_7_25,
+            value: encode_fixed_point_pitch(pitch, 25)?,
+        })
+    }
+
+    /// Returns the absolute pitch of a *Registered Per-Note Controller* message with index "Pitch 7.25".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// # use tune::pitch::Pitch;
+    /// let message = Midi2ChannelMessageType::per_note_pitch(60, Pitch::from_hz(432.0)).unwrap();
+    /// assert_approx_eq!(message.as_per_note_pitch().unwrap().as_hz(), 432.0);
+    ///
+    /// let message = Midi2ChannelMessageType::ControlChange { controller: 7, value: 0 };
+    /// assert_eq!(message.as_per_note_pitch(), None);
+    /// ```
+    pub fn as_per_note_pitch(self) -> Option<Pitch> {
+        match self {
+            Self::RegisteredPerNoteController {
+                index: PITCH_7_25,
+                value,
+                ..
+            } => Some(decode_fixed_point_pitch(value, 25)),
+            _ => None,
+        }
+    }
+
+    /// Creates a new [`Midi2ChannelMessage`] from `self` with the given `group` and `channel`.
+    ///
+    /// [`None`] is returned if the group or channel value is outside the range [0..16).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::Midi2ChannelMessageType;
+    /// let message_type = Midi2ChannelMessageType::ChannelPressure { pressure: 0x1234_5678 };
+    /// let message = message_type.in_channel(3, 15).unwrap();
+    ///
+    /// assert_eq!(message.group(), 3);
+    /// assert_eq!(message.channel(), 15);
+    /// assert_eq!(message.message_type(), message_type);
+    ///
+    /// let group_out_of_range = message_type.in_channel(16, 15);
+    /// assert!(group_out_of_range.is_none());
+    /// ```
+    pub fn in_channel(self, group: u8, channel: u8) -> Option<Midi2ChannelMessage> {
+        match group < 16 && channel < 16 {
+            true => Some(Midi2ChannelMessage {
+                group,
+                channel,
+                message_type: self,
+            }),
+            false => None,
+        }
+    }
+}
+
+/// Encodes the given `pitch` as a fixed-point MIDI note number, e.g. in the "Pitch 7.9" or "Pitch 7.25" format.
+fn encode_fixed_point_pitch(pitch: Pitch, num_fraction_bits: u32) -> Option<u32> {
+    let semitones_above_note_0 =
+        Ratio::between_pitches(Note::from_midi_number(0), pitch).as_semitones();
+    let fraction_scale = f64::from(1u32 << num_fraction_bits);
+    let encoded_value = (semitones_above_note_0 * fraction_scale).round();
+
+    (0.0..128.0 * fraction_scale)
+        .contains(&encoded_value)
+        .then_some(encoded_value as u32)
+}
+
+fn decode_fixed_point_pitch(value: u32, num_fraction_bits: u32) -> Pitch {
+    Note::from_midi_number(0).pitch()
+        * Ratio::from_semitones(f64::from(value) / f64::from(1u32 << num_fraction_bits))
+}
+
+fn upscale_velocity(velocity: u8) -> u16 {
+    upscale(velocity.into(), 7, 16) as u16
+}
+
+/// Upscales a value of `src_bits` to `dst_bits` using the *Min-Center-Max* algorithm of the MIDI 2.0 specification.
+fn upscale(src_value: u32, src_bits: u32, dst_bits: u32) -> u32 {
+    let scale_bits = dst_bits - src_bits;
+    let mut bit_shifted_value = u64::from(src_value) << scale_bits;
+    let src_center = 1 << (src_bits - 1);
+    if src_value <= src_center {
+        return bit_shifted_value as u32;
+    }
+
+    let repeat_bits = src_bits - 1;
+    let repeat_mask = (1 << repeat_bits) - 1;
+    let mut repeat_value = u64::from(src_value & repeat_mask);
+    if scale_bits > repeat_bits {
+        repeat_value <<= scale_bits - repeat_bits;
+    } else {
+        repeat_value >>= repeat_bits - scale_bits;
+    }
+    while repeat_value != 0 {
+        bit_shifted_value |= repeat_value;
+        repeat_value >>= repeat_bits;
+    }
+
+    bit_shifted_value as u32
+}
+
+fn to_signed(value: u32) -> i32 {
+    (value ^ 0x8000_0000) as i32
+}
+
+fn to_unsigned(value: i32) -> u32 {
+    value as u32 ^ 0x8000_0000
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,4 +930,137 @@ mod tests {
         };
         assert_eq!(message.to_raw_message(), [0b1110_1101, 22, 33]);
     }
+
+    #[test]
+    fn ump_roundtrip() {
+        let message_types = [
+            Midi2ChannelMessageType::RegisteredPerNoteController {
+                key: 11,
+                index: 22,
+                value: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::AssignablePerNoteController {
+                key: 11,
+                index: 22,
+                value: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::RegisteredController {
+                bank: 11,
+                index: 22,
+                value: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::AssignableController {
+                bank: 11,
+                index: 22,
+                value: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::PerNotePitchBend {
+                key: 11,
+                value: -0x1234_5678,
+            },
+            Midi2ChannelMessageType::NoteOff {
+                key: 11,
+                velocity: 0x1234,
+                attribute_type: ATTRIBUTE_PITCH_7_9,
+                attribute: 0x5678,
+            },
+            Midi2ChannelMessageType::NoteOn {
+                key: 11,
+                velocity: 0x1234,
+                attribute_type: ATTRIBUTE_PITCH_7_9,
+                attribute: 0x5678,
+            },
+            Midi2ChannelMessageType::PolyphonicKeyPressure {
+                key: 11,
+                pressure: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::ControlChange {
+                controller: 11,
+                value: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::ProgramChange {
+                program: 11,
+                bank: None,
+            },
+            Midi2ChannelMessageType::ProgramChange {
+                program: 11,
+                bank: Some((22, 33)),
+            },
+            Midi2ChannelMessageType::ChannelPressure {
+                pressure: 0x1234_5678,
+            },
+            Midi2ChannelMessageType::PitchBendChange { value: 0x1234_5678 },
+            Midi2ChannelMessageType::PerNoteManagement {
+                key: 11,
+                detach: true,
+                reset: false,
+            },
+            Midi2ChannelMessageType::PerNoteManagement {
+                key: 11,
+                detach: false,
+                reset: true,
+            },
+        ];
+
+        for message_type in message_types {
+            let message = message_type.in_channel(14, 15).unwrap();
+            assert_eq!(
+                Midi2ChannelMessage::from_ump(&message.to_ump()),
+                Some(message)
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_ump() {
+        let message = Midi2ChannelMessageType::ProgramChange {
+            program: 11,
+            bank: Some((22, 33)),
+        }
+        .in_channel(4, 5)
+        .unwrap();
+        assert_eq!(message.to_ump(), [0x44c5_0001, 0x0b00_1621]);
+
+        let message = Midi2ChannelMessageType::PerNoteManagement {
+            key: 66,
+            detach: true,
+            reset: true,
+        }
+        .in_channel(4, 5)
+        .unwrap();
+        assert_eq!(message.to_ump(), [0x44f5_4203, 0x0000_0000]);
+    }
+
+    #[test]
+    fn upscale_min_center_max() {
+        assert_eq!(upscale(0, 7, 16), 0x0000);
+        assert_eq!(upscale(1, 7, 16), 0x0200);
+        assert_eq!(upscale(64, 7, 16), 0x8000);
+        assert_eq!(upscale(65, 7, 16), 0x8208);
+        assert_eq!(upscale(127, 7, 16), 0xffff);
+
+        assert_eq!(upscale(0, 7, 32), 0x0000_0000);
+        assert_eq!(upscale(64, 7, 32), 0x8000_0000);
+        assert_eq!(upscale(127, 7, 32), 0xffff_ffff);
+
+        assert_eq!(upscale(0, 14, 32), 0x0000_0000);
+        assert_eq!(upscale(8192, 14, 32), 0x8000_0000);
+        assert_eq!(upscale(16383, 14, 32), 0xffff_ffff);
+    }
+
+    #[test]
+    fn fixed_point_pitch_roundtrip() {
+        for midi_number in [0, 1, 60, 69, 127] {
+            let pitch = Note::from_midi_number(midi_number).pitch() * Ratio::from_cents(33.3);
+            let encoded = encode_fixed_point_pitch(pitch, 25).unwrap();
+            assert_eq!(encoded >> 25, u32::try_from(midi_number).unwrap());
+
+            let deviation = Ratio::between_pitches(pitch, decode_fixed_point_pitch(encoded, 25));
+            assert!(deviation.is_negligible());
+        }
+
+        let too_high = Note::from_midi_number(128).pitch();
+        assert_eq!(encode_fixed_point_pitch(too_high, 25), None);
+        assert_eq!(encode_fixed_point_pitch(too_high, 9), None);
+    }
 }
@@ -9,7 +9,7 @@ use std::{collections::HashSet, fmt::Debug, iter};
 use crate::{
     key::PianoKey,
     midi::{ChannelMessage, ChannelMessageType},
-    note::NoteLetter,
+    note::{Note, NoteLetter},
     pitch::{Pitch, Pitched, Ratio},
     tuning::KeyboardMapping,
 };
@@ -24,6 +24,8 @@ const SYSEX_RT: u8 = 0x7f;
 const SYSEX_END: u8 = 0xf7;
 
 // MIDI Tuning Standard
+// 08 00 Bulk Tuning Dump Request
+// 08 01 Bulk Tuning Dump
 // 08 02 Single Note Tuning Change
 // 08 07 Single Note Tuning Change with Bank Select
 // 08 08 Scale/Octave Tuning, 1 byte format
@@ -31,6 +33,8 @@ const SYSEX_END: u8 = 0xf7;
 
 const MIDI_TUNING_STANDARD: u8 = 0x08;
 
+const BULK_TUNING_DUMP_REQUEST: u8 = 0x00;
+const BULK_TUNING_DUMP: u8 = 0x01;
 const SINGLE_NOTE_TUNING_CHANGE: u8 = 0x02;
 const SINGLE_NOTE_TUNING_CHANGE_WITH_BANK_SELECT: u8 = 0x07;
 const SCALE_OCTAVE_TUNING_1_BYTE_FORMAT: u8 = 0x08;
@@ -38,6 +42,9 @@ const SCALE_OCTAVE_TUNING_2_BYTE_FORMAT: u8 = 0x09;
 
 const DEVICE_ID_BROADCAST: u8 = 0x7f;
 
+const NO_CHANGE: [u8; 3] = [0x7f, 0x7f, 0x7f];
+const BULK_TUNING_DUMP_NAME_LENGTH: usize = 16;
+
 const U7_MASK: u16 = (1 << 7) - 1;
 const U14_UPPER_BOUND_AS_F64: f64 = (1 << 14) as f64;
 
@@ -215,29 +222,12 @@ impl SingleNoteTuningChangeMessage {
         let mut out_of_range_notes = Vec::new();
 
         for tuning_change in tuning_changes {
-            let approximation = tuning_change.target_pitch.find_in_tuning(());
-            let mut target_note = approximation.approx_value;
-
-            let mut detune_in_u14_resolution =
-                (approximation.deviation.as_semitones() * U14_UPPER_BOUND_AS_F64).round();
-
-            // Make sure that the detune range is [0c..100c] instead of [-50c..50c]
-            if detune_in_u14_resolution < 0.0 {
-                target_note = target_note.plus_semitones(-1);
-                detune_in_u14_resolution += U14_UPPER_BOUND_AS_F64;
-            }
-
-            if let (Some(source), Some(target)) = (
+            if let (Some(source), Some(encoded_pitch)) = (
                 tuning_change.key.checked_midi_number(),
-                target_note.checked_midi_number(),
+                encode_pitch(tuning_change.target_pitch),
             ) {
-                let pitch_msb = (detune_in_u14_resolution as u16 >> 7) as u8;
-                let pitch_lsb = (detune_in_u14_resolution as u16 & U7_MASK) as u8;
-
                 sysex_tuning_list.push(source);
-                sysex_tuning_list.push(target);
-                sysex_tuning_list.push(pitch_msb);
-                sysex_tuning_list.push(pitch_lsb);
+                sysex_tuning_list.extend(encoded_pitch);
 
                 num_retuned_notes += 1;
             } else {
@@ -801,6 +791,740 @@ impl From<u8> for Channels {
     }
 }
 
+/// Properties of the generated *Bulk Tuning Dump* message.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::mts::BulkTuningDumpMessage;
+/// # use tune::mts::BulkTuningDumpOptions;
+/// # use tune::note::NoteLetter;
+/// # use tune::scala::KbmRoot;
+/// # use tune::scala::Scl;
+/// let scl = Scl::builder().push_cents(100.0).build().unwrap();
+/// let kbm = KbmRoot::from(NoteLetter::A.in_octave(4)).to_kbm();
+///
+/// let options = BulkTuningDumpOptions {
+///     device_id: 55,
+///     tuning_program: 66,
+///     name: "12-EDO".to_owned(),
+/// };
+///
+/// let tuning_message = BulkTuningDumpMessage::from_tuning(&options, (scl, kbm)).unwrap();
+/// let sysex_bytes = tuning_message.sysex_bytes();
+///
+/// assert_eq!(sysex_bytes.len(), 408);
+/// assert_eq!(
+///     sysex_bytes[..22],
+///     [0xf0, 0x7e, 55, 0x08, 0x01, 66,                 // Non-RT Bulk Tuning Dump, tuning program
+///      b'1', b'2', b'-', b'E', b'D', b'O', b' ', b' ', // Name (padded with spaces)
+///      b' ', b' ', b' ', b' ', b' ', b' ', b' ', b' ']
+/// );
+/// assert_eq!(sysex_bytes[22 + 3 * 69..][..3], [69, 0, 0]); // A4
+/// assert_eq!(sysex_bytes[406..], [0x62, 0xf7]);            // Checksum / Sysex end
+/// ```
+#[derive(Clone, Debug)]
+pub struct BulkTuningDumpOptions {
+    /// Specifies the device ID (defaults to broadcast/0x7f).
+    pub device_id: u8,
+
+    /// Specifies the tuning program to be affected (defaults to 0).
+    pub tuning_program: u8,
+
+    /// The name of the tuning consisting of at most 16 ASCII characters (defaults to an empty name).
+    pub name: String,
+}
+
+impl Default for BulkTuningDumpOptions {
+    fn default() -> Self {
+        Self {
+            device_id: DEVICE_ID_BROADCAST,
+            tuning_program: 0,
+            name: String::new(),
+        }
+    }
+}
+
+/// Retunes up to 128 MIDI notes at once using the *Bulk Tuning Dump* message format.
+#[derive(Clone, Debug)]
+pub struct BulkTuningDumpMessage {
+    sysex_call: Vec<u8>,
+    out_of_range_notes: Vec<SingleNoteTuningChange>,
+}
+
+impl BulkTuningDumpMessage {
+    /// Creates a [`BulkTuningDumpMessage`] from the provided `tuning`.
+    ///
+    /// Keys that are not mapped by the `tuning` are marked as unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_ratio(Ratio::octave().divided_into_equal_steps(7))
+    ///     .build()
+    ///     .unwrap();
+    /// let kbm = KbmRoot::from(NoteLetter::D.in_octave(4)).to_kbm();
+    ///
+    /// let tuning_message = BulkTuningDumpMessage::from_tuning(&Default::default(), (scl, kbm))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(tuning_message.sysex_bytes().len(), 408);
+    /// assert_eq!(tuning_message.out_of_range_notes().len(), 53);
+    /// ```
+    pub fn from_tuning(
+        options: &BulkTuningDumpOptions,
+        tuning: impl KeyboardMapping<PianoKey>,
+    ) -> Result<Self, BulkTuningDumpError> {
+        let tuning_changes = (0..128).map(PianoKey::from_midi_number).flat_map(|key| {
+            tuning
+                .maybe_pitch_of(key)
+                .map(|target_pitch| SingleNoteTuningChange { key, target_pitch })
+        });
+        Self::from_tuning_changes(options, tuning_changes)
+    }
+
+    /// Creates a [`BulkTuningDumpMessage`] from the provided `tuning_changes`.
+    ///
+    /// Keys that are not affected by any of the `tuning_changes` are marked as unchanged.
+    /// If a key is affected multiple times, the last tuning change wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::SingleNoteTuningChange;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// let key = NoteLetter::A.in_octave(4).as_piano_key();
+    ///
+    /// let good = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(445.0) };
+    /// let too_low = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(1.0) };
+    ///
+    /// let tuning_message = BulkTuningDumpMessage::from_tuning_changes(
+    ///     &Default::default(), [good, too_low]
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(tuning_message.sysex_bytes()[22 + 3 * 69..][..3], [69, 25, 5]);
+    /// assert_eq!(tuning_message.out_of_range_notes(), [too_low]);
+    /// ```
+    pub fn from_tuning_changes(
+        options: &BulkTuningDumpOptions,
+        tuning_changes: impl IntoIterator<Item = SingleNoteTuningChange>,
+    ) -> Result<Self, BulkTuningDumpError> {
+        if options.device_id >= 128 {
+            return Err(BulkTuningDumpError::DeviceIdOutOfRange);
+        }
+        if options.tuning_program >= 128 {
+            return Err(BulkTuningDumpError::TuningProgramOutOfRange);
+        }
+        if options.name.len() > BULK_TUNING_DUMP_NAME_LENGTH || !options.name.is_ascii() {
+            return Err(BulkTuningDumpError::InvalidName);
+        }
+
+        let mut encoded_pitches = [NO_CHANGE; 128];
+        let mut out_of_range_notes = Vec::new();
+
+        for tuning_change in tuning_changes {
+            match (
+                tuning_change.key.checked_midi_number(),
+                encode_pitch(tuning_change.target_pitch).filter(|&encoded| encoded != NO_CHANGE),
+            ) {
+                (Some(source), Some(encoded_pitch)) => {
+                    encoded_pitches[usize::from(source)] = encoded_pitch
+                }
+                _ => out_of_range_notes.push(tuning_change),
+            }
+        }
+
+        let mut sysex_call = Vec::with_capacity(408);
+
+        sysex_call.push(SYSEX_START);
+        sysex_call.push(SYSEX_NON_RT);
+        sysex_call.push(options.device_id);
+        sysex_call.push(MIDI_TUNING_STANDARD);
+        sysex_call.push(BULK_TUNING_DUMP);
+        sysex_call.push(options.tuning_program);
+        sysex_call.extend(format!("{:<16}", options.name).bytes());
+        sysex_call.extend(encoded_pitches.iter().flatten());
+        sysex_call.push(checksum(&sysex_call[1..]));
+        sysex_call.push(SYSEX_END);
+
+        Ok(BulkTuningDumpMessage {
+            sysex_call,
+            out_of_range_notes,
+        })
+    }
+
+    /// Returns the tuning message conforming to the MIDI tuning standard.
+    pub fn sysex_bytes(&self) -> &[u8] {
+        &self.sysex_call
+    }
+
+    /// Return notes whose target pitch is not representable by the tuning message.
+    pub fn out_of_range_notes(&self) -> &[SingleNoteTuningChange] {
+        &self.out_of_range_notes
+    }
+}
+
+/// Creating a [`BulkTuningDumpMessage`] or a [`BulkTuningDumpRequest`] failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BulkTuningDumpError {
+    /// The device ID is greater than 127.
+    DeviceIdOutOfRange,
+
+    /// The tuning program number is greater than 127.
+    TuningProgramOutOfRange,
+
+    /// The name is longer than 16 characters or contains non-ASCII characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpError;
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::BulkTuningDumpOptions;
+    /// let create_tuning_message_with_name = |name: &str| {
+    ///     let options = BulkTuningDumpOptions {
+    ///         name: name.to_owned(),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     BulkTuningDumpMessage::from_tuning_changes(&options, [])
+    /// };
+    ///
+    /// assert!(matches!(
+    ///     create_tuning_message_with_name("Sixteen chars :)"),
+    ///     Ok(_)
+    /// ));
+    /// assert!(matches!(
+    ///     create_tuning_message_with_name("Seventeen chars :("),
+    ///     Err(BulkTuningDumpError::InvalidName)
+    /// ));
+    /// assert!(matches!(
+    ///     create_tuning_message_with_name("Ünïcödé"),
+    ///     Err(BulkTuningDumpError::InvalidName)
+    /// ));
+    /// ```
+    InvalidName,
+}
+
+/// The content of an incoming *Bulk Tuning Dump* message.
+#[derive(Clone, Debug)]
+pub struct BulkTuningDump {
+    /// The device ID the message is addressed to.
+    pub device_id: u8,
+
+    /// The tuning program that is affected.
+    pub tuning_program: u8,
+
+    /// The name of the tuning with trailing spaces removed.
+    pub name: String,
+
+    /// The tuning changes of all keys that are not marked as unchanged.
+    pub tuning_changes: Vec<SingleNoteTuningChange>,
+}
+
+impl BulkTuningDump {
+    /// Parses a raw *Bulk Tuning Dump* SysEx message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::mts::BulkTuningDump;
+    /// # use tune::mts::BulkTuningDumpMessage;
+    /// # use tune::mts::BulkTuningDumpOptions;
+    /// # use tune::mts::MtsParseError;
+    /// # use tune::mts::SingleNoteTuningChange;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// let key = NoteLetter::A.in_octave(4).as_piano_key();
+    /// let tuning_change = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(445.0) };
+    ///
+    /// let options = BulkTuningDumpOptions {
+    ///     device_id: 55,
+    ///     tuning_program: 66,
+    ///     name: "Detuned A4".to_owned(),
+    /// };
+    ///
+    /// let tuning_message =
+    ///     BulkTuningDumpMessage::from_tuning_changes(&options, [tuning_change]).unwrap();
+    ///
+    /// let bulk_dump = BulkTuningDump::parse(tuning_message.sysex_bytes()).unwrap();
+    ///
+    /// assert_eq!(bulk_dump.device_id, 55);
+    /// assert_eq!(bulk_dump.tuning_program, 66);
+    /// assert_eq!(bulk_dump.name, "Detuned A4");
+    /// assert_eq!(bulk_dump.tuning_changes.len(), 1);
+    /// assert_eq!(bulk_dump.tuning_changes[0].key, key);
+    /// assert_approx_eq!(bulk_dump.tuning_changes[0].target_pitch.as_hz(), 445.0, 1e-3);
+    ///
+    /// // Corrupted data
+    /// let mut sysex_bytes = tuning_message.sysex_bytes().to_vec();
+    /// sysex_bytes[100] ^= 0x01;
+    ///
+    /// assert!(matches!(
+    ///     BulkTuningDump::parse(&sysex_bytes),
+    ///     Err(MtsParseError::ChecksumMismatch)
+    /// ));
+    /// ```
+    pub fn parse(sysex_bytes: &[u8]) -> Result<Self, MtsParseError> {
+        let payload = MtsPayload::parse(sysex_bytes)?;
+
+        if payload.realtime || payload.sub_id != BULK_TUNING_DUMP {
+            return Err(MtsParseError::UnexpectedMessageType);
+        }
+
+        Self::from_payload(&payload)
+    }
+
+    fn from_payload(payload: &MtsPayload) -> Result<Self, MtsParseError> {
+        let (tuning_program, rest) = payload
+            .data
+            .split_first()
+            .ok_or(MtsParseError::UnexpectedLength)?;
+
+        if rest.len() != BULK_TUNING_DUMP_NAME_LENGTH + 3 * 128 + 1 {
+            return Err(MtsParseError::UnexpectedLength);
+        }
+
+        let (rest, checksum_byte) = rest.split_at(rest.len() - 1);
+        if checksum(&payload.inner_bytes[..payload.inner_bytes.len() - 1]) != checksum_byte[0] {
+            return Err(MtsParseError::ChecksumMismatch);
+        }
+
+        let (name, encoded_pitches) = rest.split_at(BULK_TUNING_DUMP_NAME_LENGTH);
+
+        let tuning_changes = encoded_pitches
+            .chunks(3)
+            .zip(0..)
+            .filter(|&(encoded_pitch, _)| encoded_pitch != NO_CHANGE)
+            .map(|(encoded_pitch, midi_number)| SingleNoteTuningChange {
+                key: PianoKey::from_midi_number(midi_number),
+                target_pitch: decode_pitch(encoded_pitch),
+            })
+            .collect();
+
+        Ok(Self {
+            device_id: payload.device_id,
+            tuning_program: *tuning_program,
+            name: String::from_utf8_lossy(name).trim_end().to_owned(),
+            tuning_changes,
+        })
+    }
+}
+
+/// Requests a *Bulk Tuning Dump* of the given tuning program from a device.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::mts::BulkTuningDumpRequest;
+/// let request = BulkTuningDumpRequest {
+///     device_id: 55,
+///     tuning_program: 66,
+/// };
+///
+/// let sysex_bytes = request.to_sysex_bytes().unwrap();
+///
+/// assert_eq!(
+///     sysex_bytes,
+///     [0xf0, 0x7e, 55, 0x08, 0x00, // Non-RT Bulk Tuning Dump Request
+///      66,                         // Tuning program
+///      0xf7]                       // Sysex end
+/// );
+///
+/// let parsed_request = BulkTuningDumpRequest::parse(&sysex_bytes).unwrap();
+///
+/// assert_eq!(parsed_request, request);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BulkTuningDumpRequest {
+    /// The device ID the request is addressed to.
+    pub device_id: u8,
+
+    /// The tuning program to be dumped.
+    pub tuning_program: u8,
+}
+
+impl BulkTuningDumpRequest {
+    /// Returns the request message conforming to the MIDI tuning standard.
+    pub fn to_sysex_bytes(&self) -> Result<[u8; 7], BulkTuningDumpError> {
+        if self.device_id >= 128 {
+            return Err(BulkTuningDumpError::DeviceIdOutOfRange);
+        }
+        if self.tuning_program >= 128 {
+            return Err(BulkTuningDumpError::TuningProgramOutOfRange);
+        }
+
+        Ok([
+            SYSEX_START,
+            SYSEX_NON_RT,
+            self.device_id,
+            MIDI_TUNING_STANDARD,
+            BULK_TUNING_DUMP_REQUEST,
+            self.tuning_program,
+            SYSEX_END,
+        ])
+    }
+
+    /// Parses a raw *Bulk Tuning Dump Request* SysEx message.
+    pub fn parse(sysex_bytes: &[u8]) -> Result<Self, MtsParseError> {
+        let payload = MtsPayload::parse(sysex_bytes)?;
+
+        if payload.realtime || payload.sub_id != BULK_TUNING_DUMP_REQUEST {
+            return Err(MtsParseError::UnexpectedMessageType);
+        }
+
+        Self::from_payload(&payload)
+    }
+
+    fn from_payload(payload: &MtsPayload) -> Result<Self, MtsParseError> {
+        match payload.data {
+            &[tuning_program] => Ok(Self {
+                device_id: payload.device_id,
+                tuning_program,
+            }),
+            _ => Err(MtsParseError::UnexpectedLength),
+        }
+    }
+}
+
+/// A parsed MIDI Tuning Standard SysEx message.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::mts::MtsMessage;
+/// # use tune::mts::ScaleOctaveTuning;
+/// # use tune::mts::ScaleOctaveTuningMessage;
+/// # use tune::mts::SingleNoteTuningChange;
+/// # use tune::mts::SingleNoteTuningChangeMessage;
+/// # use tune::note::NoteLetter;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// // Single Note Tuning Change
+/// let key = NoteLetter::A.in_octave(4).as_piano_key();
+/// let tuning_change = SingleNoteTuningChange { key, target_pitch: Pitch::from_hz(445.0) };
+///
+/// let tuning_message =
+///     SingleNoteTuningChangeMessage::from_tuning_changes(&Default::default(), [tuning_change])
+///         .unwrap();
+///
+/// let sysex_bytes = tuning_message.sysex_bytes().next().unwrap();
+///
+/// match MtsMessage::parse(sysex_bytes).unwrap() {
+///     MtsMessage::SingleNoteTuningChange { tuning_changes, .. } => {
+///         assert_eq!(tuning_changes.len(), 1);
+///         assert_eq!(tuning_changes[0].key, key);
+///         assert_approx_eq!(tuning_changes[0].target_pitch.as_hz(), 445.0, 1e-3);
+///     }
+///     _ => unreachable!(),
+/// }
+///
+/// // Scale/Octave Tuning
+/// let octave_tuning = ScaleOctaveTuning {
+///     d: Ratio::from_cents(-20.0),
+///     ..Default::default()
+/// };
+///
+/// let tuning_message =
+///     ScaleOctaveTuningMessage::from_octave_tuning(&Default::default(), &octave_tuning)
+///         .unwrap();
+///
+/// match MtsMessage::parse(tuning_message.sysex_bytes()).unwrap() {
+///     MtsMessage::ScaleOctaveTuning { octave_tuning, .. } => {
+///         assert_approx_eq!(octave_tuning.c.as_cents(), 0.0);
+///         assert_approx_eq!(octave_tuning.d.as_cents(), -20.0);
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub enum MtsMessage {
+    /// A *Single Note Tuning Change* message with or without bank select.
+    SingleNoteTuningChange {
+        realtime: bool,
+        device_id: u8,
+        tuning_bank: Option<u8>,
+        tuning_program: u8,
+        /// Tuning changes marked as "no change" are omitted.
+        tuning_changes: Vec<SingleNoteTuningChange>,
+    },
+    /// A *Scale/Octave Tuning* message in 1-byte or 2-byte format.
+    ScaleOctaveTuning {
+        realtime: bool,
+        device_id: u8,
+        channels: Channels,
+        format: ScaleOctaveTuningFormat,
+        octave_tuning: ScaleOctaveTuning,
+    },
+    /// A *Bulk Tuning Dump* message.
+    BulkTuningDump(BulkTuningDump),
+    /// A *Bulk Tuning Dump Request* message.
+    BulkTuningDumpRequest(BulkTuningDumpRequest),
+}
+
+impl MtsMessage {
+    /// Parses any of the supported raw MIDI Tuning Standard SysEx messages.
+    pub fn parse(sysex_bytes: &[u8]) -> Result<Self, MtsParseError> {
+        let payload = MtsPayload::parse(sysex_bytes)?;
+
+        match (payload.realtime, payload.sub_id) {
+            (_, SINGLE_NOTE_TUNING_CHANGE) => match *payload.data {
+                [tuning_program, ref tuning_list @ ..] => Self::parse_single_note_tuning_change(
+                    &payload,
+                    None,
+                    tuning_program,
+                    tuning_list,
+                ),
+                _ => Err(MtsParseError::UnexpectedLength),
+            },
+            (_, SINGLE_NOTE_TUNING_CHANGE_WITH_BANK_SELECT) => match *payload.data {
+                [tuning_bank, tuning_program, ref tuning_list @ ..] => {
+                    Self::parse_single_note_tuning_change(
+                        &payload,
+                        Some(tuning_bank),
+                        tuning_program,
+                        tuning_list,
+                    )
+                }
+                _ => Err(MtsParseError::UnexpectedLength),
+            },
+            (_, SCALE_OCTAVE_TUNING_1_BYTE_FORMAT) => {
+                Self::parse_scale_octave_tuning(&payload, ScaleOctaveTuningFormat::OneByte)
+            }
+            (_, SCALE_OCTAVE_TUNING_2_BYTE_FORMAT) => {
+                Self::parse_scale_octave_tuning(&payload, ScaleOctaveTuningFormat::TwoByte)
+            }
+            (false, BULK_TUNING_DUMP) => {
+                BulkTuningDump::from_payload(&payload).map(Self::BulkTuningDump)
+            }
+            (false, BULK_TUNING_DUMP_REQUEST) => {
+                BulkTuningDumpRequest::from_payload(&payload).map(Self::BulkTuningDumpRequest)
+            }
+            _ => Err(MtsParseError::UnexpectedMessageType),
+        }
+    }
+
+    fn parse_single_note_tuning_change(
+        payload: &MtsPayload,
+        tuning_bank: Option<u8>,
+        tuning_program: u8,
+        tuning_list: &[u8],
+    ) -> Result<Self, MtsParseError> {
+        let (num_changes, tuning_list) = tuning_list
+            .split_first()
+            .ok_or(MtsParseError::UnexpectedLength)?;
+
+        if tuning_list.len() != 4 * usize::from(*num_changes) {
+            return Err(MtsParseError::UnexpectedLength);
+        }
+
+        let tuning_changes = tuning_list
+            .chunks(4)
+            .filter(|tuning_change| tuning_change[1..] != NO_CHANGE)
+            .map(|tuning_change| SingleNoteTuningChange {
+                key: PianoKey::from_midi_number(tuning_change[0]),
+                target_pitch: decode_pitch(&tuning_change[1..]),
+            })
+            .collect();
+
+        Ok(Self::SingleNoteTuningChange {
+            realtime: payload.realtime,
+            device_id: payload.device_id,
+            tuning_bank,
+            tuning_program,
+            tuning_changes,
+        })
+    }
+
+    fn parse_scale_octave_tuning(
+        payload: &MtsPayload,
+        format: ScaleOctaveTuningFormat,
+    ) -> Result<Self, MtsParseError> {
+        let bytes_per_value = match format {
+            ScaleOctaveTuningFormat::OneByte => 1,
+            ScaleOctaveTuningFormat::TwoByte => 2,
+        };
+
+        if payload.data.len() != 3 + 12 * bytes_per_value {
+            return Err(MtsParseError::UnexpectedLength);
+        }
+
+        let (encoded_channels, encoded_values) = payload.data.split_at(3);
+
+        let channels: HashSet<_> = encoded_channels
+            .iter()
+            .rev()
+            .zip(0..)
+            .flat_map(|(&bits, row)| {
+                (0..7)
+                    .filter(move |bit_position| bits & 1 << bit_position != 0)
+                    .map(move |bit_position| 7 * row + bit_position)
+            })
+            .filter(|&channel| channel < 16)
+            .collect();
+
+        let mut pitch_bends = encoded_values
+            .chunks(bytes_per_value)
+            .map(|value| match format {
+                ScaleOctaveTuningFormat::OneByte => Ratio::from_cents(f64::from(value[0]) - 64.0),
+                ScaleOctaveTuningFormat::TwoByte => {
+                    let value = u16::from(value[0]) << 7 | u16::from(value[1]);
+                    Ratio::from_semitones(f64::from(value) / 8192.0 - 1.0)
+                }
+            });
+        let mut next_pitch_bend = || pitch_bends.next().unwrap();
+
+        Ok(Self::ScaleOctaveTuning {
+            realtime: payload.realtime,
+            device_id: payload.device_id,
+            channels: if channels.len() == 16 {
+                Channels::All
+            } else {
+                Channels::Some(channels)
+            },
+            format,
+            octave_tuning: ScaleOctaveTuning {
+                c: next_pitch_bend(),
+                csh: next_pitch_bend(),
+                d: next_pitch_bend(),
+                dsh: next_pitch_bend(),
+                e: next_pitch_bend(),
+                f: next_pitch_bend(),
+                fsh: next_pitch_bend(),
+                g: next_pitch_bend(),
+                gsh: next_pitch_bend(),
+                a: next_pitch_bend(),
+                ash: next_pitch_bend(),
+                b: next_pitch_bend(),
+            },
+        })
+    }
+}
+
+/// Parsing a raw MIDI Tuning Standard SysEx message failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MtsParseError {
+    /// The message does not start with 0xf0 or does not end with 0xf7.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpRequest;
+    /// # use tune::mts::MtsParseError;
+    /// assert_eq!(
+    ///     BulkTuningDumpRequest::parse(&[0xf0, 0x7e, 0x7f, 0x08, 0x00, 0]),
+    ///     Err(MtsParseError::InvalidFraming)
+    /// );
+    /// ```
+    InvalidFraming,
+
+    /// The message is not a Universal SysEx message of the MIDI Tuning Standard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpRequest;
+    /// # use tune::mts::MtsParseError;
+    /// // Sample Dump Request
+    /// assert_eq!(
+    ///     BulkTuningDumpRequest::parse(&[0xf0, 0x7e, 0x7f, 0x03, 0, 0, 0xf7]),
+    ///     Err(MtsParseError::NotAnMtsMessage)
+    /// );
+    /// ```
+    NotAnMtsMessage,
+
+    /// The message is an MTS message but not of the expected type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpRequest;
+    /// # use tune::mts::MtsParseError;
+    /// // Bulk Tuning Dump instead of Bulk Tuning Dump Request
+    /// assert_eq!(
+    ///     BulkTuningDumpRequest::parse(&[0xf0, 0x7e, 0x7f, 0x08, 0x01, 0, 0xf7]),
+    ///     Err(MtsParseError::UnexpectedMessageType)
+    /// );
+    /// ```
+    UnexpectedMessageType,
+
+    /// The message is too short or too long for its type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpRequest;
+    /// # use tune::mts::MtsParseError;
+    /// assert_eq!(
+    ///     BulkTuningDumpRequest::parse(&[0xf0, 0x7e, 0x7f, 0x08, 0x00, 0, 1, 0xf7]),
+    ///     Err(MtsParseError::UnexpectedLength)
+    /// );
+    /// ```
+    UnexpectedLength,
+
+    /// A data byte is greater than 127.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::mts::BulkTuningDumpRequest;
+    /// # use tune::mts::MtsParseError;
+    /// assert_eq!(
+    ///     BulkTuningDumpRequest::parse(&[0xf0, 0x7e, 0x7f, 0x08, 0x00, 128, 0xf7]),
+    ///     Err(MtsParseError::DataByteOutOfRange)
+    /// );
+    /// ```
+    DataByteOutOfRange,
+
+    /// The checksum of the message does not match its content.
+    ChecksumMismatch,
+}
+
+struct MtsPayload<'a> {
+    inner_bytes: &'a [u8],
+    realtime: bool,
+    device_id: u8,
+    sub_id: u8,
+    data: &'a [u8],
+}
+
+impl<'a> MtsPayload<'a> {
+    fn parse(sysex_bytes: &'a [u8]) -> Result<Self, MtsParseError> {
+        let inner_bytes = match sysex_bytes {
+            [SYSEX_START, inner_bytes @ .., SYSEX_END] => inner_bytes,
+            _ => return Err(MtsParseError::InvalidFraming),
+        };
+
+        if inner_bytes.iter().any(|&byte| byte >= 128) {
+            return Err(MtsParseError::DataByteOutOfRange);
+        }
+
+        match *inner_bytes {
+            [realtime @ (SYSEX_NON_RT | SYSEX_RT), device_id, MIDI_TUNING_STANDARD, sub_id, ref data @ ..] => {
+                Ok(Self {
+                    inner_bytes,
+                    realtime: realtime == SYSEX_RT,
+                    device_id,
+                    sub_id,
+                    data,
+                })
+            }
+            _ => Err(MtsParseError::NotAnMtsMessage),
+        }
+    }
+}
+
 pub fn channel_fine_tuning(channel: u8, detuning: Ratio) -> Option<[ChannelMessage; 4]> {
     const CHANNEL_FINE_TUNING_MSB: u8 = 0x00;
     const CHANNEL_FINE_TUNING_LSB: u8 = 0x01;
@@ -840,6 +1564,96 @@ pub fn tuning_bank_change(channel: u8, tuning_bank: u8) -> Option<[ChannelMessag
     )
 }
 
+/// Creates a *Pitch Bend Sensitivity* RPN message that sets the pitch-bend range of the given `channel` to `range`.
+///
+/// Returns [`None`] if the channel is out of range or `range` is not within [0, 128) semitones.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::mts;
+/// # use tune::pitch::Ratio;
+/// let messages = mts::pitch_bend_sensitivity(1, Ratio::from_semitones(48.5)).unwrap();
+///
+/// assert_eq!(
+///     messages.map(|message| message.message_type()),
+///     [
+///         ChannelMessageType::ControlChange { controller: 0x65, value: 0x00 },
+///         ChannelMessageType::ControlChange { controller: 0x64, value: 0x00 },
+///         ChannelMessageType::ControlChange { controller: 0x06, value: 48 },
+///         ChannelMessageType::ControlChange { controller: 0x26, value: 50 },
+///     ]
+/// );
+///
+/// assert!(mts::pitch_bend_sensitivity(1, Ratio::from_semitones(128)).is_none());
+/// ```
+pub fn pitch_bend_sensitivity(channel: u8, range: Ratio) -> Option<[ChannelMessage; 4]> {
+    const PITCH_BEND_SENSITIVITY_MSB: u8 = 0x00;
+    const PITCH_BEND_SENSITIVITY_LSB: u8 = 0x00;
+
+    let range_in_cents = range.as_cents().round();
+    if !(0.0..12800.0).contains(&range_in_cents) {
+        return None;
+    }
+    let range_in_cents = range_in_cents as u16;
+
+    rpn_message_2_byte(
+        channel,
+        PITCH_BEND_SENSITIVITY_MSB,
+        PITCH_BEND_SENSITIVITY_LSB,
+        (range_in_cents / 100) as u8,
+        (range_in_cents % 100) as u8,
+    )
+}
+
+/// Creates an *MPE Configuration Message* (MCM) that assigns `num_member_channels` member channels to the zone of the given `manager_channel`.
+///
+/// The manager channel of the lower zone is 0 and its member channels are counted upwards.
+/// The manager channel of the upper zone is 15 and its member channels are counted downwards.
+/// A value of 0 for `num_member_channels` disables the zone.
+///
+/// Returns [`None`] if the manager channel is not 0 or 15 or if more than 15 member channels are requested.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::mts;
+/// let messages = mts::mpe_configuration(0, 15).unwrap();
+///
+/// assert_eq!(messages[0].channel(), 0);
+/// assert_eq!(
+///     messages.map(|message| message.message_type()),
+///     [
+///         ChannelMessageType::ControlChange { controller: 0x65, value: 0x00 },
+///         ChannelMessageType::ControlChange { controller: 0x64, value: 0x06 },
+///         ChannelMessageType::ControlChange { controller: 0x06, value: 15 },
+///     ]
+/// );
+///
+/// assert!(mts::mpe_configuration(1, 15).is_none());
+/// assert!(mts::mpe_configuration(15, 16).is_none());
+/// ```
+pub fn mpe_configuration(
+    manager_channel: u8,
+    num_member_channels: u8,
+) -> Option<[ChannelMessage; 3]> {
+    const MPE_CONFIGURATION_MSB: u8 = 0x00;
+    const MPE_CONFIGURATION_LSB: u8 = 0x06;
+
+    if !matches!(manager_channel, 0 | 15) || num_member_channels > 15 {
+        return None;
+    }
+
+    rpn_message_1_byte(
+        manager_channel,
+        MPE_CONFIGURATION_MSB,
+        MPE_CONFIGURATION_LSB,
+        num_member_channels,
+    )
+}
+
 // RPN format reference: https://www.midi.org/specifications-old/item/table-3-control-change-messages-data-bytes-2
 
 const RPN_MSB: u8 = 0x65;
@@ -909,12 +1723,41 @@ fn ratio_to_u8s(ratio: Ratio) -> (u8, u8) {
     ((as_u16 / 128) as u8, (as_u16 % 128) as u8)
 }
 
+fn encode_pitch(pitch: Pitch) -> Option<[u8; 3]> {
+    let approximation = pitch.find_in_tuning(());
+    let mut target_note = approximation.approx_value;
+
+    let mut detune_in_u14_resolution =
+        (approximation.deviation.as_semitones() * U14_UPPER_BOUND_AS_F64).round();
+
+    // Make sure that the detune range is [0c..100c] instead of [-50c..50c]
+    if detune_in_u14_resolution < 0.0 {
+        target_note = target_note.plus_semitones(-1);
+        detune_in_u14_resolution += U14_UPPER_BOUND_AS_F64;
+    }
+
+    let pitch_msb = (detune_in_u14_resolution as u16 >> 7) as u8;
+    let pitch_lsb = (detune_in_u14_resolution as u16 & U7_MASK) as u8;
+
+    target_note
+        .checked_midi_number()
+        .map(|target| [target, pitch_msb, pitch_lsb])
+}
+
+fn decode_pitch(encoded_pitch: &[u8]) -> Pitch {
+    let detune_in_u14_resolution = u16::from(encoded_pitch[1]) << 7 | u16::from(encoded_pitch[2]);
+
+    Note::from_midi_number(encoded_pitch[0]).pitch()
+        * Ratio::from_semitones(f64::from(detune_in_u14_resolution) / U14_UPPER_BOUND_AS_F64)
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |checksum, byte| checksum ^ byte) & 0x7f
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{
-        note::Note,
-        scala::{KbmRoot, Scl},
-    };
+    use crate::scala::{KbmRoot, Scl};
 
     use super::*;
 
@@ -1184,4 +2027,207 @@ mod test {
         );
         assert_eq!(tuning_message.out_of_range_notes().len(), 4);
     }
+
+    #[test]
+    fn bulk_tuning_dump_roundtrip() {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::octave().divided_into_equal_steps(31))
+            .build()
+            .unwrap();
+        let kbm = KbmRoot::from(NoteLetter::D.in_octave(4)).to_kbm();
+        let tuning = (scl, kbm);
+
+        let options = BulkTuningDumpOptions {
+            device_id: 11,
+            tuning_program: 22,
+            name: "31-EDO".to_owned(),
+        };
+        let tuning_message = BulkTuningDumpMessage::from_tuning(&options, &tuning).unwrap();
+        assert_eq!(tuning_message.out_of_range_notes().len(), 0);
+
+        let bulk_dump = BulkTuningDump::parse(tuning_message.sysex_bytes()).unwrap();
+        assert_eq!(bulk_dump.device_id, 11);
+        assert_eq!(bulk_dump.tuning_program, 22);
+        assert_eq!(bulk_dump.name, "31-EDO");
+        assert_eq!(bulk_dump.tuning_changes.len(), 128);
+
+        for tuning_change in bulk_dump.tuning_changes {
+            let expected_pitch = tuning.maybe_pitch_of(tuning_change.key).unwrap();
+            let deviation = Ratio::between_pitches(expected_pitch, tuning_change.target_pitch);
+            assert!(deviation.as_cents().abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn parse_single_note_tuning_change() {
+        let scl = Scl::builder()
+            .push_ratio(Ratio::octave().divided_into_equal_steps(31))
+            .build()
+            .unwrap();
+        let kbm = KbmRoot::from(NoteLetter::D.in_octave(4)).to_kbm();
+        let tuning = (scl, kbm);
+
+        let options = SingleNoteTuningChangeOptions {
+            realtime: false,
+            device_id: 11,
+            tuning_program: 22,
+            with_bank_select: Some(33),
+        };
+        let tuning_message = SingleNoteTuningChangeMessage::from_tuning(
+            &options,
+            &tuning,
+            (0..128).map(PianoKey::from_midi_number),
+        )
+        .unwrap();
+
+        let mut num_parsed_changes = 0;
+        for sysex_bytes in tuning_message.sysex_bytes() {
+            let MtsMessage::SingleNoteTuningChange {
+                realtime,
+                device_id,
+                tuning_bank,
+                tuning_program,
+                tuning_changes,
+            } = MtsMessage::parse(sysex_bytes).unwrap()
+            else {
+                panic!("Unexpected message type");
+            };
+
+            assert!(!realtime);
+            assert_eq!(device_id, 11);
+            assert_eq!(tuning_bank, Some(33));
+            assert_eq!(tuning_program, 22);
+
+            for tuning_change in tuning_changes {
+                let expected_pitch = tuning.maybe_pitch_of(tuning_change.key).unwrap();
+                let deviation = Ratio::between_pitches(expected_pitch, tuning_change.target_pitch);
+                assert!(deviation.as_cents().abs() < 0.01);
+                num_parsed_changes += 1;
+            }
+        }
+        assert_eq!(num_parsed_changes, 128);
+    }
+
+    #[test]
+    fn parse_scale_octave_tuning() {
+        let octave_tuning = ScaleOctaveTuning {
+            c: Ratio::from_cents(-61.0),
+            csh: Ratio::from_cents(-50.0),
+            d: Ratio::from_cents(-39.0),
+            dsh: Ratio::from_cents(-28.0),
+            e: Ratio::from_cents(-17.0),
+            f: Ratio::from_cents(-6.0),
+            fsh: Ratio::from_cents(5.0),
+            g: Ratio::from_cents(16.0),
+            gsh: Ratio::from_cents(27.0),
+            a: Ratio::from_cents(38.0),
+            ash: Ratio::from_cents(49.0),
+            b: Ratio::from_cents(60.0),
+        };
+
+        for (format, tolerance) in [
+            (ScaleOctaveTuningFormat::OneByte, 0.5),
+            (ScaleOctaveTuningFormat::TwoByte, 0.01),
+        ] {
+            for channels in [vec![], vec![0], vec![7, 13, 14], vec![1, 3, 5, 15]] {
+                let options = ScaleOctaveTuningOptions {
+                    realtime: true,
+                    device_id: 77,
+                    channels: Channels::Some(channels.iter().copied().collect()),
+                    format,
+                };
+                let tuning_message =
+                    ScaleOctaveTuningMessage::from_octave_tuning(&options, &octave_tuning).unwrap();
+
+                let MtsMessage::ScaleOctaveTuning {
+                    realtime,
+                    device_id,
+                    channels: Channels::Some(parsed_channels),
+                    octave_tuning: parsed_octave_tuning,
+                    ..
+                } = MtsMessage::parse(tuning_message.sysex_bytes()).unwrap()
+                else {
+                    panic!("Unexpected message type");
+                };
+
+                assert!(realtime);
+                assert_eq!(device_id, 77);
+                assert_eq!(parsed_channels, channels.into_iter().collect());
+
+                for (parsed, expected) in [
+                    (parsed_octave_tuning.c, octave_tuning.c),
+                    (parsed_octave_tuning.fsh, octave_tuning.fsh),
+                    (parsed_octave_tuning.b, octave_tuning.b),
+                ] {
+                    assert!((parsed.as_cents() - expected.as_cents()).abs() < tolerance);
+                }
+            }
+        }
+
+        let tuning_message =
+            ScaleOctaveTuningMessage::from_octave_tuning(&Default::default(), &octave_tuning)
+                .unwrap();
+        assert!(matches!(
+            MtsMessage::parse(tuning_message.sysex_bytes()),
+            Ok(MtsMessage::ScaleOctaveTuning {
+                channels: Channels::All,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_malformed_messages() {
+        let test_cases: &[(&[u8], _)] = &[
+            (&[], MtsParseError::InvalidFraming),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x02, 0, 0],
+                MtsParseError::InvalidFraming,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x02, 0, 0xf8, 0xf7],
+                MtsParseError::DataByteOutOfRange,
+            ),
+            (
+                &[0xf0, 0x7d, 0x7f, 0x08, 0x02, 0, 0, 0xf7],
+                MtsParseError::NotAnMtsMessage,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x09, 0x02, 0, 0, 0xf7],
+                MtsParseError::NotAnMtsMessage,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x03, 0, 0, 0xf7],
+                MtsParseError::UnexpectedMessageType,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x01, 0, 0xf7],
+                MtsParseError::UnexpectedMessageType,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x02, 0xf7],
+                MtsParseError::UnexpectedLength,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x02, 0, 1, 69, 69, 0, 0xf7],
+                MtsParseError::UnexpectedLength,
+            ),
+            (
+                &[0xf0, 0x7f, 0x7f, 0x08, 0x07, 0, 0, 0xf7],
+                MtsParseError::UnexpectedLength,
+            ),
+            (
+                &[0xf0, 0x7e, 0x7f, 0x08, 0x08, 3, 127, 127, 64, 0xf7],
+                MtsParseError::UnexpectedLength,
+            ),
+        ];
+
+        for (sysex_bytes, expected_error) in test_cases {
+            assert_eq!(
+                MtsMessage::parse(sysex_bytes).unwrap_err(),
+                *expected_error,
+                "{sysex_bytes:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,302 @@
+use std::cmp::Ordering;
+
+use crate::pitch::Ratio;
+
+use super::Scl;
+
+pub(crate) fn analyze_scl(scl: &Scl) -> SclAnalysis {
+    let num_items = scl.num_items();
+
+    let interval_classes = (1..num_items)
+        .map(|num_steps| {
+            let mut specific_sizes: Vec<_> = (0..i32::from(num_items))
+                .map(|degree| {
+                    scl.sorted_relative_pitch_of(degree + i32::from(num_steps))
+                        .deviation_from(scl.sorted_relative_pitch_of(degree))
+                })
+                .collect();
+            specific_sizes.sort_by(Ratio::total_cmp);
+
+            IntervalClass {
+                num_steps,
+                sizes: count_sizes(specific_sizes),
+            }
+        })
+        .collect();
+
+    SclAnalysis { interval_classes }
+}
+
+fn count_sizes(sorted_sizes: impl IntoIterator<Item = Ratio>) -> Vec<IntervalSize> {
+    let mut counted_sizes = Vec::<IntervalSize>::new();
+    for size in sorted_sizes {
+        match counted_sizes.last_mut() {
+            Some(last) if size.deviation_from(last.size).is_negligible() => last.count += 1,
+            _ => counted_sizes.push(IntervalSize { size, count: 1 }),
+        }
+    }
+    counted_sizes
+}
+
+/// Structural properties of an [`Scl`] as reported by [`Scl::analyze`].
+///
+/// All properties are derived from the *sorted* pitches of the scale (see [`Scl::sorted_relative_pitch_of`]).
+/// Two intervals are considered equal if they differ by a negligible amount (see [`Ratio::is_negligible`]).
+#[derive(Clone, Debug)]
+pub struct SclAnalysis {
+    interval_classes: Vec<IntervalClass>,
+}
+
+impl SclAnalysis {
+    /// Returns the specific interval sizes for each generic interval from 1 to *n*-1 scale steps where *n* is the number of scale items.
+    pub fn interval_classes(&self) -> &[IntervalClass] {
+        &self.interval_classes
+    }
+
+    /// Returns the number of occurrences of each specific interval, regardless of its generic size.
+    ///
+    /// The resulting list is sorted in ascending order.
+    pub fn interval_vector(&self) -> Vec<IntervalSize> {
+        let mut all_sizes: Vec<_> = self
+            .interval_classes
+            .iter()
+            .flat_map(|interval_class| &interval_class.sizes)
+            .flat_map(|size| (0..size.count).map(move |_| size.size))
+            .collect();
+        all_sizes.sort_by(Ratio::total_cmp);
+
+        count_sizes(all_sizes)
+    }
+
+    /// Checks whether each specific interval is always spanned by the same number of scale steps.
+    pub fn is_constant_structure(&self) -> bool {
+        let mut sizes_and_classes: Vec<_> = self
+            .interval_classes
+            .iter()
+            .flat_map(|interval_class| {
+                interval_class
+                    .sizes
+                    .iter()
+                    .map(move |size| (size.size, interval_class.num_steps))
+            })
+            .collect();
+        sizes_and_classes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        sizes_and_classes.windows(2).all(|window| {
+            let (lower_size, lower_class) = window[0];
+            let (upper_size, upper_class) = window[1];
+            lower_class == upper_class || !upper_size.deviation_from(lower_size).is_negligible()
+        })
+    }
+
+    /// Checks whether the scale is proper in the sense of Rothenberg, i.e. no specific interval is larger than any interval spanning more scale steps.
+    pub fn is_proper(&self) -> bool {
+        self.compare_neighboring_classes(|ordering| ordering != Ordering::Greater)
+    }
+
+    /// Checks whether the scale is strictly proper, i.e. every specific interval is smaller than any interval spanning more scale steps.
+    pub fn is_strictly_proper(&self) -> bool {
+        self.compare_neighboring_classes(|ordering| ordering == Ordering::Less)
+    }
+
+    fn compare_neighboring_classes(&self, accept: impl Fn(Ordering) -> bool) -> bool {
+        self.interval_classes.windows(2).all(|window| {
+            match (window[0].sizes.last(), window[1].sizes.first()) {
+                (Some(largest_of_lower_class), Some(smallest_of_upper_class)) => {
+                    let ordering = if smallest_of_upper_class
+                        .size
+                        .deviation_from(largest_of_lower_class.size)
+                        .is_negligible()
+                    {
+                        Ordering::Equal
+                    } else {
+                        largest_of_lower_class
+                            .size
+                            .total_cmp(&smallest_of_upper_class.size)
+                    };
+                    accept(ordering)
+                }
+                _ => true,
+            }
+        })
+    }
+
+    /// Checks whether every generic interval comes in exactly two specific sizes.
+    pub fn has_myhill_property(&self) -> bool {
+        self.interval_classes
+            .iter()
+            .all(|interval_class| interval_class.sizes.len() == 2)
+    }
+
+    /// Checks whether every generic interval comes in at most two specific sizes.
+    pub fn is_distributionally_even(&self) -> bool {
+        self.interval_classes
+            .iter()
+            .all(|interval_class| interval_class.sizes.len() <= 2)
+    }
+
+    /// Checks whether the scale is a moment of symmetry, i.e. it is distributionally even and has exactly two step sizes.
+    pub fn is_mos(&self) -> bool {
+        self.is_distributionally_even()
+            && self
+                .interval_classes
+                .first()
+                .filter(|step_class| step_class.sizes.len() == 2)
+                .is_some()
+    }
+}
+
+/// A generic interval and the specific sizes it comes in.
+#[derive(Clone, Debug)]
+pub struct IntervalClass {
+    /// The number of scale steps spanned by the interval.
+    pub num_steps: u16,
+
+    /// The specific sizes of the interval in ascending order.
+    pub sizes: Vec<IntervalSize>,
+}
+
+/// A specific interval size and the number of times it occurs within a period.
+#[derive(Copy, Clone, Debug)]
+pub struct IntervalSize {
+    /// The size of the interval.
+    pub size: Ratio,
+
+    /// The number of occurrences.
+    pub count: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::scala::{self, Scl};
+
+    use super::*;
+
+    #[test]
+    fn analyze_equal_temperament() {
+        let analysis = Scl::builder().push_cents(100.0).build().unwrap().analyze();
+
+        assert!(analysis.interval_classes().is_empty());
+        assert!(analysis.is_constant_structure());
+        assert!(analysis.is_strictly_proper());
+        assert!(analysis.has_myhill_property());
+        assert!(!analysis.is_mos());
+
+        let analysis = scala::create_rank2_temperament_scale(
+            None,
+            Ratio::from_cents(100.0),
+            11,
+            0,
+            Ratio::octave(),
+        )
+        .unwrap()
+        .analyze();
+
+        assert_eq!(analysis.interval_classes().len(), 11);
+        assert!(analysis.is_constant_structure());
+        assert!(analysis.is_strictly_proper());
+        assert!(!analysis.has_myhill_property());
+        assert!(analysis.is_distributionally_even());
+        assert!(!analysis.is_mos());
+    }
+
+    #[test]
+    fn analyze_diatonic_scale() {
+        let analysis = scala::create_rank2_temperament_scale(
+            None,
+            Ratio::from_float(1.5),
+            5,
+            1,
+            Ratio::octave(),
+        )
+        .unwrap()
+        .analyze();
+
+        let steps = &analysis.interval_classes()[0];
+        assert_eq!(steps.num_steps, 1);
+        assert_eq!(steps.sizes.len(), 2);
+        assert_approx_eq!(steps.sizes[0].size.as_cents(), 90.224996);
+        assert_eq!(steps.sizes[0].count, 2);
+        assert_approx_eq!(steps.sizes[1].size.as_cents(), 203.910002);
+        assert_eq!(steps.sizes[1].count, 5);
+
+        // The augmented fourth (729/512) is larger than the diminished fifth (1024/729)
+        assert!(analysis.is_constant_structure());
+        assert!(!analysis.is_proper());
+        assert!(!analysis.is_strictly_proper());
+        assert!(analysis.has_myhill_property());
+        assert!(analysis.is_distributionally_even());
+        assert!(analysis.is_mos());
+
+        let meantone_analysis = scala::create_rank2_temperament_scale(
+            None,
+            Ratio::from_cents(696.578),
+            5,
+            1,
+            Ratio::octave(),
+        )
+        .unwrap()
+        .analyze();
+
+        assert!(meantone_analysis.is_constant_structure());
+        assert!(meantone_analysis.is_proper());
+        assert!(meantone_analysis.is_strictly_proper());
+        assert!(meantone_analysis.is_mos());
+    }
+
+    #[test]
+    fn analyze_improper_scale() {
+        // Step sizes: 9/8, 16/15, 10/9, 9/8, 16/15, 75/64, 16/15
+        let harmonic_minor = Scl::builder()
+            .push_fraction(9, 8)
+            .push_fraction(6, 5)
+            .push_fraction(4, 3)
+            .push_fraction(3, 2)
+            .push_fraction(8, 5)
+            .push_fraction(15, 8)
+            .push_int(2)
+            .build()
+            .unwrap()
+            .analyze();
+
+        assert!(!harmonic_minor.is_distributionally_even());
+        assert!(!harmonic_minor.is_mos());
+
+        let hexachord = Scl::builder()
+            .push_cents(50.0)
+            .push_cents(100.0)
+            .push_cents(600.0)
+            .push_int(2)
+            .build()
+            .unwrap()
+            .analyze();
+
+        assert!(!hexachord.is_proper());
+        assert!(!hexachord.is_strictly_proper());
+        assert!(!hexachord.is_constant_structure());
+    }
+
+    #[test]
+    fn interval_vector() {
+        let interval_vector = Scl::builder()
+            .push_cents(200.0)
+            .push_cents(400.0)
+            .push_int(2)
+            .build()
+            .unwrap()
+            .analyze()
+            .interval_vector();
+
+        let as_cents_and_counts: Vec<_> = interval_vector
+            .iter()
+            .map(|size| (size.size.as_cents().round(), size.count))
+            .collect();
+
+        assert_eq!(
+            as_cents_and_counts,
+            [(200.0, 2), (400.0, 1), (800.0, 1), (1000.0, 2)]
+        );
+    }
+}
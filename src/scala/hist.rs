@@ -0,0 +1,304 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::pitch::Ratio;
+
+use super::{Scl, SclBuildError};
+
+/// Historical 12-note temperaments used in early music.
+///
+/// All temperaments are described as a chain of fifths, each of which is narrowed by a fraction of a comma.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HistoricalTemperament {
+    /// Pure fifths from E&flat; to G&sharp; with a wolf fifth between G&sharp; and E&flat;.
+    Pythagorean,
+
+    /// Fifths narrowed by 1/3 syntonic comma from E&flat; to G&sharp;.
+    ThirdCommaMeantone,
+
+    /// Fifths narrowed by 1/4 syntonic comma from E&flat; to G&sharp; yielding pure major thirds.
+    QuarterCommaMeantone,
+
+    /// Fifths narrowed by 1/6 syntonic comma from E&flat; to G&sharp;.
+    SixthCommaMeantone,
+
+    /// The fifths C-G, G-D, D-A and B-F&sharp; are narrowed by 1/4 Pythagorean comma.
+    Werckmeister3,
+
+    /// The fifths C-G, G-D, D-A and A-E are narrowed by 1/4 syntonic comma, F&sharp;-C&sharp; by a schisma.
+    Kirnberger3,
+
+    /// The fifths from F to B are narrowed by 1/6 Pythagorean comma.
+    Vallotti,
+
+    /// Thomas Young's second temperament: The fifths from C to F&sharp; are narrowed by 1/6 Pythagorean comma.
+    Young,
+}
+
+impl HistoricalTemperament {
+    /// All available [`HistoricalTemperament`]s.
+    pub const ALL: [HistoricalTemperament; 8] = [
+        HistoricalTemperament::Pythagorean,
+        HistoricalTemperament::ThirdCommaMeantone,
+        HistoricalTemperament::QuarterCommaMeantone,
+        HistoricalTemperament::SixthCommaMeantone,
+        HistoricalTemperament::Werckmeister3,
+        HistoricalTemperament::Kirnberger3,
+        HistoricalTemperament::Vallotti,
+        HistoricalTemperament::Young,
+    ];
+
+    /// The short name used for parsing and display, e.g. `werckmeister3`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HistoricalTemperament::Pythagorean => "pythagorean",
+            HistoricalTemperament::ThirdCommaMeantone => "third-comma",
+            HistoricalTemperament::QuarterCommaMeantone => "quarter-comma",
+            HistoricalTemperament::SixthCommaMeantone => "sixth-comma",
+            HistoricalTemperament::Werckmeister3 => "werckmeister3",
+            HistoricalTemperament::Kirnberger3 => "kirnberger3",
+            HistoricalTemperament::Vallotti => "vallotti",
+            HistoricalTemperament::Young => "young",
+        }
+    }
+
+    /// A human-readable description, e.g. `Werckmeister III`.
+    pub fn description(self) -> &'static str {
+        match self {
+            HistoricalTemperament::Pythagorean => "Pythagorean tuning",
+            HistoricalTemperament::ThirdCommaMeantone => "1/3-comma meantone",
+            HistoricalTemperament::QuarterCommaMeantone => "1/4-comma meantone",
+            HistoricalTemperament::SixthCommaMeantone => "1/6-comma meantone",
+            HistoricalTemperament::Werckmeister3 => "Werckmeister III",
+            HistoricalTemperament::Kirnberger3 => "Kirnberger III",
+            HistoricalTemperament::Vallotti => "Vallotti",
+            HistoricalTemperament::Young => "Young II",
+        }
+    }
+
+    /// Returns the pitches of the 12 pitch classes relative to C, starting with C.
+    pub fn pitch_classes(self) -> [Ratio; 12] {
+        let pythagorean_comma = Ratio::from_float(531441.0 / 524288.0);
+        let syntonic_comma = Ratio::from_float(81.0 / 80.0);
+        let schisma = pythagorean_comma.deviation_from(syntonic_comma);
+
+        let meantone =
+            |divisions: u8| (EB, [syntonic_comma.divided_into_equal_steps(divisions); 11]);
+        let pure = Ratio::default();
+
+        let (chain_start, narrowings) = match self {
+            HistoricalTemperament::Pythagorean => (EB, [pure; 11]),
+            HistoricalTemperament::ThirdCommaMeantone => meantone(3),
+            HistoricalTemperament::QuarterCommaMeantone => meantone(4),
+            HistoricalTemperament::SixthCommaMeantone => meantone(6),
+            HistoricalTemperament::Werckmeister3 => {
+                let quarter = pythagorean_comma.divided_into_equal_steps(4);
+                // C-G, G-D, D-A, A-E, E-B, B-F#, F#-C#, C#-G#, G#-Eb, Eb-Bb, Bb-F
+                let narrowings = [
+                    quarter, quarter, quarter, pure, pure, quarter, pure, pure, pure, pure, pure,
+                ];
+                (C, narrowings)
+            }
+            HistoricalTemperament::Kirnberger3 => {
+                let quarter = syntonic_comma.divided_into_equal_steps(4);
+                // C-G, G-D, D-A, A-E, E-B, B-F#, F#-C#, C#-G#, G#-Eb, Eb-Bb, Bb-F
+                let narrowings = [
+                    quarter, quarter, quarter, quarter, pure, pure, schisma, pure, pure, pure, pure,
+                ];
+                (C, narrowings)
+            }
+            HistoricalTemperament::Vallotti => {
+                let sixth = pythagorean_comma.divided_into_equal_steps(6);
+                // F-C, C-G, G-D, D-A, A-E, E-B, B-F#, F#-C#, C#-G#, G#-Eb, Eb-Bb
+                let narrowings = [
+                    sixth, sixth, sixth, sixth, sixth, sixth, pure, pure, pure, pure, pure,
+                ];
+                (F, narrowings)
+            }
+            HistoricalTemperament::Young => {
+                let sixth = pythagorean_comma.divided_into_equal_steps(6);
+                // C-G, G-D, D-A, A-E, E-B, B-F#, F#-C#, C#-G#, G#-Eb, Eb-Bb, Bb-F
+                let narrowings = [
+                    sixth, sixth, sixth, sixth, sixth, sixth, pure, pure, pure, pure, pure,
+                ];
+                (C, narrowings)
+            }
+        };
+
+        let pure_fifth = Ratio::from_float(1.5);
+
+        let mut pitch_classes = [Ratio::default(); 12];
+        let mut pitch_class = chain_start;
+        let mut pitch = Ratio::default();
+        for narrowing in narrowings {
+            pitch = pitch.stretched_by(pure_fifth.deviation_from(narrowing));
+            pitch_class = (pitch_class + 7) % 12;
+            pitch_classes[pitch_class] = pitch;
+        }
+
+        let c_pitch = pitch_classes[C];
+        pitch_classes.map(|pitch| {
+            Ratio::from_octaves(pitch.deviation_from(c_pitch).as_octaves().rem_euclid(1.0))
+        })
+    }
+}
+
+const C: usize = 0;
+const EB: usize = 3;
+const F: usize = 5;
+
+impl Display for HistoricalTemperament {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for HistoricalTemperament {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HistoricalTemperament::ALL
+            .into_iter()
+            .find(|temperament| temperament.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = HistoricalTemperament::ALL
+                    .iter()
+                    .map(|temperament| temperament.name())
+                    .collect();
+                format!(
+                    "Unknown temperament `{s}`. Should be one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Creates a 12-note [`Scl`] of the given [`HistoricalTemperament`].
+///
+/// The scale starts at the pitch class `rotation` semitones above C.
+/// This is useful when the reference note of the keyboard mapping is not C.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::scala;
+/// # use tune::scala::HistoricalTemperament;
+/// let werckmeister3 =
+///     scala::create_historical_temperament_scale(None, HistoricalTemperament::Werckmeister3, 0)
+///         .unwrap();
+///
+/// assert_eq!(
+///     format!("{}", werckmeister3.export()).lines().collect::<Vec<_>>(),
+///     ["Werckmeister III",
+///      "12", "90.225", "192.180", "294.135", "390.225", "498.045", "588.270",
+///      "696.090", "792.180", "888.270", "996.090", "1092.180", "1200.000"]
+/// );
+///
+/// // Start at D
+/// let werckmeister3_on_d =
+///     scala::create_historical_temperament_scale(None, HistoricalTemperament::Werckmeister3, 2)
+///         .unwrap();
+///
+/// assert_eq!(
+///     format!("{}", werckmeister3_on_d.export()).lines().collect::<Vec<_>>(),
+///     ["Werckmeister III rotated by 2 semitones",
+///      "12", "101.955", "198.045", "305.865", "396.090", "503.910", "600.000",
+///      "696.090", "803.910", "900.000", "1007.820", "1098.045", "1200.000"]
+/// );
+/// ```
+pub fn create_historical_temperament_scale(
+    description: impl Into<Option<String>>,
+    temperament: HistoricalTemperament,
+    rotation: u8,
+) -> Result<Scl, SclBuildError> {
+    let pitch_classes = temperament.pitch_classes();
+    let rotation = usize::from(rotation % 12);
+    let start = pitch_classes[rotation];
+
+    let mut builder = Scl::builder();
+    for step in 1..12 {
+        let pitch = pitch_classes[(rotation + step) % 12].deviation_from(start);
+        builder = builder.push_ratio(Ratio::from_octaves(pitch.as_octaves().rem_euclid(1.0)));
+    }
+    builder = builder.push_ratio(Ratio::octave());
+
+    let description = description.into().unwrap_or_else(|| match rotation {
+        0 => temperament.description().to_owned(),
+        rotation => format!(
+            "{} rotated by {rotation} semitones",
+            temperament.description()
+        ),
+    });
+    builder.build_with_description(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn pitch_classes_of_historical_temperaments() {
+        let expected_pitch_classes = [
+            (
+                HistoricalTemperament::Pythagorean,
+                [
+                    0.0, 113.685, 203.910, 294.135, 407.820, 498.045, 611.730, 701.955, 815.640,
+                    905.865, 996.090, 1109.775,
+                ],
+            ),
+            (
+                HistoricalTemperament::QuarterCommaMeantone,
+                [
+                    0.0, 76.049, 193.157, 310.265, 386.314, 503.422, 579.471, 696.578, 772.627,
+                    889.735, 1006.843, 1082.892,
+                ],
+            ),
+            (
+                HistoricalTemperament::Werckmeister3,
+                [
+                    0.0, 90.225, 192.180, 294.135, 390.225, 498.045, 588.270, 696.090, 792.180,
+                    888.270, 996.090, 1092.180,
+                ],
+            ),
+            (
+                HistoricalTemperament::Kirnberger3,
+                [
+                    0.0, 90.225, 193.157, 294.135, 386.314, 498.045, 590.224, 696.578, 792.180,
+                    889.735, 996.090, 1088.269,
+                ],
+            ),
+            (
+                HistoricalTemperament::Vallotti,
+                [
+                    0.0, 94.135, 196.090, 298.045, 392.180, 501.955, 592.180, 698.045, 796.090,
+                    894.135, 1000.000, 1090.225,
+                ],
+            ),
+            (
+                HistoricalTemperament::Young,
+                [
+                    0.0, 90.225, 196.090, 294.135, 392.180, 498.045, 588.270, 698.045, 792.180,
+                    894.135, 996.090, 1090.225,
+                ],
+            ),
+        ];
+
+        for (temperament, expected) in expected_pitch_classes {
+            for (actual, expected) in temperament.pitch_classes().iter().zip(expected) {
+                assert_approx_eq!(actual.as_cents(), expected, 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_temperament_names() {
+        for temperament in HistoricalTemperament::ALL {
+            assert_eq!(temperament.name().parse(), Ok(temperament));
+        }
+        assert!("werckmeister".parse::<HistoricalTemperament>().is_err());
+    }
+}
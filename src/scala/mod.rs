@@ -1,6 +1,9 @@
 //! Interop with [Scala](http://www.huygens-fokker.org/scala/) tuning files.
 
+mod analysis;
+mod hist;
 mod import;
+mod transform;
 
 use std::{
     borrow::Borrow,
@@ -11,6 +14,7 @@ use std::{
 };
 
 use crate::{
+    consonance::Spectrum,
     key::PianoKey,
     math,
     note::{Note, PitchedNote},
@@ -19,7 +23,7 @@ use crate::{
     tuning::{Approximation, KeyboardMapping, Scale, Tuning},
 };
 
-pub use self::import::*;
+pub use self::{analysis::*, hist::*, import::*};
 
 /// Scale format according to <http://www.huygens-fokker.org/scala/scl_format.html>.
 ///
@@ -87,6 +91,41 @@ impl Scl {
         self.num_items
     }
 
+    /// Returns the pitch values as they have been declared, excluding the implicit unison and including the period.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::PitchValue;
+    /// # use tune::scala::Scl;
+    /// let scl = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_cents(386.314)
+    ///     .push_int(2)
+    ///     .build().unwrap();
+    ///
+    /// assert_eq!(
+    ///     scl.pitch_values(),
+    ///     [
+    ///         PitchValue::Fraction(9, Some(8)),
+    ///         PitchValue::Cents(386.314),
+    ///         PitchValue::Fraction(2, None)
+    ///     ]
+    /// );
+    ///
+    /// let rebuilt = scl
+    ///     .pitch_values()
+    ///     .iter()
+    ///     .fold(Scl::builder(), |builder, &pitch_value| builder.push_pitch_value(pitch_value))
+    ///     .build_with_description(scl.description())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(rebuilt.export().to_string(), scl.export().to_string());
+    /// ```
+    pub fn pitch_values(&self) -> &[PitchValue] {
+        &self.pitch_values[1..]
+    }
+
     /// Retrieves relative pitches without requiring any [`Kbm`] reference.
     ///
     /// # Examples
@@ -371,6 +410,37 @@ impl Scl {
     pub fn export(&self) -> SclExport {
         SclExport(self)
     }
+
+    /// Analyzes the structural properties of the current scale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala;
+    /// let pythagorean_major =
+    ///     scala::create_rank2_temperament_scale(
+    ///         None, Ratio::from_float(1.5), 5, 1, Ratio::octave(),
+    ///     ).unwrap();
+    ///
+    /// let analysis = pythagorean_major.analyze();
+    ///
+    /// assert!(analysis.is_constant_structure());
+    /// assert!(!analysis.is_proper());
+    /// assert!(analysis.is_mos());
+    ///
+    /// // The fourth comes in two sizes: The perfect fourth (6x) and the augmented fourth (1x)
+    /// let fourths = &analysis.interval_classes()[2];
+    /// assert_eq!(fourths.num_steps, 3);
+    /// assert_approx_eq!(fourths.sizes[0].size.as_cents(), 498.044999);
+    /// assert_eq!(fourths.sizes[0].count, 6);
+    /// assert_approx_eq!(fourths.sizes[1].size.as_cents(), 611.730005);
+    /// assert_eq!(fourths.sizes[1].count, 1);
+    /// ```
+    pub fn analyze(&self) -> SclAnalysis {
+        analysis::analyze_scl(self)
+    }
 }
 
 /// Builder created by [`Scl::builder`].
@@ -395,7 +465,7 @@ impl SclBuilder {
         self.push_pitch_value(PitchValue::Fraction(numer, Some(denom)))
     }
 
-    fn push_pitch_value(mut self, pitch_value: PitchValue) -> Self {
+    pub fn push_pitch_value(mut self, pitch_value: PitchValue) -> Self {
         self.pitch_values.push(pitch_value);
         self
     }
@@ -513,14 +583,17 @@ pub enum SclBuildError {
     ScaleTooLarge,
 }
 
-#[derive(Copy, Clone, Debug)]
-enum PitchValue {
+/// A single pitch line of an [`Scl`] file as it has been declared, i.e. without losing the distinction between fractions and cents.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PitchValue {
+    /// A pitch declared in cents, e.g. `701.955`.
     Cents(f64),
+    /// A pitch declared as a fraction, e.g. `3/2`, or as an integer, e.g. `2`.
     Fraction(u32, Option<u32>),
 }
 
 impl PitchValue {
-    fn as_ratio(self) -> Ratio {
+    pub fn as_ratio(self) -> Ratio {
         match self {
             PitchValue::Cents(cents_value) => Ratio::from_cents(cents_value),
             PitchValue::Fraction(numer, denom) => {
@@ -606,6 +679,27 @@ impl Kbm {
         self.num_items
     }
 
+    /// Returns the keyboard mapping entries where [`None`] stands for an unmapped key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::note::Note;
+    /// # use tune::scala::Kbm;
+    /// let kbm = Kbm::builder(Note::from_midi_number(62))
+    ///     .push_mapped_key(0)
+    ///     .push_unmapped_key()
+    ///     .push_mapped_key(1)
+    ///     .formal_octave(2)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(kbm.key_mapping(), [Some(0), None, Some(1)]);
+    /// ```
+    pub fn key_mapping(&self) -> &[Option<i16>] {
+        &self.key_mapping
+    }
+
     /// Returns the scale degree for the given [`PianoKey`] .
     ///
     /// # Examples
@@ -1340,6 +1434,47 @@ pub fn create_harmonics_scale(
     builder.build_with_description(description.into().unwrap_or(builtin_description))
 }
 
+/// Creates a scale from the minima of the dissonance curve of the given [`Spectrum`] following William Sethares.
+///
+/// The scale consists of all local minima between the unison and `period` and is completed by `period` itself.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::consonance::Spectrum;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::scala;
+/// // Inharmonic spectrum with a pseudo-octave of 2.76
+/// let spectrum = "1,2.76,5.4,8.93".parse::<Spectrum>().unwrap();
+/// let pseudo_octave = Ratio::from_float(2.76);
+/// let scl = scala::create_spectrum_scale(None, &spectrum, Pitch::from_hz(261.626), pseudo_octave)
+///     .unwrap();
+///
+/// assert_eq!(
+///     format!("{}", scl.export()).lines().collect::<Vec<_>>(),
+///     ["Dissonance minima of spectrum 1.0000@1,2.7600@1,5.4000@1,8.9300@1",
+///      "6", "601.952", "870.841", "1013.682", "1161.949", "1421.797", "1757.602"]
+/// );
+/// ```
+pub fn create_spectrum_scale(
+    description: impl Into<Option<String>>,
+    spectrum: &Spectrum,
+    fundamental: Pitch,
+    period: Ratio,
+) -> Result<Scl, SclBuildError> {
+    let mut builder = Scl::builder();
+    for minimum in spectrum.dissonance_minima(fundamental, period) {
+        builder = builder.push_ratio(minimum);
+    }
+    builder = builder.push_ratio(period);
+
+    let description = description
+        .into()
+        .unwrap_or_else(|| format!("Dissonance minima of spectrum {spectrum}"));
+    builder.build_with_description(description)
+}
+
 /// Type of harmonic series segment to use.
 #[derive(Copy, Clone, Debug)]
 pub enum SegmentType {
@@ -0,0 +1,484 @@
+use crate::{math, pitch::Ratio};
+
+use super::{PitchValue, Scl, SclBuildError};
+
+/// Operations deriving a new [`Scl`] from an existing one.
+///
+/// Whenever possible, fractions are kept as fractions s.t. the derived scale is still exact.
+impl Scl {
+    /// Rotates the scale s.t. the given degree becomes the new root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build_with_description("Major")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     format!("{}", major.mode(5).unwrap().export()).lines().collect::<Vec<_>>(),
+    ///     ["Major mode 5", "7", "9/8", "6/5", "27/20", "3/2", "8/5", "9/5", "2"]
+    /// );
+    /// ```
+    pub fn mode(&self, degree: u16) -> Result<Scl, SclBuildError> {
+        let degree = i32::from(degree);
+        let root = self.pitch_value_of(degree);
+
+        let mut builder = Scl::builder();
+        for step in 1..=i32::from(self.num_items) {
+            builder =
+                builder.push_pitch_value(self.pitch_value_of(degree + step).deviation_from(root));
+        }
+        builder.build_with_description(format!("{} mode {degree}", self.description))
+    }
+
+    /// Reflects all intervals of the scale s.t. the steps of the scale appear in reverse order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build_with_description("Major")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     format!("{}", major.inverted().unwrap().export()).lines().collect::<Vec<_>>(),
+    ///     ["Major inverted", "7", "16/15", "6/5", "4/3", "3/2", "8/5", "16/9", "2"]
+    /// );
+    /// ```
+    pub fn inverted(&self) -> Result<Scl, SclBuildError> {
+        let num_items = i32::from(self.num_items);
+        let period = self.pitch_value_of(num_items);
+
+        let mut builder = Scl::builder();
+        for degree in 1..num_items {
+            builder = builder
+                .push_pitch_value(period.deviation_from(self.pitch_value_of(num_items - degree)));
+        }
+        builder
+            .push_pitch_value(period)
+            .build_with_description(format!("{} inverted", self.description))
+    }
+
+    /// Creates a scale consisting of the given degrees only.
+    ///
+    /// The root and the period are always included. Degrees are reduced into the period and duplicates are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build_with_description("Major")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     format!("{}", major.subset([1, 2, 4, 5, 11]).unwrap().export()).lines().collect::<Vec<_>>(),
+    ///     ["Major subset 1,2,4,5", "5", "9/8", "5/4", "3/2", "5/3", "2"]
+    /// );
+    /// ```
+    pub fn subset(&self, degrees: impl IntoIterator<Item = u16>) -> Result<Scl, SclBuildError> {
+        let mut degrees: Vec<_> = degrees
+            .into_iter()
+            .map(|degree| degree.checked_rem(self.num_items).unwrap_or_default())
+            .filter(|&degree| degree != 0)
+            .collect();
+        degrees.sort();
+        degrees.dedup();
+
+        let mut builder = Scl::builder();
+        for &degree in &degrees {
+            builder = builder.push_pitch_value(self.pitch_value_of(i32::from(degree)));
+        }
+
+        let degrees: Vec<_> = degrees.iter().map(u16::to_string).collect();
+        builder
+            .push_pitch_value(self.pitch_value_of(i32::from(self.num_items)))
+            .build_with_description(format!("{} subset {}", self.description, degrees.join(",")))
+    }
+
+    /// Creates a scale containing the pitches of both scales within the period of `self`.
+    ///
+    /// Pitches of `other` that deviate by at most `tolerance` from an already existing pitch are discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::Scl;
+    /// let major_triad = Scl::builder()
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(3, 2)
+    ///     .push_int(2)
+    ///     .build_with_description("Major triad")
+    ///     .unwrap();
+    ///
+    /// let minor_triad_on_fifth = Scl::builder()
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(9, 5)
+    ///     .push_fraction(9, 4)
+    ///     .push_int(2)
+    ///     .build_with_description("Minor triad")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     format!("{}", major_triad.merged_with(&minor_triad_on_fifth, Ratio::from_cents(1.0)).unwrap().export())
+    ///         .lines()
+    ///         .collect::<Vec<_>>(),
+    ///     ["Major triad merged with Minor triad", "5", "9/8", "5/4", "3/2", "9/5", "2"]
+    /// );
+    /// ```
+    pub fn merged_with(&self, other: &Scl, tolerance: Ratio) -> Result<Scl, SclBuildError> {
+        let period = self.pitch_value_of(i32::from(self.num_items));
+
+        let mut pitch_values = vec![PitchValue::Fraction(1, None), period];
+        let candidates = (1..i32::from(self.num_items))
+            .map(|degree| self.pitch_value_of(degree))
+            .chain(
+                (1..=i32::from(other.num_items))
+                    .map(|degree| reduce_into_period(other.pitch_value_of(degree), period)),
+            );
+        for candidate in candidates {
+            let is_duplicate = pitch_values.iter().any(|pitch_value| {
+                let deviation = candidate.as_ratio().deviation_from(pitch_value.as_ratio());
+                deviation.is_negligible() || deviation.abs() <= tolerance.abs()
+            });
+            if !is_duplicate {
+                pitch_values.push(candidate);
+            }
+        }
+
+        let mut builder = Scl::builder();
+        for pitch_value in sorted_without_unison(pitch_values) {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        builder.build_with_description(format!(
+            "{} merged with {}",
+            self.description, other.description
+        ))
+    }
+
+    /// Stacks `num_notes` notes that are `step` degrees apart to form a chord repeating at the period.
+    ///
+    /// Notes exceeding the period are reduced into the period. Notes that coincide with the root or another note are discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::scala::Scl;
+    /// let major = Scl::builder()
+    ///     .push_fraction(9, 8)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(5, 3)
+    ///     .push_fraction(15, 8)
+    ///     .push_int(2)
+    ///     .build_with_description("Major")
+    ///     .unwrap();
+    ///
+    /// // Major seventh chord
+    /// assert_eq!(
+    ///     format!("{}", major.stacked(2, 4).unwrap().export()).lines().collect::<Vec<_>>(),
+    ///     ["Major stacked by 2 degrees", "4", "5/4", "3/2", "15/8", "2"]
+    /// );
+    ///
+    /// // Major ninth chord exceeding the period
+    /// assert_eq!(
+    ///     format!("{}", major.stacked(2, 5).unwrap().export()).lines().collect::<Vec<_>>(),
+    ///     ["Major stacked by 2 degrees", "5", "9/8", "5/4", "3/2", "15/8", "2"]
+    /// );
+    /// ```
+    pub fn stacked(&self, step: u16, num_notes: u16) -> Result<Scl, SclBuildError> {
+        let period = self.pitch_value_of(i32::from(self.num_items));
+
+        let mut pitch_values = vec![PitchValue::Fraction(1, None)];
+        for note in 1..i32::from(num_notes) {
+            let candidate = reduce_into_period(self.pitch_value_of(note * i32::from(step)), period);
+            let is_duplicate = pitch_values.iter().any(|pitch_value| {
+                candidate
+                    .as_ratio()
+                    .deviation_from(pitch_value.as_ratio())
+                    .is_negligible()
+            });
+            if !is_duplicate {
+                pitch_values.push(candidate);
+            }
+        }
+
+        let mut builder = Scl::builder();
+        for pitch_value in sorted_without_unison(pitch_values) {
+            builder = builder.push_pitch_value(pitch_value);
+        }
+        builder
+            .push_pitch_value(period)
+            .build_with_description(format!("{} stacked by {step} degrees", self.description))
+    }
+
+    /// Replaces the period of the scale. Pitches that are not below the new period are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::Scl;
+    /// let bohlen_pierce = Scl::builder()
+    ///     .push_ratio("1:13:3".parse().unwrap())
+    ///     .push_ratio("2:13:3".parse().unwrap())
+    ///     .push_ratio("3:13:3".parse().unwrap())
+    ///     .push_ratio("4:13:3".parse().unwrap())
+    ///     .push_ratio("5:13:3".parse().unwrap())
+    ///     .push_ratio("6:13:3".parse().unwrap())
+    ///     .push_ratio("7:13:3".parse().unwrap())
+    ///     .build_with_description("Partial BP")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     format!("{}", bohlen_pierce.with_period(Ratio::octave()).unwrap().export())
+    ///         .lines()
+    ///         .collect::<Vec<_>>(),
+    ///     [
+    ///         "Partial BP with period 1200.000c", "7",
+    ///         "146.304", "292.608", "438.913", "585.217", "731.521", "877.825", "1200.000"
+    ///     ]
+    /// );
+    /// ```
+    pub fn with_period(&self, period: Ratio) -> Result<Scl, SclBuildError> {
+        let mut builder = Scl::builder();
+        for degree in 1..i32::from(self.num_items) {
+            let pitch_value = self.pitch_value_of(degree);
+            let pitch = pitch_value.as_ratio();
+            if pitch < period && !pitch.deviation_from(period).is_negligible() {
+                builder = builder.push_pitch_value(pitch_value);
+            }
+        }
+        builder.push_ratio(period).build_with_description(format!(
+            "{} with period {:.3}c",
+            self.description,
+            period.as_cents()
+        ))
+    }
+
+    /// Like [`Scl::relative_pitch_of`] but keeps the exact representation of the pitch.
+    fn pitch_value_of(&self, degree: i32) -> PitchValue {
+        let (num_periods, pitch_index) = self.num_periods_and_pitch_index_for_degree(degree);
+        let period = self.pitch_values[usize::from(self.num_items)];
+        // The root is stored as 0 cents but should not turn the derived pitches into cents values
+        let pitch_value = match pitch_index {
+            0 => PitchValue::Fraction(1, None),
+            pitch_index => self.pitch_values[pitch_index],
+        };
+
+        (0..num_periods.unsigned_abs()).fold(pitch_value, |pitch_value, _| {
+            if num_periods > 0 {
+                pitch_value.stretched_by(period)
+            } else {
+                pitch_value.deviation_from(period)
+            }
+        })
+    }
+}
+
+/// Sorts the pitch values and removes the unison which is expected to be contained exactly once.
+fn sorted_without_unison(mut pitch_values: Vec<PitchValue>) -> impl Iterator<Item = PitchValue> {
+    pitch_values.sort_by(|a, b| a.as_ratio().total_cmp(&b.as_ratio()));
+    pitch_values.into_iter().skip(1)
+}
+
+fn reduce_into_period(mut pitch_value: PitchValue, period: PitchValue) -> PitchValue {
+    if period.as_ratio() <= Ratio::default() || period.as_ratio().is_negligible() {
+        return pitch_value;
+    }
+    while pitch_value.as_ratio() >= period.as_ratio() {
+        pitch_value = pitch_value.deviation_from(period);
+    }
+    while pitch_value.as_ratio() < Ratio::default() {
+        pitch_value = pitch_value.stretched_by(period);
+    }
+    pitch_value
+}
+
+impl PitchValue {
+    fn stretched_by(self, other: PitchValue) -> PitchValue {
+        match (self.as_fraction(), other.as_fraction()) {
+            (Some((numer, denom)), Some((other_numer, other_denom))) => {
+                PitchValue::from_fraction(numer * other_numer, denom * other_denom)
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            PitchValue::Cents(self.as_ratio().stretched_by(other.as_ratio()).as_cents())
+        })
+    }
+
+    fn deviation_from(self, other: PitchValue) -> PitchValue {
+        match (self.as_fraction(), other.as_fraction()) {
+            (Some((numer, denom)), Some((other_numer, other_denom))) => {
+                PitchValue::from_fraction(numer * other_denom, denom * other_numer)
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            PitchValue::Cents(self.as_ratio().deviation_from(other.as_ratio()).as_cents())
+        })
+    }
+
+    fn as_fraction(self) -> Option<(u64, u64)> {
+        match self {
+            PitchValue::Cents(_) => None,
+            PitchValue::Fraction(numer, denom) => {
+                Some((u64::from(numer), u64::from(denom.unwrap_or(1))))
+            }
+        }
+    }
+
+    fn from_fraction(numer: u64, denom: u64) -> Option<PitchValue> {
+        if numer == 0 || denom == 0 {
+            return None;
+        }
+        let gcd = math::gcd_u64(numer, denom);
+        let numer = u32::try_from(numer / gcd).ok()?;
+        Some(match u32::try_from(denom / gcd).ok()? {
+            1 => PitchValue::Fraction(numer, None),
+            denom => PitchValue::Fraction(numer, Some(denom)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn transformations_of_cents_based_scale() {
+        let mut builder = Scl::builder();
+        for step in 1..=12 {
+            builder = builder.push_cents(f64::from(step) * 100.0);
+        }
+        let edo12 = builder.build().unwrap();
+
+        for transformed in [
+            edo12.mode(3).unwrap(),
+            edo12.mode(15).unwrap(),
+            edo12.inverted().unwrap(),
+        ] {
+            assert_eq!(transformed.num_items(), 12);
+            for degree in -12..=24 {
+                assert_approx_eq!(
+                    transformed.relative_pitch_of(degree).as_cents(),
+                    f64::from(degree) * 100.0
+                );
+            }
+        }
+
+        let whole_tone = edo12.subset((0..12).step_by(2)).unwrap();
+        assert_eq!(whole_tone.num_items(), 6);
+        assert_approx_eq!(whole_tone.relative_pitch_of(1).as_cents(), 200.0);
+
+        let merged = whole_tone
+            .merged_with(&edo12, Ratio::from_cents(1.0))
+            .unwrap();
+        assert_eq!(merged.num_items(), 12);
+        assert_approx_eq!(merged.relative_pitch_of(1).as_cents(), 100.0);
+        assert_approx_eq!(merged.period().as_cents(), 1200.0);
+    }
+
+    #[test]
+    fn merge_with_zero_tolerance() {
+        let major_triad = Scl::builder()
+            .push_fraction(5, 4)
+            .push_fraction(3, 2)
+            .push_int(2)
+            .build()
+            .unwrap();
+
+        let minor_triad_on_fifth = Scl::builder()
+            .push_fraction(3, 2)
+            .push_fraction(9, 5)
+            .push_fraction(9, 4)
+            .push_int(2)
+            .build()
+            .unwrap();
+
+        let merged = major_triad
+            .merged_with(&minor_triad_on_fifth, Ratio::default())
+            .unwrap();
+
+        assert_eq!(
+            merged
+                .export()
+                .to_string()
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>(),
+            ["5", "9/8", "5/4", "3/2", "9/5", "2"]
+        );
+    }
+
+    #[test]
+    fn subset_exceeding_the_period() {
+        let major_triad = Scl::builder()
+            .push_fraction(5, 4)
+            .push_fraction(3, 2)
+            .push_int(2)
+            .build()
+            .unwrap();
+
+        let subset = major_triad.subset([2, 3, 4, 7]).unwrap();
+        assert_eq!(
+            subset
+                .export()
+                .to_string()
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>(),
+            ["3", "5/4", "3/2", "2"]
+        );
+    }
+
+    #[test]
+    fn chord_exceeding_the_period() {
+        let major_triad = Scl::builder()
+            .push_fraction(5, 4)
+            .push_fraction(3, 2)
+            .push_int(2)
+            .build()
+            .unwrap();
+
+        let chord = major_triad.stacked(1, 5).unwrap();
+        assert_eq!(
+            chord
+                .export()
+                .to_string()
+                .lines()
+                .skip(1)
+                .collect::<Vec<_>>(),
+            ["3", "5/4", "3/2", "2"]
+        );
+    }
+}
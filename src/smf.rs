@@ -0,0 +1,1103 @@
+//! Reading and writing of Standard MIDI Files.
+//!
+//! References:
+//! - [Standard MIDI Files](https://www.midi.org/specifications/file-format-specifications/standard-midi-files)
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Read, Write},
+};
+
+use crate::{
+    midi::{ChannelMessage, ChannelMessageType},
+    mts::{Channels, MtsMessage, ScaleOctaveTuning},
+    note::Note,
+    pitch::{Pitch, Pitched, Ratio},
+};
+
+// Chunk types
+const HEADER_CHUNK: &[u8; 4] = b"MThd";
+const TRACK_CHUNK: &[u8; 4] = b"MTrk";
+
+// Event prefixes
+const SYSEX_START: u8 = 0xf0;
+const SYSEX_ESCAPE: u8 = 0xf7;
+const META_EVENT: u8 = 0xff;
+
+// Meta event types
+const TRACK_NAME: u8 = 0x03;
+const END_OF_TRACK: u8 = 0x2f;
+const SET_TEMPO: u8 = 0x51;
+
+const PROGRAM_CHANGE: u8 = 0b1100;
+const CHANNEL_PRESSURE: u8 = 0b1101;
+
+// Controllers
+const NRPN_LSB: u8 = 0x62;
+const NRPN_MSB: u8 = 0x63;
+const RPN_LSB: u8 = 0x64;
+const RPN_MSB: u8 = 0x65;
+const DATA_ENTRY_MSB: u8 = 0x06;
+const DATA_ENTRY_LSB: u8 = 0x26;
+
+// Registered parameter numbers
+const RPN_NULL: (u8, u8) = (0x7f, 0x7f);
+const PITCH_BEND_SENSITIVITY: (u8, u8) = (0x00, 0x00);
+const CHANNEL_FINE_TUNING: (u8, u8) = (0x00, 0x01);
+const CHANNEL_COARSE_TUNING: (u8, u8) = (0x00, 0x02);
+const TUNING_PROGRAM_CHANGE: (u8, u8) = (0x00, 0x03);
+const TUNING_BANK_SELECT: (u8, u8) = (0x00, 0x04);
+
+/// In-memory representation of a Standard MIDI File.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::ChannelMessageType;
+/// # use tune::smf::Smf;
+/// # use tune::smf::SmfEvent;
+/// # use tune::smf::SmfMessage;
+/// # use tune::smf::SmfTrack;
+/// let note_on = ChannelMessageType::NoteOn { key: 69, velocity: 100 };
+/// let note_off = ChannelMessageType::NoteOff { key: 69, velocity: 100 };
+///
+/// let smf = Smf {
+///     ticks_per_quarter: 480,
+///     tracks: vec![SmfTrack {
+///         events: vec![
+///             SmfEvent {
+///                 ticks: 0,
+///                 message: SmfMessage::Channel(note_on.in_channel(0).unwrap()),
+///             },
+///             SmfEvent {
+///                 ticks: 480,
+///                 message: SmfMessage::Channel(note_off.in_channel(0).unwrap()),
+///             },
+///         ],
+///     }],
+/// };
+///
+/// let mut bytes = Vec::new();
+/// smf.write(&mut bytes).unwrap();
+///
+/// assert_eq!(
+///     bytes,
+///     [
+///         b'M', b'T', b'h', b'd', 0, 0, 0, 6, // Header chunk
+///         0, 0, 0, 1, 0x01, 0xe0, // Format 0, 1 track, 480 ticks per quarter
+///         b'M', b'T', b'r', b'k', 0, 0, 0, 13, // Track chunk
+///         0x00, 0x90, 69, 100, // Note on after 0 ticks
+///         0x83, 0x60, 0x80, 69, 100, // Note off after 480 ticks
+///         0x00, 0xff, 0x2f, 0x00, // End of track
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Smf {
+    /// Time resolution of the file.
+    pub ticks_per_quarter: u16,
+
+    /// The tracks of the file. A file with multiple tracks is written in SMF format 1.
+    pub tracks: Vec<SmfTrack>,
+}
+
+/// A single track of a Standard MIDI File.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SmfTrack {
+    /// The events of the track. The *End of Track* event is added implicitly.
+    pub events: Vec<SmfEvent>,
+}
+
+/// A message with an absolute time stamp.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SmfEvent {
+    /// Absolute time of the event in ticks.
+    pub ticks: u64,
+
+    pub message: SmfMessage,
+}
+
+/// The contents of an [`SmfEvent`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SmfMessage {
+    /// A channel message.
+    Channel(ChannelMessage),
+
+    /// A complete System Exclusive message, including the leading `0xf0` and the trailing `0xf7` bytes.
+    SysEx(Vec<u8>),
+
+    /// A *Set Tempo* meta event in microseconds per quarter note.
+    Tempo(u32),
+
+    /// A *Sequence/Track Name* meta event.
+    TrackName(String),
+
+    /// Any other meta event.
+    Meta { meta_type: u8, data: Vec<u8> },
+}
+
+/// A note of a Standard MIDI File together with its sounding [`Pitch`].
+///
+/// See [`Smf::notes`] for how the notes are obtained.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmfNote {
+    /// Absolute start time of the note in ticks.
+    pub start_ticks: u64,
+
+    /// Absolute end time of the note in ticks. Notes that are never released end with the last event of the file.
+    pub end_ticks: u64,
+
+    pub channel: u8,
+
+    pub key: u8,
+
+    pub velocity: u8,
+
+    /// The pitch of the note at the time it was started.
+    pub pitch: Pitch,
+}
+
+impl SmfMessage {
+    /// Parses a raw MIDI message as sent over the wire.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::smf::SmfMessage;
+    /// assert_eq!(
+    ///     SmfMessage::from_raw_message(&[0x91, 69, 100]),
+    ///     Some(SmfMessage::Channel(
+    ///         ChannelMessageType::NoteOn { key: 69, velocity: 100 }
+    ///             .in_channel(1)
+    ///             .unwrap()
+    ///     ))
+    /// );
+    /// assert_eq!(
+    ///     SmfMessage::from_raw_message(&[0xf0, 0x7e, 0x7f, 0xf7]),
+    ///     Some(SmfMessage::SysEx(vec![0xf0, 0x7e, 0x7f, 0xf7]))
+    /// );
+    /// assert_eq!(SmfMessage::from_raw_message(&[0xfe]), None);
+    /// ```
+    pub fn from_raw_message(message: &[u8]) -> Option<SmfMessage> {
+        match message.first()? {
+            &SYSEX_START => Some(SmfMessage::SysEx(message.to_vec())),
+            _ => ChannelMessage::from_raw_message(message).map(SmfMessage::Channel),
+        }
+    }
+}
+
+impl Smf {
+    /// Reads a file in binary format from the given `source`.
+    ///
+    /// Running status is resolved s.t. every channel message is complete.
+    /// Escaped (`0xf7`) events and unknown chunks are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::smf::Smf;
+    /// # use tune::smf::SmfEvent;
+    /// # use tune::smf::SmfMessage;
+    /// let bytes = [
+    ///     b'M', b'T', b'h', b'd', 0, 0, 0, 6, // Header chunk
+    ///     0, 0, 0, 1, 0x01, 0xe0, // Format 0, 1 track, 480 ticks per quarter
+    ///     b'M', b'T', b'r', b'k', 0, 0, 0, 12, // Track chunk
+    ///     0x00, 0x90, 69, 100, // Note on after 0 ticks
+    ///     0x83, 0x60, 69, 0, // Note on (running status) after 480 ticks
+    ///     0x00, 0xff, 0x2f, 0x00, // End of track
+    /// ];
+    ///
+    /// let smf = Smf::read(&bytes[..]).unwrap();
+    ///
+    /// assert_eq!(smf.ticks_per_quarter, 480);
+    /// assert_eq!(smf.tracks.len(), 1);
+    /// assert_eq!(
+    ///     smf.tracks[0].events,
+    ///     [
+    ///         SmfEvent {
+    ///             ticks: 0,
+    ///             message: SmfMessage::Channel(
+    ///                 ChannelMessageType::NoteOn { key: 69, velocity: 100 }
+    ///                     .in_channel(0)
+    ///                     .unwrap()
+    ///             )
+    ///         },
+    ///         SmfEvent {
+    ///             ticks: 480,
+    ///             message: SmfMessage::Channel(
+    ///                 ChannelMessageType::NoteOn { key: 69, velocity: 0 }
+    ///                     .in_channel(0)
+    ///                     .unwrap()
+    ///             )
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn read(mut source: impl Read) -> Result<Smf, SmfReadError> {
+        let mut bytes = Vec::new();
+        source.read_to_end(&mut bytes)?;
+
+        let mut reader = ByteReader { bytes: &bytes };
+
+        if reader.take(4)? != HEADER_CHUNK {
+            return Err(SmfReadError::InvalidHeader);
+        }
+        let header_len = reader.read_u32()?;
+        let header = reader.take(header_len as usize)?;
+        let [_, _, _, _, division_msb, division_lsb, ..] = *header else {
+            return Err(SmfReadError::InvalidHeader);
+        };
+        let ticks_per_quarter = u16::from_be_bytes([division_msb, division_lsb]);
+        if ticks_per_quarter & 0x8000 != 0 {
+            return Err(SmfReadError::UnsupportedTimeFormat);
+        }
+
+        let mut tracks = Vec::new();
+        while !reader.bytes.is_empty() {
+            let chunk_type = reader.take(4)?;
+            let chunk_len = reader.read_u32()?;
+            let chunk_data = reader.take(chunk_len as usize)?;
+            if chunk_type == TRACK_CHUNK {
+                tracks.push(SmfTrack::decode(chunk_data)?);
+            }
+        }
+
+        Ok(Smf {
+            ticks_per_quarter,
+            tracks,
+        })
+    }
+
+    /// Writes the file in binary format to the given `target`.
+    ///
+    /// The events of each track are written in chronological order. Events with the same time stamp keep their order.
+    pub fn write(&self, mut target: impl Write) -> io::Result<()> {
+        let format: u16 = if self.tracks.len() == 1 { 0 } else { 1 };
+        let num_tracks = u16::try_from(self.tracks.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many tracks"))?;
+
+        target.write_all(HEADER_CHUNK)?;
+        target.write_all(&6u32.to_be_bytes())?;
+        target.write_all(&format.to_be_bytes())?;
+        target.write_all(&num_tracks.to_be_bytes())?;
+        target.write_all(&self.ticks_per_quarter.to_be_bytes())?;
+
+        for track in &self.tracks {
+            let track_data = track.encode();
+            let track_len = u32::try_from(track_data.len())
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Track too long"))?;
+
+            target.write_all(TRACK_CHUNK)?;
+            target.write_all(&track_len.to_be_bytes())?;
+            target.write_all(&track_data)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Smf {
+    /// Extracts the notes of all tracks and resolves their sounding pitches.
+    ///
+    /// The pitch of a note is determined by the tuning state of its channel at the time the note is started.
+    /// The following messages contribute to the tuning state:
+    ///
+    /// - Pitch-bend changes and the *Pitch Bend Sensitivity* RPN (default: 2 semitones)
+    /// - *Channel Fine Tuning* and *Channel Coarse Tuning* RPNs
+    /// - *Single Note Tuning Change* and *Bulk Tuning Dump* messages in combination with the *Tuning Program Change* and *Tuning Bank Select* RPNs
+    /// - *Scale/Octave Tuning* messages
+    ///
+    /// Events with the same time stamp are processed in track order. The notes are sorted by their start time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::mts;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::smf::Smf;
+    /// # use tune::smf::SmfEvent;
+    /// # use tune::smf::SmfMessage;
+    /// # use tune::smf::SmfTrack;
+    /// let channel_message = |ticks, message_type: ChannelMessageType| SmfEvent {
+    ///     ticks,
+    ///     message: SmfMessage::Channel(message_type.in_channel(0).unwrap()),
+    /// };
+    ///
+    /// let mut events: Vec<_> = mts::pitch_bend_sensitivity(0, Ratio::from_semitones(12))
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|message| channel_message(0, message.message_type()))
+    ///     .collect();
+    /// events.extend([
+    ///     channel_message(0, ChannelMessageType::PitchBendChange { value: 2048 }),
+    ///     channel_message(0, ChannelMessageType::NoteOn { key: 69, velocity: 100 }),
+    ///     channel_message(480, ChannelMessageType::NoteOff { key: 69, velocity: 100 }),
+    /// ]);
+    ///
+    /// let smf = Smf {
+    ///     ticks_per_quarter: 480,
+    ///     tracks: vec![SmfTrack { events }],
+    /// };
+    ///
+    /// let notes = smf.notes();
+    ///
+    /// assert_eq!(notes.len(), 1);
+    /// assert_eq!((notes[0].start_ticks, notes[0].end_ticks), (0, 480));
+    /// assert_eq!((notes[0].channel, notes[0].key, notes[0].velocity), (0, 69, 100));
+    /// assert_approx_eq!(notes[0].pitch.as_hz(), 440.0 * 2f64.powf(3.0 / 12.0)); // 2048 of 8192 = 3 semitones
+    /// ```
+    pub fn notes(&self) -> Vec<SmfNote> {
+        let mut events: Vec<_> = self.tracks.iter().flat_map(|track| &track.events).collect();
+        events.sort_by_key(|event| event.ticks);
+
+        let mut resolver = NoteResolver::default();
+        for event in &events {
+            resolver.process(event);
+        }
+
+        let last_ticks = events.last().map(|event| event.ticks).unwrap_or_default();
+        resolver
+            .notes
+            .into_iter()
+            .map(|(note, end_ticks)| SmfNote {
+                end_ticks: end_ticks.unwrap_or(last_ticks),
+                ..note
+            })
+            .collect()
+    }
+}
+
+impl SmfTrack {
+    fn decode(data: &[u8]) -> Result<SmfTrack, SmfReadError> {
+        let mut reader = ByteReader { bytes: data };
+        let mut events = Vec::new();
+        let mut curr_ticks = 0;
+        let mut running_status = None;
+
+        while !reader.bytes.is_empty() {
+            curr_ticks += reader.read_variable_length()?;
+
+            let status = match reader.bytes.first() {
+                Some(&status) if status >= 0x80 => {
+                    reader.take(1)?;
+                    status
+                }
+                _ => running_status.ok_or(SmfReadError::InvalidEvent)?,
+            };
+
+            let message = match status {
+                SYSEX_START => {
+                    running_status = None;
+                    let len = reader.read_variable_length()?;
+                    let mut message = vec![SYSEX_START];
+                    message.extend(reader.take(len as usize)?);
+                    Some(SmfMessage::SysEx(message))
+                }
+                SYSEX_ESCAPE => {
+                    running_status = None;
+                    let len = reader.read_variable_length()?;
+                    reader.take(len as usize)?;
+                    None
+                }
+                META_EVENT => {
+                    running_status = None;
+                    let meta_type = reader.take(1)?[0];
+                    let len = reader.read_variable_length()?;
+                    let data = reader.take(len as usize)?;
+                    match (meta_type, data) {
+                        (END_OF_TRACK, _) => break,
+                        (SET_TEMPO, &[b0, b1, b2]) => {
+                            Some(SmfMessage::Tempo(u32::from_be_bytes([0, b0, b1, b2])))
+                        }
+                        (TRACK_NAME, _) => Some(SmfMessage::TrackName(
+                            String::from_utf8_lossy(data).into_owned(),
+                        )),
+                        _ => Some(SmfMessage::Meta {
+                            meta_type,
+                            data: data.to_vec(),
+                        }),
+                    }
+                }
+                0x80..=0xef => {
+                    running_status = Some(status);
+                    let num_data_bytes = match status >> 4 {
+                        PROGRAM_CHANGE | CHANNEL_PRESSURE => 1,
+                        _ => 2,
+                    };
+                    let mut message = vec![status];
+                    message.extend(reader.take(num_data_bytes)?);
+                    Some(SmfMessage::Channel(
+                        ChannelMessage::from_raw_message(&message)
+                            .ok_or(SmfReadError::InvalidEvent)?,
+                    ))
+                }
+                _ => return Err(SmfReadError::InvalidEvent),
+            };
+
+            events.extend(message.map(|message| SmfEvent {
+                ticks: curr_ticks,
+                message,
+            }));
+        }
+
+        Ok(SmfTrack { events })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut events: Vec<_> = self.events.iter().collect();
+        events.sort_by_key(|event| event.ticks);
+
+        let mut data = Vec::new();
+        let mut curr_ticks = 0;
+
+        for event in events {
+            write_variable_length(&mut data, event.ticks - curr_ticks);
+            curr_ticks = event.ticks;
+
+            match &event.message {
+                SmfMessage::Channel(message) => {
+                    let raw_message = message.to_raw_message();
+                    match raw_message[0] >> 4 {
+                        PROGRAM_CHANGE | CHANNEL_PRESSURE => data.extend(&raw_message[..2]),
+                        _ => data.extend(raw_message),
+                    }
+                }
+                SmfMessage::SysEx(message) => {
+                    let payload = message.strip_prefix(&[SYSEX_START]).unwrap_or(message);
+                    data.push(SYSEX_START);
+                    write_variable_length(&mut data, payload.len() as u64);
+                    data.extend(payload);
+                }
+                SmfMessage::Tempo(micros_per_quarter) => {
+                    write_meta_event(&mut data, SET_TEMPO, &micros_per_quarter.to_be_bytes()[1..])
+                }
+                SmfMessage::TrackName(name) => {
+                    write_meta_event(&mut data, TRACK_NAME, name.as_bytes())
+                }
+                SmfMessage::Meta {
+                    meta_type,
+                    data: meta_data,
+                } => write_meta_event(&mut data, *meta_type, meta_data),
+            }
+        }
+
+        data.push(0);
+        write_meta_event(&mut data, END_OF_TRACK, &[]);
+
+        data
+    }
+}
+
+/// Reading a Standard MIDI File failed.
+#[derive(Debug)]
+pub enum SmfReadError {
+    IoError(io::Error),
+
+    /// The file does not start with a valid header chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::smf::Smf;
+    /// # use tune::smf::SmfReadError;
+    /// assert!(matches!(
+    ///     Smf::read(&b"RIFF"[..]),
+    ///     Err(SmfReadError::InvalidHeader)
+    /// ));
+    /// ```
+    InvalidHeader,
+
+    /// The file uses SMPTE-based time stamps instead of ticks per quarter note.
+    UnsupportedTimeFormat,
+
+    /// A chunk or an event is truncated.
+    UnexpectedEndOfData,
+
+    /// An event has an invalid status byte.
+    InvalidEvent,
+}
+
+impl From<io::Error> for SmfReadError {
+    fn from(v: io::Error) -> Self {
+        SmfReadError::IoError(v)
+    }
+}
+
+#[derive(Default)]
+struct NoteResolver {
+    channels: [ChannelTuning; 16],
+    tuning_tables: HashMap<(u8, u8, u8), Pitch>,
+    active_notes: HashMap<(u8, u8), VecDeque<usize>>,
+    notes: Vec<(SmfNote, Option<u64>)>,
+}
+
+impl NoteResolver {
+    fn process(&mut self, event: &SmfEvent) {
+        match &event.message {
+            SmfMessage::Channel(message) => {
+                self.process_channel_message(event.ticks, message.channel(), message.message_type())
+            }
+            SmfMessage::SysEx(sysex_bytes) => {
+                if let Ok(mts_message) = MtsMessage::parse(sysex_bytes) {
+                    self.process_mts_message(mts_message);
+                }
+            }
+            SmfMessage::Tempo(_) | SmfMessage::TrackName(_) | SmfMessage::Meta { .. } => {}
+        }
+    }
+
+    fn process_channel_message(
+        &mut self,
+        ticks: u64,
+        channel: u8,
+        message_type: ChannelMessageType,
+    ) {
+        match message_type {
+            ChannelMessageType::NoteOff { key, .. }
+            | ChannelMessageType::NoteOn { key, velocity: 0 } => {
+                if let Some(note_index) = self
+                    .active_notes
+                    .get_mut(&(channel, key))
+                    .and_then(VecDeque::pop_front)
+                {
+                    self.notes[note_index].1 = Some(ticks);
+                }
+            }
+            ChannelMessageType::NoteOn { key, velocity } => {
+                let pitch = self.pitch_of(channel, key);
+                self.active_notes
+                    .entry((channel, key))
+                    .or_default()
+                    .push_back(self.notes.len());
+                self.notes.push((
+                    SmfNote {
+                        start_ticks: ticks,
+                        end_ticks: ticks,
+                        channel,
+                        key,
+                        velocity,
+                        pitch,
+                    },
+                    None,
+                ));
+            }
+            ChannelMessageType::ControlChange { controller, value } => {
+                self.channels[usize::from(channel)].process_control_change(controller, value)
+            }
+            ChannelMessageType::PitchBendChange { value } => {
+                self.channels[usize::from(channel)].pitch_bend = value
+            }
+            ChannelMessageType::PolyphonicKeyPressure { .. }
+            | ChannelMessageType::ProgramChange { .. }
+            | ChannelMessageType::ChannelPressure { .. } => {}
+        }
+    }
+
+    fn process_mts_message(&mut self, mts_message: MtsMessage) {
+        let (tuning_bank, tuning_program, tuning_changes) = match mts_message {
+            MtsMessage::SingleNoteTuningChange {
+                tuning_bank,
+                tuning_program,
+                tuning_changes,
+                ..
+            } => (
+                tuning_bank.unwrap_or_default(),
+                tuning_program,
+                tuning_changes,
+            ),
+            MtsMessage::BulkTuningDump(bulk_dump) => {
+                (0, bulk_dump.tuning_program, bulk_dump.tuning_changes)
+            }
+            MtsMessage::ScaleOctaveTuning {
+                channels,
+                octave_tuning,
+                ..
+            } => {
+                for (channel, channel_tuning) in (0..).zip(&mut self.channels) {
+                    if match &channels {
+                        Channels::All => true,
+                        Channels::Some(channels) => channels.contains(&channel),
+                    } {
+                        channel_tuning.octave_tuning = octave_tuning.clone();
+                    }
+                }
+                return;
+            }
+            MtsMessage::BulkTuningDumpRequest(_) => return,
+        };
+
+        for tuning_change in tuning_changes {
+            if let Ok(key) = u8::try_from(tuning_change.key.midi_number()) {
+                self.tuning_tables.insert(
+                    (tuning_bank, tuning_program, key),
+                    tuning_change.target_pitch,
+                );
+            }
+        }
+    }
+
+    fn pitch_of(&mut self, channel: u8, key: u8) -> Pitch {
+        let channel_tuning = &mut self.channels[usize::from(channel)];
+        let note = Note::from_midi_number(key);
+
+        let base_pitch = match self.tuning_tables.get(&(
+            channel_tuning.tuning_bank,
+            channel_tuning.tuning_program,
+            key,
+        )) {
+            Some(&pitch) => pitch,
+            None => {
+                note.pitch()
+                    * *channel_tuning
+                        .octave_tuning
+                        .as_mut(note.letter_and_octave().0)
+            }
+        };
+
+        base_pitch
+            * channel_tuning.fine_tuning
+            * channel_tuning.coarse_tuning
+            * Ratio::from_semitones(
+                f64::from(channel_tuning.pitch_bend) / 8192.0
+                    * channel_tuning.pitch_bend_range.as_semitones(),
+            )
+    }
+}
+
+struct ChannelTuning {
+    rpn: (u8, u8),
+    data_entry: (u8, u8),
+    pitch_bend: i16,
+    pitch_bend_range: Ratio,
+    fine_tuning: Ratio,
+    coarse_tuning: Ratio,
+    tuning_bank: u8,
+    tuning_program: u8,
+    octave_tuning: ScaleOctaveTuning,
+}
+
+impl Default for ChannelTuning {
+    fn default() -> Self {
+        Self {
+            rpn: RPN_NULL,
+            data_entry: (0, 0),
+            pitch_bend: 0,
+            pitch_bend_range: Ratio::from_semitones(2),
+            fine_tuning: Ratio::default(),
+            coarse_tuning: Ratio::default(),
+            tuning_bank: 0,
+            tuning_program: 0,
+            octave_tuning: ScaleOctaveTuning::default(),
+        }
+    }
+}
+
+impl ChannelTuning {
+    fn process_control_change(&mut self, controller: u8, value: u8) {
+        match controller {
+            RPN_MSB => self.rpn.0 = value,
+            RPN_LSB => self.rpn.1 = value,
+            NRPN_MSB | NRPN_LSB => self.rpn = RPN_NULL,
+            DATA_ENTRY_MSB => {
+                self.data_entry = (value, 0);
+                self.apply_data_entry();
+            }
+            DATA_ENTRY_LSB => {
+                self.data_entry.1 = value;
+                self.apply_data_entry();
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_data_entry(&mut self) {
+        let (msb, lsb) = self.data_entry;
+
+        match self.rpn {
+            PITCH_BEND_SENSITIVITY => {
+                self.pitch_bend_range = Ratio::from_cents(f64::from(msb) * 100.0 + f64::from(lsb))
+            }
+            CHANNEL_FINE_TUNING => {
+                let value = u16::from(msb) << 7 | u16::from(lsb);
+                self.fine_tuning = Ratio::from_semitones(f64::from(value) / 8192.0 - 1.0)
+            }
+            CHANNEL_COARSE_TUNING => {
+                self.coarse_tuning = Ratio::from_semitones(f64::from(msb) - 64.0)
+            }
+            TUNING_PROGRAM_CHANGE => self.tuning_program = msb,
+            TUNING_BANK_SELECT => self.tuning_bank = msb,
+            _ => {}
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SmfReadError> {
+        if len > self.bytes.len() {
+            return Err(SmfReadError::UnexpectedEndOfData);
+        }
+        let (taken, remaining) = self.bytes.split_at(len);
+        self.bytes = remaining;
+        Ok(taken)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SmfReadError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_variable_length(&mut self) -> Result<u64, SmfReadError> {
+        let mut value = 0;
+        loop {
+            let septet = self.take(1)?[0];
+            value = (value << 7) | u64::from(septet & 0x7f);
+            if septet & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+fn write_meta_event(data: &mut Vec<u8>, meta_type: u8, payload: &[u8]) {
+    data.push(META_EVENT);
+    data.push(meta_type);
+    write_variable_length(data, payload.len() as u64);
+    data.extend(payload);
+}
+
+fn write_variable_length(data: &mut Vec<u8>, value: u64) {
+    let mut septets = vec![(value & 0x7f) as u8];
+    let mut remaining = value >> 7;
+    while remaining > 0 {
+        septets.push((remaining & 0x7f) as u8 | 0x80);
+        remaining >>= 7;
+    }
+    data.extend(septets.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use crate::{
+        mts::{
+            self, ScaleOctaveTuningMessage, ScaleOctaveTuningOptions, SingleNoteTuningChange,
+            SingleNoteTuningChangeMessage, SingleNoteTuningChangeOptions,
+        },
+        note::NoteLetter,
+    };
+
+    use super::*;
+
+    #[test]
+    fn variable_length_quantities() {
+        for (value, expected) in [
+            (0x00, &[0x00][..]),
+            (0x40, &[0x40]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x81, 0x00]),
+            (0x2000, &[0xc0, 0x00]),
+            (0x3fff, &[0xff, 0x7f]),
+            (0x4000, &[0x81, 0x80, 0x00]),
+            (0x0fffffff, &[0xff, 0xff, 0xff, 0x7f]),
+        ] {
+            let mut data = Vec::new();
+            write_variable_length(&mut data, value);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn read_variable_length_quantities() {
+        for bytes in [
+            &[0x00][..],
+            &[0x81, 0x00],
+            &[0xff, 0x7f],
+            &[0x81, 0x80, 0x00],
+        ] {
+            let mut data = Vec::new();
+            let value = ByteReader { bytes }.read_variable_length().unwrap();
+            write_variable_length(&mut data, value);
+            assert_eq!(data, bytes);
+        }
+
+        assert!(matches!(
+            ByteReader { bytes: &[0x81] }.read_variable_length(),
+            Err(SmfReadError::UnexpectedEndOfData)
+        ));
+    }
+
+    #[test]
+    fn write_and_read_multi_track_file() {
+        let smf = Smf {
+            ticks_per_quarter: 96,
+            tracks: vec![
+                SmfTrack {
+                    events: vec![
+                        SmfEvent {
+                            ticks: 0,
+                            message: SmfMessage::Tempo(500000),
+                        },
+                        SmfEvent {
+                            ticks: 10,
+                            message: SmfMessage::SysEx(vec![0xf0, 0x7e, 0x7f, 0xf7]),
+                        },
+                    ],
+                },
+                SmfTrack {
+                    events: vec![
+                        SmfEvent {
+                            ticks: 200,
+                            message: SmfMessage::Channel(
+                                ChannelMessageType::ProgramChange { program: 5 }
+                                    .in_channel(3)
+                                    .unwrap(),
+                            ),
+                        },
+                        SmfEvent {
+                            ticks: 100,
+                            message: SmfMessage::TrackName("Piano".to_owned()),
+                        },
+                    ],
+                },
+            ],
+        };
+
+        let mut bytes = Vec::new();
+        smf.write(&mut bytes).unwrap();
+
+        assert_eq!(
+            bytes,
+            [
+                b'M', b'T', b'h', b'd', 0, 0, 0, 6, // Header chunk
+                0, 1, 0, 2, 0, 96, // Format 1, 2 tracks, 96 ticks per quarter
+                b'M', b'T', b'r', b'k', 0, 0, 0, 17, // Track chunk
+                0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // Tempo
+                0x0a, 0xf0, 0x03, 0x7e, 0x7f, 0xf7, // SysEx
+                0x00, 0xff, 0x2f, 0x00, // End of track
+                b'M', b'T', b'r', b'k', 0, 0, 0, 16, // Track chunk
+                0x64, 0xff, 0x03, 0x05, b'P', b'i', b'a', b'n', b'o', // Track name
+                0x64, 0xc3, 0x05, // Program change
+                0x00, 0xff, 0x2f, 0x00, // End of track
+            ]
+        );
+
+        let mut expected_smf = smf.clone();
+        expected_smf.tracks[1].events.reverse();
+
+        assert_eq!(Smf::read(&bytes[..]).unwrap(), expected_smf);
+    }
+
+    #[test]
+    fn resolve_pitches_of_notes() {
+        let a4 = NoteLetter::A.in_octave(4).as_piano_key();
+
+        let mut events = Vec::new();
+        let mut add_channel_message = |ticks, channel, message_type: ChannelMessageType| {
+            events.push(SmfEvent {
+                ticks,
+                message: SmfMessage::Channel(message_type.in_channel(channel).unwrap()),
+            })
+        };
+
+        // Channel 0: Single Note Tuning Change in tuning program 1
+        for message in mts::tuning_program_change(0, 1).unwrap() {
+            add_channel_message(0, 0, message.message_type());
+        }
+        add_channel_message(
+            10,
+            0,
+            ChannelMessageType::NoteOn {
+                key: 69,
+                velocity: 100,
+            },
+        );
+        add_channel_message(
+            20,
+            0,
+            ChannelMessageType::NoteOff {
+                key: 69,
+                velocity: 100,
+            },
+        );
+
+        // Channel 1: Scale/Octave Tuning
+        add_channel_message(
+            10,
+            1,
+            ChannelMessageType::NoteOn {
+                key: 69,
+                velocity: 90,
+            },
+        );
+        add_channel_message(
+            20,
+            1,
+            ChannelMessageType::NoteOff {
+                key: 69,
+                velocity: 90,
+            },
+        );
+
+        // Channel 2: Channel Fine Tuning and pitch bend with default range
+        for message in mts::channel_fine_tuning(2, Ratio::from_cents(30.0)).unwrap() {
+            add_channel_message(0, 2, message.message_type());
+        }
+        add_channel_message(0, 2, ChannelMessageType::PitchBendChange { value: -4096 });
+        add_channel_message(
+            10,
+            2,
+            ChannelMessageType::NoteOn {
+                key: 69,
+                velocity: 80,
+            },
+        );
+        add_channel_message(
+            20,
+            2,
+            ChannelMessageType::NoteOff {
+                key: 69,
+                velocity: 80,
+            },
+        );
+
+        // Channel 3: Repeated and unreleased notes
+        add_channel_message(
+            30,
+            3,
+            ChannelMessageType::NoteOn {
+                key: 60,
+                velocity: 70,
+            },
+        );
+        add_channel_message(
+            40,
+            3,
+            ChannelMessageType::NoteOn {
+                key: 60,
+                velocity: 60,
+            },
+        );
+        add_channel_message(
+            50,
+            3,
+            ChannelMessageType::NoteOn {
+                key: 60,
+                velocity: 0,
+            },
+        );
+        add_channel_message(
+            60,
+            3,
+            ChannelMessageType::NoteOff {
+                key: 60,
+                velocity: 0,
+            },
+        );
+        add_channel_message(
+            70,
+            3,
+            ChannelMessageType::NoteOn {
+                key: 62,
+                velocity: 50,
+            },
+        );
+        add_channel_message(80, 3, ChannelMessageType::ProgramChange { program: 0 });
+
+        let single_note_tuning_change = SingleNoteTuningChangeMessage::from_tuning_changes(
+            &SingleNoteTuningChangeOptions {
+                tuning_program: 1,
+                ..Default::default()
+            },
+            [SingleNoteTuningChange {
+                key: a4,
+                target_pitch: Pitch::from_hz(445.0),
+            }],
+        )
+        .unwrap();
+
+        let scale_octave_tuning = ScaleOctaveTuningMessage::from_octave_tuning(
+            &ScaleOctaveTuningOptions {
+                channels: 1.into(),
+                ..Default::default()
+            },
+            &ScaleOctaveTuning {
+                a: Ratio::from_cents(-20.0),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let smf = Smf {
+            ticks_per_quarter: 96,
+            tracks: vec![
+                SmfTrack {
+                    events: vec![
+                        SmfEvent {
+                            ticks: 0,
+                            message: SmfMessage::SysEx(
+                                single_note_tuning_change
+                                    .sysex_bytes()
+                                    .next()
+                                    .unwrap()
+                                    .to_vec(),
+                            ),
+                        },
+                        SmfEvent {
+                            ticks: 0,
+                            message: SmfMessage::SysEx(scale_octave_tuning.sysex_bytes().to_vec()),
+                        },
+                    ],
+                },
+                SmfTrack { events },
+            ],
+        };
+
+        let notes = smf.notes();
+
+        assert_eq!(
+            notes
+                .iter()
+                .map(|note| (
+                    note.start_ticks,
+                    note.end_ticks,
+                    note.channel,
+                    note.key,
+                    note.velocity
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (10, 20, 0, 69, 100),
+                (10, 20, 1, 69, 90),
+                (10, 20, 2, 69, 80),
+                (30, 50, 3, 60, 70),
+                (40, 60, 3, 60, 60),
+                (70, 80, 3, 62, 50),
+            ]
+        );
+
+        assert_approx_eq!(notes[0].pitch.as_hz(), 445.0, 1e-3);
+        assert_approx_eq!(
+            Ratio::between_pitches(Pitch::from_hz(440.0), notes[1].pitch).as_cents(),
+            -20.0,
+            1e-3
+        );
+        assert_approx_eq!(
+            Ratio::between_pitches(Pitch::from_hz(440.0), notes[2].pitch).as_cents(),
+            30.0 - 100.0,
+            1e-1
+        );
+        assert_approx_eq!(
+            notes[3].pitch.as_hz(),
+            NoteLetter::C.in_octave(4).pitch().as_hz()
+        );
+    }
+}
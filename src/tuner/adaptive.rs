@@ -0,0 +1,437 @@
+use std::hash::Hash;
+
+use crate::pitch::{Pitch, Ratio};
+
+use super::{IsErr, JitTuner, PoolingMode, TunableSynth};
+
+/// Retunes the currently sounding notes toward just intervals.
+///
+/// The [`AdaptiveTuner`] is built on top of a [`JitTuner`]. Whenever a note is started or stopped, the pitches of all held notes are adapted using an [`AdaptiveTuningModel`].
+pub struct AdaptiveTuner<K, S> {
+    model: AdaptiveTuningModel<K>,
+    jit_tuner: JitTuner<K, S>,
+}
+
+impl<K, S: TunableSynth> AdaptiveTuner<K, S> {
+    /// Starts a new [`AdaptiveTuner`] with the given `synth` and `pooling_mode`.
+    ///
+    /// See [`AdaptiveTuningModel::new`] for the meaning of `odd_limit` and `max_drift`.
+    pub fn start(synth: S, pooling_mode: PoolingMode, odd_limit: u16, max_drift: Ratio) -> Self {
+        Self {
+            model: AdaptiveTuningModel::new(odd_limit, max_drift),
+            jit_tuner: JitTuner::start(synth, pooling_mode),
+        }
+    }
+}
+
+impl<K: Copy + Eq + Hash, S: TunableSynth> AdaptiveTuner<K, S> {
+    /// Starts a note with the given nominal `pitch` and adapts the pitches of all held notes.
+    pub fn note_on(&mut self, key: K, pitch: Pitch, attr: S::NoteAttr) -> S::Result {
+        for (adapted_key, adapted_pitch) in self.model.register_key(key, pitch) {
+            let result = if adapted_key == key {
+                self.jit_tuner.note_on(key, adapted_pitch, attr.clone())
+            } else {
+                self.jit_tuner.note_pitch(adapted_key, adapted_pitch)
+            };
+            if result.is_err() {
+                return result;
+            }
+        }
+        S::Result::ok()
+    }
+
+    /// Stops the note of the given `key` and adapts the pitches of the remaining notes.
+    pub fn note_off(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
+        let result = self.jit_tuner.note_off(key, attr);
+        if result.is_err() {
+            return result;
+        }
+        for (adapted_key, adapted_pitch) in self.model.deregister_key(key) {
+            let result = self.jit_tuner.note_pitch(adapted_key, adapted_pitch);
+            if result.is_err() {
+                return result;
+            }
+        }
+        S::Result::ok()
+    }
+
+    /// Sets a polyphonic attribute for the note with the given `key`.
+    pub fn note_attr(&mut self, key: K, attr: S::NoteAttr) -> S::Result {
+        self.jit_tuner.note_attr(key, attr)
+    }
+
+    /// Sets a channel-global attribute on the channel of the note with the given `key` only.
+    pub fn note_channel_attr(&mut self, key: K, attr: S::GlobalAttr) -> S::Result {
+        self.jit_tuner.note_channel_attr(key, attr)
+    }
+
+    /// Sets a channel-global attribute.
+    pub fn global_attr(&mut self, attr: S::GlobalAttr) -> S::Result {
+        self.jit_tuner.global_attr(attr)
+    }
+
+    /// Informs the tuner about the state of the sustain pedal.
+    pub fn sustain(&mut self, sustain: bool) {
+        self.jit_tuner.sustain(sustain)
+    }
+
+    /// Stops the current [`AdaptiveTuner`] yielding the consumed [`TunableSynth`] for future reuse.
+    pub fn stop(self) -> S {
+        self.jit_tuner.stop()
+    }
+}
+
+/// Computes pitches for a set of simultaneously sounding keys s.t. their intervals become just.
+///
+/// Every interval between two keys is approximated by the nearest fraction within the given odd limit.
+/// The pitches are then shifted s.t. the squared deviation from the just intervals becomes minimal while the average shift stays zero.
+/// The latter constraint compensates for the drift that would occur if the just intervals were stacked naively.
+/// Intervals that are already pure are never detuned.
+///
+/// # Examples
+///
+/// ```
+/// # use assert_approx_eq::assert_approx_eq;
+/// # use tune::pitch::Pitch;
+/// # use tune::pitch::Ratio;
+/// # use tune::tuner::AdaptiveTuningModel;
+/// let mut model = AdaptiveTuningModel::new(5, Ratio::from_cents(20.0));
+///
+/// let c4 = Pitch::from_hz(261.626);
+/// let e4 = c4 * Ratio::from_semitones(4);
+/// let g4 = c4 * Ratio::from_semitones(7);
+///
+/// model.register_key("C", c4);
+/// model.register_key("E", e4);
+/// model.register_key("G", g4);
+///
+/// let c4 = model.adapted_pitch("C").unwrap();
+/// let e4 = model.adapted_pitch("E").unwrap();
+/// let g4 = model.adapted_pitch("G").unwrap();
+///
+/// // The C major chord has become a just 4:5:6 chord
+/// assert_approx_eq!(Ratio::between_pitches(c4, e4).as_float(), 5.0 / 4.0);
+/// assert_approx_eq!(Ratio::between_pitches(c4, g4).as_float(), 6.0 / 4.0);
+///
+/// // No drift
+/// assert_approx_eq!(Ratio::between_pitches(Pitch::from_hz(261.626), c4).as_cents(), 3.910428);
+/// assert_approx_eq!(Ratio::between_pitches(Pitch::from_hz(261.626) * Ratio::from_semitones(4), e4).as_cents(), -9.775858);
+/// assert_approx_eq!(Ratio::between_pitches(Pitch::from_hz(261.626) * Ratio::from_semitones(7), g4).as_cents(), 5.865429);
+/// ```
+pub struct AdaptiveTuningModel<K> {
+    odd_limit: u16,
+    max_drift: Ratio,
+    keys: Vec<AdaptedKey<K>>,
+}
+
+struct AdaptedKey<K> {
+    key: K,
+    nominal_pitch: Pitch,
+    adapted_pitch: Pitch,
+}
+
+impl<K> AdaptiveTuningModel<K> {
+    /// Creates a new [`AdaptiveTuningModel`].
+    ///
+    /// Intervals are approximated by fractions within the given `odd_limit`.
+    /// Intervals that deviate by more than `max_drift` from their nearest fraction are considered non-just and are left untouched.
+    /// No pitch is shifted by more than `max_drift`. If required, the shifts of all keys connected by just intervals are scaled down together.
+    pub fn new(odd_limit: u16, max_drift: Ratio) -> Self {
+        Self {
+            odd_limit,
+            max_drift,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K: Copy + Eq> AdaptiveTuningModel<K> {
+    /// Registers a key with the given nominal `pitch` or updates the nominal pitch of an already registered key.
+    ///
+    /// Returns the adapted pitches of all keys whose pitch has changed. The newly registered key is always included.
+    pub fn register_key(&mut self, key: K, pitch: Pitch) -> Vec<(K, Pitch)> {
+        match self
+            .keys
+            .iter_mut()
+            .find(|adapted_key| adapted_key.key == key)
+        {
+            Some(adapted_key) => adapted_key.nominal_pitch = pitch,
+            None => self.keys.push(AdaptedKey {
+                key,
+                nominal_pitch: pitch,
+                adapted_pitch: pitch,
+            }),
+        }
+
+        self.adapt(Some(key))
+    }
+
+    /// Deregisters the given `key`.
+    ///
+    /// Returns the adapted pitches of all remaining keys whose pitch has changed.
+    pub fn deregister_key(&mut self, key: K) -> Vec<(K, Pitch)> {
+        self.keys.retain(|adapted_key| adapted_key.key != key);
+        self.adapt(None)
+    }
+
+    /// Returns the current adapted pitch of the given `key`.
+    pub fn adapted_pitch(&self, key: K) -> Option<Pitch> {
+        self.keys
+            .iter()
+            .find(|adapted_key| adapted_key.key == key)
+            .map(|adapted_key| adapted_key.adapted_pitch)
+    }
+
+    fn adapt(&mut self, new_key: Option<K>) -> Vec<(K, Pitch)> {
+        const PURE_INTERVAL_IN_CENTS: f64 = 0.01;
+        const MIN_CHANGE_IN_CENTS: f64 = 0.01;
+
+        let max_drift_in_cents = self.max_drift.as_cents().abs();
+        let num_keys = self.keys.len();
+
+        // Keys forming pure intervals are merged s.t. they are always shifted by the same amount
+        let mut merged = UnionFind::new(num_keys);
+        let mut just_intervals = Vec::new();
+        for (i, lower) in self.keys.iter().enumerate() {
+            for (j, upper) in self.keys.iter().enumerate().skip(i + 1) {
+                let interval = Ratio::between_pitches(lower.nominal_pitch, upper.nominal_pitch);
+                let deviation_in_cents = interval
+                    .nearest_fraction(self.odd_limit)
+                    .deviation
+                    .as_cents();
+
+                if deviation_in_cents.abs() < PURE_INTERVAL_IN_CENTS {
+                    merged.union(i, j);
+                } else if deviation_in_cents.abs() <= max_drift_in_cents {
+                    just_intervals.push((i, j, deviation_in_cents));
+                }
+            }
+        }
+
+        let mut connected = UnionFind::new(num_keys);
+        for i in 0..num_keys {
+            connected.union(i, merged.find(i));
+        }
+        for &(i, j, _) in &just_intervals {
+            connected.union(i, j);
+        }
+
+        // Least-squares fit of the shifts s.t. the intervals become just, i.e. L * x = b where L is the Laplacian of the graph of just intervals.
+        // Adding the outer product of the key counts of each connected group makes the system regular and keeps the average shift of each group at zero.
+        let mut system = vec![vec![0.0; num_keys + 1]; num_keys];
+        for &(i, j, deviation_in_cents) in &just_intervals {
+            let (i, j) = (merged.find(i), merged.find(j));
+            system[i][i] += 1.0;
+            system[j][j] += 1.0;
+            system[i][j] -= 1.0;
+            system[j][i] -= 1.0;
+            system[i][num_keys] += deviation_in_cents;
+            system[j][num_keys] -= deviation_in_cents;
+        }
+        let num_merged_keys = |root| (0..num_keys).filter(|&k| merged.find(k) == root).count();
+        for i in (0..num_keys).filter(|&i| merged.find(i) == i) {
+            for j in (0..num_keys).filter(|&j| merged.find(j) == j) {
+                if connected.find(i) == connected.find(j) {
+                    system[i][j] += (num_merged_keys(i) * num_merged_keys(j)) as f64;
+                }
+            }
+        }
+        for i in (0..num_keys).filter(|&i| merged.find(i) != i) {
+            // Merged keys are represented by their root
+            system[i][i] = 1.0;
+        }
+        let solution = solve(system);
+
+        // Scaling down the shifts of a whole group keeps its pure intervals pure
+        let scaling = |group| {
+            let max_shift_in_cents = (0..num_keys)
+                .filter(|&k| connected.find(k) == group)
+                .map(|k| solution[merged.find(k)].abs())
+                .fold(0.0, f64::max);
+            if max_shift_in_cents > max_drift_in_cents {
+                max_drift_in_cents / max_shift_in_cents
+            } else {
+                1.0
+            }
+        };
+        let shifts_in_cents: Vec<_> = (0..num_keys)
+            .map(|k| solution[merged.find(k)] * scaling(connected.find(k)))
+            .collect();
+
+        let mut updates = Vec::new();
+        for (adapted_key, shift_in_cents) in self.keys.iter_mut().zip(shifts_in_cents) {
+            let adapted_pitch = adapted_key.nominal_pitch * Ratio::from_cents(shift_in_cents);
+
+            if Some(adapted_key.key) == new_key
+                || Ratio::between_pitches(adapted_key.adapted_pitch, adapted_pitch)
+                    .as_cents()
+                    .abs()
+                    >= MIN_CHANGE_IN_CENTS
+            {
+                adapted_key.adapted_pitch = adapted_pitch;
+                updates.push((adapted_key.key, adapted_pitch));
+            }
+        }
+        updates
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(num_elements: usize) -> Self {
+        Self {
+            parents: (0..num_elements).collect(),
+        }
+    }
+
+    fn find(&self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            element = self.parents[element];
+        }
+        element
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        // The smaller index becomes the root
+        self.parents[a.max(b)] = a.min(b);
+    }
+}
+
+/// Solves the regular linear system given by the augmented matrix `system` using Gaussian elimination.
+fn solve(mut system: Vec<Vec<f64>>) -> Vec<f64> {
+    let size = system.len();
+
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))
+            .unwrap();
+        system.swap(column, pivot);
+
+        let (pivot_rows, lower_rows) = system.split_at_mut(column + 1);
+        let pivot_row = &pivot_rows[column];
+        for row in lower_rows {
+            let factor = row[column] / pivot_row[column];
+            for (target, source) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *target -= factor * source;
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size).map(|k| system[row][k] * solution[k]).sum();
+        solution[row] = (system[row][size] - sum) / system[row][row];
+    }
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn adapt_intervals() {
+        let mut model = AdaptiveTuningModel::new(7, Ratio::from_cents(15.0));
+
+        let a4 = Pitch::from_hz(440.0);
+
+        let updates = model.register_key("A", a4);
+        assert_eq!(updates.len(), 1);
+        assert_approx_eq!(updates[0].1.as_hz(), 440.0);
+
+        // Fifth: Both keys move by half the deviation
+        let updates = model.register_key("E", a4 * Ratio::from_semitones(7));
+        assert_eq!(updates.len(), 2);
+        assert_approx_eq!(pitch_of(&model, "A", a4), -0.977500);
+        assert_approx_eq!(
+            pitch_of(&model, "E", a4 * Ratio::from_semitones(7)),
+            0.977500
+        );
+        assert_approx_eq!(interval_of(&model, "A", "E"), 3.0 / 2.0);
+
+        // Minor seventh: Approximated by 7/4 but 31 cents off, i.e. not just. The pure fifth stays pure.
+        let updates = model.register_key("G", a4 * Ratio::from_semitones(10));
+        assert_eq!(updates.len(), 1);
+        assert_approx_eq!(pitch_of(&model, "A", a4), -0.977500);
+        assert_approx_eq!(
+            pitch_of(&model, "E", a4 * Ratio::from_semitones(7)),
+            0.977500
+        );
+        assert_approx_eq!(pitch_of(&model, "G", a4 * Ratio::from_semitones(10)), 0.0);
+        assert_approx_eq!(interval_of(&model, "A", "E"), 3.0 / 2.0);
+
+        // Unchanged pitches are not reported
+        let updates = model.deregister_key("G");
+        assert_eq!(updates.len(), 0);
+
+        // Octave: Both A keys are shifted by the same amount s.t. all intervals are pure
+        let updates = model.register_key("A'", a4 * Ratio::from_semitones(12));
+        assert_eq!(updates.len(), 3);
+        assert_approx_eq!(pitch_of(&model, "A", a4), -0.651667);
+        assert_approx_eq!(
+            pitch_of(&model, "E", a4 * Ratio::from_semitones(7)),
+            1.303333
+        );
+        assert_approx_eq!(
+            pitch_of(&model, "A'", a4 * Ratio::from_semitones(12)),
+            -0.651667
+        );
+        assert_approx_eq!(interval_of(&model, "A", "E"), 3.0 / 2.0);
+        assert_approx_eq!(interval_of(&model, "E", "A'"), 4.0 / 3.0);
+        assert_approx_eq!(interval_of(&model, "A", "A'"), 2.0);
+
+        let updates = model.deregister_key("A'");
+        assert_eq!(updates.len(), 2);
+        let updates = model.register_key("A'", a4 * Ratio::from_semitones(24));
+        assert_eq!(updates.len(), 3);
+        assert_approx_eq!(interval_of(&model, "A", "A'"), 4.0);
+
+        let updates = model.deregister_key("E");
+        assert_eq!(updates.len(), 2);
+        assert_approx_eq!(pitch_of(&model, "A", a4), 0.0);
+        assert_approx_eq!(pitch_of(&model, "A'", a4 * Ratio::from_semitones(24)), 0.0);
+    }
+
+    #[test]
+    fn limit_drift() {
+        let mut model = AdaptiveTuningModel::new(5, Ratio::from_cents(5.0));
+
+        let a4 = Pitch::from_hz(440.0);
+
+        model.register_key("A", a4);
+        model.register_key("C#", a4 * Ratio::from_semitones(4));
+
+        // The major third deviates by 13.7 cents, i.e. more than the max drift
+        assert_approx_eq!(pitch_of(&model, "A", a4), 0.0);
+        assert_approx_eq!(pitch_of(&model, "C#", a4 * Ratio::from_semitones(4)), 0.0);
+
+        let mut model = AdaptiveTuningModel::new(5, Ratio::from_cents(15.0));
+
+        model.register_key("A", a4);
+        model.register_key("C#", a4 * Ratio::from_semitones(4));
+
+        assert_approx_eq!(pitch_of(&model, "A", a4), 6.843143);
+        assert_approx_eq!(
+            pitch_of(&model, "C#", a4 * Ratio::from_semitones(4)),
+            -6.843143
+        );
+    }
+
+    fn interval_of(model: &AdaptiveTuningModel<&str>, lower: &str, upper: &str) -> f64 {
+        Ratio::between_pitches(
+            model.adapted_pitch(lower).unwrap(),
+            model.adapted_pitch(upper).unwrap(),
+        )
+        .as_float()
+    }
+
+    fn pitch_of(model: &AdaptiveTuningModel<&str>, key: &str, nominal_pitch: Pitch) -> f64 {
+        Ratio::between_pitches(nominal_pitch, model.adapted_pitch(key).unwrap()).as_cents()
+    }
+}
@@ -92,6 +92,16 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> AotTuner<K, S> {
         S::Result::ok()
     }
 
+    /// Sets a channel-global attribute on the channel of the note with the given `key` only.
+    ///
+    /// This is useful for synthesizers that interpret channel messages as per-note expressions, e.g. MPE synthesizers.
+    pub fn note_channel_attr(&mut self, key: K, attr: S::GlobalAttr) -> S::Result {
+        if let Some((channel, _)) = self.model.get_channel_and_note_for_key(key) {
+            return self.synth.channel_attr(channel, attr);
+        }
+        S::Result::ok()
+    }
+
     /// Sets a channel-global attribute.
     pub fn global_attr(&mut self, attr: S::GlobalAttr) -> S::Result {
         self.synth.global_attr(attr)
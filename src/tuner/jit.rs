@@ -1,6 +1,7 @@
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
     hash::Hash,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -31,14 +32,15 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
     ///
     /// `key` is used as identifier for currently sounding notes.
     pub fn note_on(&mut self, key: K, pitch: Pitch, attr: S::NoteAttr) -> S::Result {
-        match self.model.register_key(key, pitch) {
+        let velocity = self.synth.note_velocity(&attr);
+        match self.model.register_key_with_velocity(key, pitch, velocity) {
             RegisterKeyResult::Accepted {
                 channel,
-                stopped_note,
+                stopped_notes,
                 started_note,
                 detuning,
             } => {
-                if let Some(stopped_note) = stopped_note {
+                for stopped_note in stopped_notes {
                     let result = self.synth.note_off(channel, stopped_note, attr.clone());
                     if result.is_err() {
                         return result;
@@ -69,7 +71,7 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
 
     /// Updates the note of `key` with the given `pitch`.
     pub fn note_pitch(&mut self, key: K, pitch: Pitch) -> S::Result {
-        match self.model.access_key(key) {
+        match self.model.update_key(key, pitch) {
             AccessKeyResult::Found {
                 channel,
                 found_note,
@@ -92,11 +94,49 @@ impl<K: Copy + Eq + Hash, S: TunableSynth> JitTuner<K, S> {
         }
     }
 
+    /// Sets a channel-global attribute on the channel of the note with the given `key` only.
+    ///
+    /// This is useful for synthesizers that interpret channel messages as per-note expressions, e.g. MPE synthesizers.
+    pub fn note_channel_attr(&mut self, key: K, attr: S::GlobalAttr) -> S::Result {
+        match self.model.access_key(key) {
+            AccessKeyResult::Found { channel, .. } => self.synth.channel_attr(channel, attr),
+            AccessKeyResult::NotFound => S::Result::ok(),
+        }
+    }
+
     /// Sets a channel-global attribute.
     pub fn global_attr(&mut self, attr: S::GlobalAttr) -> S::Result {
         self.synth.global_attr(attr)
     }
 
+    /// Allows new notes to share a channel with notes of a similar detuning.
+    ///
+    /// See [`JitTuningModel::set_tolerance`].
+    pub fn set_tolerance(&mut self, tolerance: Option<Ratio>) {
+        self.model.set_tolerance(tolerance)
+    }
+
+    /// Keeps the channels of released notes reserved for the duration of their release tails.
+    ///
+    /// See [`JitTuningModel::set_release_time`].
+    pub fn set_release_time(&mut self, release_time: Option<Duration>) {
+        self.model.set_release_time(release_time)
+    }
+
+    /// Sets the time at which the following notes are started or stopped.
+    ///
+    /// See [`JitTuningModel::set_time`].
+    pub fn set_time(&mut self, time: Option<Instant>) {
+        self.model.set_time(time)
+    }
+
+    /// Informs the tuner about the state of the sustain pedal.
+    ///
+    /// See [`PoolingMode::StopReleased`].
+    pub fn sustain(&mut self, sustain: bool) {
+        self.model.set_sustain(sustain)
+    }
+
     /// Stops the current [`JitTuner`] yielding the consumed [`TunableSynth`] for future reuse.
     pub fn stop(mut self) -> S {
         let active_keys: Vec<_> = self.model.active_keys().collect();
@@ -116,6 +156,10 @@ pub struct JitTuningModel<K> {
     num_channels: usize,
     group_by: GroupBy,
     pooling_mode: PoolingMode,
+    tolerance: Option<Ratio>,
+    release_time: Option<Duration>,
+    time: Option<Instant>,
+    sustain: bool,
     pools: HashMap<Group, JitPool<K, usize, Note>>,
     groups: HashMap<K, Group>,
 }
@@ -126,6 +170,10 @@ impl<K> JitTuningModel<K> {
             num_channels,
             group_by,
             pooling_mode,
+            tolerance: None,
+            release_time: None,
+            time: None,
+            sustain: false,
             pools: HashMap::new(),
             groups: HashMap::new(),
         }
@@ -133,7 +181,22 @@ impl<K> JitTuningModel<K> {
 }
 
 impl<K: Copy + Eq + Hash> JitTuningModel<K> {
+    /// Registers a key with the given `pitch`.
+    ///
+    /// All keys registered this way are considered equally loud. Use [`JitTuningModel::register_key_with_velocity`] if [`PoolingMode::StopQuietest`] is used.
     pub fn register_key(&mut self, key: K, pitch: Pitch) -> RegisterKeyResult {
+        self.register_key_with_velocity(key, pitch, 0)
+    }
+
+    /// Registers a key with the given `pitch` and `velocity`.
+    ///
+    /// `velocity` is only taken into account by [`PoolingMode::StopQuietest`].
+    pub fn register_key_with_velocity(
+        &mut self,
+        key: K,
+        pitch: Pitch,
+        velocity: u8,
+    ) -> RegisterKeyResult {
         let Approximation {
             approx_value,
             deviation,
@@ -141,22 +204,32 @@ impl<K: Copy + Eq + Hash> JitTuningModel<K> {
 
         let group = self.group_by.group(approx_value);
 
-        let pool = self
-            .pools
-            .entry(group)
-            .or_insert_with(|| JitPool::new(self.pooling_mode, 0..self.num_channels));
+        let pool = self.pools.entry(group).or_insert_with(|| {
+            let mut pool = JitPool::new(self.pooling_mode, 0..self.num_channels);
+            pool.tolerance = self.tolerance;
+            pool.release_time = self.release_time;
+            pool.time = self.time;
+            pool.set_sustain(self.sustain);
+            pool
+        });
+
+        let props = KeyProps {
+            pitch,
+            velocity,
+            detuning: deviation,
+        };
 
-        match pool.key_pressed(key, approx_value) {
+        match pool.key_pressed(key, approx_value, props) {
             Some((channel, stopped)) => {
                 self.groups.insert(key, group);
-                if let Some(stopped) = stopped {
-                    self.groups.remove(&stopped.0);
+                for (stopped_key, _) in &stopped {
+                    self.groups.remove(stopped_key);
                 }
                 RegisterKeyResult::Accepted {
-                    stopped_note: stopped.map(|(_, note)| note),
+                    stopped_notes: stopped.into_iter().map(|(_, note)| note).collect(),
                     started_note: approx_value,
                     channel,
-                    detuning: deviation,
+                    detuning: pool.find_detuning(key).unwrap_or(deviation),
                 }
             }
             None => RegisterKeyResult::Rejected,
@@ -182,6 +255,24 @@ impl<K: Copy + Eq + Hash> JitTuningModel<K> {
         }
     }
 
+    /// Updates the pitch of an already registered key.
+    ///
+    /// The detuning of the key's channel is updated s.t. new keys are only shared with the channel if they match the new detuning.
+    pub fn update_key(&mut self, key: K, pitch: Pitch) -> AccessKeyResult {
+        match self
+            .groups
+            .get(&key)
+            .and_then(|group| self.pools.get_mut(group))
+            .and_then(|pool| pool.key_updated(key, pitch, |note: Note| note.pitch()))
+        {
+            Some((channel, found_note)) => AccessKeyResult::Found {
+                found_note,
+                channel,
+            },
+            None => AccessKeyResult::NotFound,
+        }
+    }
+
     pub fn access_key(&self, key: K) -> AccessKeyResult {
         match self
             .groups
@@ -197,6 +288,113 @@ impl<K: Copy + Eq + Hash> JitTuningModel<K> {
         }
     }
 
+    /// Allows new keys to share a channel with keys whose detuning deviates by at most `tolerance`.
+    ///
+    /// This trades a small pitch error for a lower channel consumption since the new key adopts the detuning of the shared channel.
+    /// A value of [`None`] (default) disables channel sharing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuner::GroupBy;
+    /// # use tune::tuner::JitTuningModel;
+    /// # use tune::tuner::PoolingMode;
+    /// # use tune::tuner::RegisterKeyResult;
+    /// let mut model = JitTuningModel::new(16, GroupBy::Channel, PoolingMode::Stop);
+    /// model.set_tolerance(Some(Ratio::from_cents(1.0)));
+    ///
+    /// let channel_and_detuning = |result| match result {
+    ///     RegisterKeyResult::Accepted { channel, detuning, .. } => (channel, detuning.as_cents()),
+    ///     RegisterKeyResult::Rejected => unreachable!(),
+    /// };
+    ///
+    /// let a4 = Pitch::from_hz(440.0);
+    ///
+    /// let (channel_1, detuning_1) =
+    ///     channel_and_detuning(model.register_key(1, a4 * Ratio::from_cents(10.0)));
+    /// let (channel_2, detuning_2) =
+    ///     channel_and_detuning(model.register_key(2, a4 * Ratio::from_cents(710.5)));
+    /// let (channel_3, _) =
+    ///     channel_and_detuning(model.register_key(3, a4 * Ratio::from_cents(420.0)));
+    ///
+    /// assert_eq!(channel_1, channel_2);
+    /// assert_ne!(channel_1, channel_3);
+    /// assert!((detuning_1 - 10.0).abs() < 1e-6);
+    /// assert!((detuning_2 - 10.0).abs() < 1e-6);
+    /// ```
+    pub fn set_tolerance(&mut self, tolerance: Option<Ratio>) {
+        self.tolerance = tolerance;
+        for pool in self.pools.values_mut() {
+            pool.tolerance = tolerance;
+        }
+    }
+
+    /// Keeps the channel of a released key reserved for `release_time` s.t. the release tail of the note is not affected by tuning changes.
+    ///
+    /// Channels that only hold release tails are handed over to new keys of a different tuning only if no other channel is free.
+    /// A value of [`None`] (default) frees channels immediately.
+    ///
+    /// The reservation is purely time-based since MIDI targets do not report when their voices stop sounding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuner::GroupBy;
+    /// # use tune::tuner::JitTuningModel;
+    /// # use tune::tuner::PoolingMode;
+    /// # use tune::tuner::RegisterKeyResult;
+    /// let mut model = JitTuningModel::new(2, GroupBy::Channel, PoolingMode::Stop);
+    /// model.set_release_time(Some(Duration::from_secs(3600)));
+    ///
+    /// let channel = |result| match result {
+    ///     RegisterKeyResult::Accepted { channel, .. } => channel,
+    ///     RegisterKeyResult::Rejected => unreachable!(),
+    /// };
+    ///
+    /// let a4 = Pitch::from_hz(440.0);
+    ///
+    /// assert_eq!(channel(model.register_key(1, a4 * Ratio::from_cents(10.0))), 0);
+    /// model.deregister_key(1);
+    ///
+    /// // Channel 0 is reserved for the release tail of key 1
+    /// assert_eq!(channel(model.register_key(2, a4 * Ratio::from_cents(20.0))), 1);
+    ///
+    /// // No more free channels: Use the channel of the release tail
+    /// assert_eq!(channel(model.register_key(3, a4 * Ratio::from_cents(30.0))), 0);
+    /// ```
+    pub fn set_release_time(&mut self, release_time: Option<Duration>) {
+        self.release_time = release_time;
+        for pool in self.pools.values_mut() {
+            pool.release_time = release_time;
+        }
+    }
+
+    /// Sets the time at which the following keys are registered or deregistered.
+    ///
+    /// This is useful for tracking release tails when the keys are processed faster than real time, e.g. when reading them from a file.
+    /// A value of [`None`] (default) uses the system clock.
+    pub fn set_time(&mut self, time: Option<Instant>) {
+        self.time = time;
+        for pool in self.pools.values_mut() {
+            pool.time = time;
+        }
+    }
+
+    /// Informs the model about the state of the sustain pedal.
+    ///
+    /// When the pedal is lifted, all channels that are kept for released keys are freed or start their release tail.
+    pub fn set_sustain(&mut self, sustain: bool) {
+        self.sustain = sustain;
+        for pool in self.pools.values_mut() {
+            pool.set_sustain(sustain);
+        }
+    }
+
     pub fn active_keys(&self) -> impl Iterator<Item = K> + '_ {
         self.pools.values().flat_map(|pool| pool.active_keys())
     }
@@ -205,12 +403,12 @@ impl<K: Copy + Eq + Hash> JitTuningModel<K> {
 /// Reports the channel, [`Note`] and detuning of a newly registered key.
 ///
 /// If the key cannot be registered [`RegisterKeyResult::Rejected`] is returned.
-/// If the new key requires a registered note to be stopped `stopped_note` is [`Option::Some`].
+/// If the new key requires registered notes to be stopped `stopped_notes` is non-empty.
 
 pub enum RegisterKeyResult {
     Accepted {
         channel: usize,
-        stopped_note: Option<Note>,
+        stopped_notes: Vec<Note>,
         started_note: Note,
         detuning: Ratio,
     },
@@ -225,26 +423,75 @@ pub enum AccessKeyResult {
     NotFound,
 }
 
+/// Defines what to do when the channel pool is full and a new key cannot be registered.
+#[derive(Clone, Copy, Debug)]
+pub enum PoolingMode {
+    /// Do not accept the new key.
+    Block,
+    /// Stop the oldest key and accept the new key.
+    Stop,
+    /// Neither block nor stop. Accept that an old key receives an arbitrary tuning update.
+    Ignore,
+    /// Stop the key with the lowest velocity. Among equally quiet keys, the oldest one is stopped.
+    StopQuietest,
+    /// Keys released while the sustain pedal is held keep their channels s.t. their tuning stays intact.
+    /// These keys are stopped first. If there are none, the oldest key is stopped.
+    StopReleased,
+    /// Stop the key that is closest in pitch to the new key.
+    StopClosest,
+    /// Let the new key share the channel of a key whose detuning deviates by at most `tolerance`.
+    /// The new key adopts the detuning of that channel. If no such channel exists, the oldest key is stopped.
+    Reuse { tolerance: Ratio },
+}
+
 struct JitPool<K, C, N> {
     mode: PoolingMode,
+    tolerance: Option<Ratio>,
+    release_time: Option<Duration>,
+    time: Option<Instant>,
+    sustain: bool,
     free: VecDeque<C>,
-    tuned: BTreeMap<u64, K>, // Insertion order is conserved
+    tuned: BTreeMap<u64, Usage<K, C, N>>, // Insertion order is conserved
     active: HashMap<K, (u64, C, N)>,
     curr_usage_id: u64,
 }
 
-/// Defines what to do when the channel pool is full and a new key cannot be registered.
-#[derive(Clone, Copy, Debug)]
-pub enum PoolingMode {
-    Block,
-    Stop,
-    Ignore,
+struct Usage<K, C, N> {
+    channel: C,
+    detuning: Ratio,
+    voices: Vec<Voice<K, N>>,
 }
 
-impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
+struct Voice<K, N> {
+    key: K,
+    note: N,
+    pitch: Pitch,
+    velocity: u8,
+    state: VoiceState,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum VoiceState {
+    Pressed,
+    Sustained,
+    Releasing(Instant),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct KeyProps {
+    pitch: Pitch,
+    velocity: u8,
+    detuning: Ratio,
+}
+
+impl<K: Copy + Eq + Hash, C: Copy, N: Copy + PartialEq> JitPool<K, C, N> {
     fn new(mode: PoolingMode, channels: impl IntoIterator<Item = C>) -> Self {
         Self {
             mode,
+            tolerance: None,
+            release_time: None,
+            time: None,
+            sustain: false,
             free: VecDeque::from_iter(channels),
             tuned: BTreeMap::new(),
             active: HashMap::new(),
@@ -252,33 +499,146 @@ impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
         }
     }
 
-    fn key_pressed(&mut self, key: K, note: N) -> Option<(C, Option<(K, N)>)> {
-        if let Some(channel) = self.try_insert(key, note) {
-            return Some((channel, None));
+    fn key_pressed(&mut self, key: K, note: N, props: KeyProps) -> Option<(C, Vec<(K, N)>)> {
+        if self.release_time.is_some() {
+            self.expire_release_tails(self.now());
+        }
+
+        if let Some(channel) = self
+            .tolerance
+            .and_then(|tolerance| self.try_share(key, note, props, tolerance))
+        {
+            return Some((channel, Vec::new()));
+        }
+
+        if let Some(channel) = self.try_insert(key, note, props) {
+            return Some((channel, Vec::new()));
+        }
+
+        // Channels that only hold released keys are sacrificed first unless stealing is blocked
+        if !matches!(self.mode, PoolingMode::Block) {
+            if let Some(usage_id) = self.find_released_usage() {
+                return self.steal(usage_id, key, note, props);
+            }
         }
 
         match self.mode {
             PoolingMode::Block => None,
-            PoolingMode::Stop => self.find_old_key().map(|(channel, old_key, old_location)| {
-                self.key_released(old_key);
-                self.try_insert(key, note).unwrap();
-                (channel, Some((old_key, old_location)))
-            }),
-            PoolingMode::Ignore => self.find_old_key().map(|(channel, old_key, _)| {
-                self.weaken_key(old_key);
-                self.try_insert(key, note).unwrap();
-                (channel, None)
-            }),
+            PoolingMode::Stop | PoolingMode::StopReleased => {
+                self.steal(self.find_oldest_usage()?, key, note, props)
+            }
+            PoolingMode::Ignore => {
+                let usage_id = self.find_oldest_usage()?;
+                self.free_usage(usage_id);
+                Some((self.try_insert(key, note, props).unwrap(), Vec::new()))
+            }
+            PoolingMode::StopQuietest => self.steal(self.find_quietest_usage()?, key, note, props),
+            PoolingMode::StopClosest => {
+                self.steal(self.find_closest_usage(props.pitch)?, key, note, props)
+            }
+            PoolingMode::Reuse { tolerance } => {
+                if let Some(channel) = self.try_share(key, note, props, tolerance) {
+                    return Some((channel, Vec::new()));
+                }
+                self.steal(self.find_oldest_usage()?, key, note, props)
+            }
         }
     }
 
     fn key_released(&mut self, key: K) -> Option<(C, N)> {
-        self.active
-            .remove(&key)
-            .map(|(usage_id, freed_channel, location)| {
-                self.free_key(usage_id, freed_channel);
-                (freed_channel, location)
-            })
+        let (usage_id, freed_channel, location) = self.active.remove(&key)?;
+
+        let new_state = if self.sustain && matches!(self.mode, PoolingMode::StopReleased) {
+            Some(VoiceState::Sustained)
+        } else {
+            self.release_time
+                .map(|release_time| VoiceState::Releasing(self.now() + release_time))
+        };
+
+        if let Some(usage) = self.tuned.get_mut(&usage_id) {
+            match new_state {
+                Some(new_state) => {
+                    for voice in &mut usage.voices {
+                        if voice.key == key && voice.state == VoiceState::Pressed {
+                            voice.state = new_state;
+                        }
+                    }
+                }
+                None => usage
+                    .voices
+                    .retain(|voice| voice.key != key || voice.state != VoiceState::Pressed),
+            }
+
+            if usage.voices.is_empty() {
+                self.free_usage(usage_id);
+            }
+        }
+
+        Some((freed_channel, location))
+    }
+
+    fn key_updated(
+        &mut self,
+        key: K,
+        pitch: Pitch,
+        pitch_of: impl Fn(N) -> Pitch,
+    ) -> Option<(C, N)> {
+        let &(usage_id, channel, location) = self.active.get(&key)?;
+
+        if let Some(usage) = self.tuned.get_mut(&usage_id) {
+            usage.detuning = Ratio::between_pitches(pitch_of(location), pitch);
+            for voice in &mut usage.voices {
+                if voice.key == key && voice.state == VoiceState::Pressed {
+                    voice.pitch = pitch;
+                }
+            }
+        }
+
+        Some((channel, location))
+    }
+
+    fn set_sustain(&mut self, sustain: bool) {
+        self.sustain = sustain;
+
+        if sustain {
+            return;
+        }
+
+        match self.release_time {
+            Some(release_time) => {
+                let until = self.now() + release_time;
+                for usage in self.tuned.values_mut() {
+                    for voice in &mut usage.voices {
+                        if voice.state == VoiceState::Sustained {
+                            voice.state = VoiceState::Releasing(until);
+                        }
+                    }
+                }
+            }
+            None => self.remove_voices(|voice| voice.state == VoiceState::Sustained),
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.time.unwrap_or_else(Instant::now)
+    }
+
+    fn expire_release_tails(&mut self, now: Instant) {
+        self.remove_voices(
+            |voice| matches!(voice.state, VoiceState::Releasing(until) if until <= now),
+        );
+    }
+
+    fn remove_voices(&mut self, mut predicate: impl FnMut(&Voice<K, N>) -> bool) {
+        let free = &mut self.free;
+        self.tuned.retain(|_, usage| {
+            usage.voices.retain(|voice| !predicate(voice));
+            let is_empty = usage.voices.is_empty();
+            if is_empty {
+                free.push_back(usage.channel);
+            }
+            !is_empty
+        });
     }
 
     fn find_key(&self, key: K) -> Option<(C, N)> {
@@ -287,34 +647,122 @@ impl<K: Copy + Eq + Hash, C: Copy, N: Copy> JitPool<K, C, N> {
             .map(|&(_, channel, location)| (channel, location))
     }
 
+    fn find_detuning(&self, key: K) -> Option<Ratio> {
+        let (usage_id, _, _) = self.active.get(&key)?;
+        self.tuned.get(usage_id).map(|usage| usage.detuning)
+    }
+
     fn active_keys(&self) -> impl Iterator<Item = K> + '_ {
         self.active.keys().copied()
     }
 
-    fn try_insert(&mut self, key: K, note: N) -> Option<C> {
+    fn try_insert(&mut self, key: K, note: N, props: KeyProps) -> Option<C> {
         let free_channel = self.free.pop_front()?;
-        self.tuned.insert(self.curr_usage_id, key);
+        self.tuned.insert(
+            self.curr_usage_id,
+            Usage {
+                channel: free_channel,
+                detuning: props.detuning,
+                voices: vec![Voice::new(key, note, props)],
+            },
+        );
         self.active
             .insert(key, (self.curr_usage_id, free_channel, note));
         self.curr_usage_id += 1;
         Some(free_channel)
     }
 
-    fn find_old_key(&mut self) -> Option<(C, K, N)> {
-        let key = *self.tuned.values().next()?;
-        let &(_, channel, location) = self.active.get(&key)?;
-        Some((channel, key, location))
+    fn try_share(&mut self, key: K, note: N, props: KeyProps, tolerance: Ratio) -> Option<C> {
+        let (&usage_id, usage) = self.tuned.iter_mut().find(|(_, usage)| {
+            (usage.detuning.as_cents() - props.detuning.as_cents()).abs()
+                <= tolerance.as_cents().abs()
+                && usage.voices.iter().all(|voice| voice.note != note)
+        })?;
+        usage.voices.push(Voice::new(key, note, props));
+        self.active.insert(key, (usage_id, usage.channel, note));
+        Some(usage.channel)
     }
 
-    fn weaken_key(&mut self, key: K) {
-        if let Some(&(usage_id, freed_channel, _)) = self.active.get(&key) {
-            self.free_key(usage_id, freed_channel);
+    fn steal(
+        &mut self,
+        usage_id: u64,
+        key: K,
+        note: N,
+        props: KeyProps,
+    ) -> Option<(C, Vec<(K, N)>)> {
+        let usage = self.tuned.get(&usage_id)?;
+
+        let stopped: Vec<_> = usage
+            .voices
+            .iter()
+            .filter(|voice| voice.state == VoiceState::Pressed)
+            .filter(
+                |voice| matches!(self.active.get(&voice.key), Some(&(id, _, _)) if id == usage_id),
+            )
+            .map(|voice| (voice.key, voice.note))
+            .collect();
+
+        for (stopped_key, _) in &stopped {
+            self.active.remove(stopped_key);
         }
+        self.free_usage(usage_id);
+
+        Some((self.try_insert(key, note, props).unwrap(), stopped))
     }
 
-    fn free_key(&mut self, usage_id: u64, freed_channel: C) {
-        if self.tuned.remove(&usage_id).is_some() {
-            self.free.push_back(freed_channel);
+    fn find_oldest_usage(&self) -> Option<u64> {
+        self.tuned.keys().next().copied()
+    }
+
+    fn find_quietest_usage(&self) -> Option<u64> {
+        self.tuned
+            .iter()
+            .min_by_key(|(_, usage)| usage.voices.iter().map(|voice| voice.velocity).max())
+            .map(|(&usage_id, _)| usage_id)
+    }
+
+    fn find_released_usage(&self) -> Option<u64> {
+        self.tuned
+            .iter()
+            .find(|(_, usage)| {
+                usage
+                    .voices
+                    .iter()
+                    .all(|voice| voice.state != VoiceState::Pressed)
+            })
+            .map(|(&usage_id, _)| usage_id)
+    }
+
+    fn find_closest_usage(&self, pitch: Pitch) -> Option<u64> {
+        let distance = |usage: &Usage<K, C, N>| {
+            usage
+                .voices
+                .iter()
+                .map(|voice| Ratio::between_pitches(voice.pitch, pitch).as_cents().abs())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        self.tuned
+            .iter()
+            .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+            .map(|(&usage_id, _)| usage_id)
+    }
+
+    fn free_usage(&mut self, usage_id: u64) {
+        if let Some(usage) = self.tuned.remove(&usage_id) {
+            self.free.push_back(usage.channel);
+        }
+    }
+}
+
+impl<K, N> Voice<K, N> {
+    fn new(key: K, note: N, props: KeyProps) -> Self {
+        Self {
+            key,
+            note,
+            pitch: props.pitch,
+            velocity: props.velocity,
+            state: VoiceState::Pressed,
         }
     }
 }
@@ -327,10 +775,10 @@ mod tests {
     fn pooling_mode_block() {
         let mut pool = JitPool::new(PoolingMode::Block, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
-        assert_eq!(pool.key_pressed("keyD", "locD"), None);
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((2, vec![])));
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), None);
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), Some((1, "locB")));
@@ -338,8 +786,8 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), None);
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyE", "locE"), None);
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((1, vec![])));
+        assert_eq!(pool.key_pressed("keyE", "locE", props()), None);
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), None);
@@ -364,12 +812,12 @@ mod tests {
     fn pooling_mode_stop() {
         let mut pool = JitPool::new(PoolingMode::Stop, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((2, vec![])));
         assert_eq!(
-            pool.key_pressed("keyD", "locD"),
-            Some((0, Some(("keyA", "locA"))))
+            pool.key_pressed("keyD", "locD", props()),
+            Some((0, vec![("keyA", "locA")]))
         );
 
         assert_eq!(pool.find_key("keyA"), None);
@@ -378,10 +826,10 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), Some((0, "locD")));
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((1, vec![])));
         assert_eq!(
-            pool.key_pressed("keyE", "locE"),
-            Some((2, Some(("keyC", "locC"))))
+            pool.key_pressed("keyE", "locE", props()),
+            Some((2, vec![("keyC", "locC")]))
         );
 
         assert_eq!(pool.find_key("keyA"), None);
@@ -407,10 +855,10 @@ mod tests {
     fn pooling_mode_ignore() {
         let mut pool = JitPool::new(PoolingMode::Ignore, 0..3);
 
-        assert_eq!(pool.key_pressed("keyA", "locA"), Some((0, None)));
-        assert_eq!(pool.key_pressed("keyB", "locB"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyC", "locC"), Some((2, None)));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((0, None)));
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((2, vec![])));
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((0, vec![])));
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), Some((1, "locB")));
@@ -418,8 +866,8 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), Some((0, "locD")));
 
         assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
-        assert_eq!(pool.key_pressed("keyD", "locD"), Some((1, None)));
-        assert_eq!(pool.key_pressed("keyE", "locE"), Some((2, None)));
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((1, vec![])));
+        assert_eq!(pool.key_pressed("keyE", "locE", props()), Some((2, vec![])));
 
         assert_eq!(pool.find_key("keyA"), Some((0, "locA")));
         assert_eq!(pool.find_key("keyB"), None);
@@ -439,4 +887,287 @@ mod tests {
         assert_eq!(pool.find_key("keyD"), None);
         assert_eq!(pool.find_key("keyE"), None);
     }
+
+    #[test]
+    fn pooling_mode_block_keeps_release_tails() {
+        let mut pool = JitPool::new(PoolingMode::Block, 0..2);
+        pool.release_time = Some(Duration::from_secs(3600));
+
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_released("keyA"), Some((0, "locA")));
+
+        // The release tail reserves channel 0
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+
+        // No more free channels: Block instead of stealing the release tail
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), None);
+    }
+
+    #[test]
+    fn pooling_mode_stop_quietest() {
+        let mut pool = JitPool::new(PoolingMode::StopQuietest, 0..3);
+
+        assert_eq!(
+            pool.key_pressed("keyA", "locA", loud(80)),
+            Some((0, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyB", "locB", loud(40)),
+            Some((1, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyC", "locC", loud(40)),
+            Some((2, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyD", "locD", loud(100)),
+            Some((1, vec![("keyB", "locB")]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyE", "locE", loud(100)),
+            Some((2, vec![("keyC", "locC")]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyF", "locF", loud(100)),
+            Some((0, vec![("keyA", "locA")]))
+        );
+    }
+
+    #[test]
+    fn pooling_mode_stop_released() {
+        let mut pool = JitPool::new(PoolingMode::StopReleased, 0..3);
+
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+
+        pool.set_sustain(true);
+        assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
+        assert_eq!(pool.find_key("keyB"), None);
+
+        // The channel of the sustained key is not reused as long as other channels are free
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((2, vec![])));
+
+        // The sustained key is stolen first and does not need to be stopped again
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((1, vec![])));
+
+        // No sustained key left, stop the oldest one
+        assert_eq!(
+            pool.key_pressed("keyE", "locE", props()),
+            Some((0, vec![("keyA", "locA")]))
+        );
+
+        assert_eq!(pool.key_released("keyC"), Some((2, "locC")));
+        pool.set_sustain(false);
+        assert_eq!(pool.key_pressed("keyF", "locF", props()), Some((2, vec![])));
+    }
+
+    #[test]
+    fn pooling_mode_stop_closest() {
+        let mut pool = JitPool::new(PoolingMode::StopClosest, 0..3);
+
+        assert_eq!(
+            pool.key_pressed("keyA", "locA", hz(220.0)),
+            Some((0, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyB", "locB", hz(440.0)),
+            Some((1, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyC", "locC", hz(880.0)),
+            Some((2, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyD", "locD", hz(500.0)),
+            Some((1, vec![("keyB", "locB")]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyE", "locE", hz(100.0)),
+            Some((0, vec![("keyA", "locA")]))
+        );
+    }
+
+    #[test]
+    fn pooling_mode_reuse() {
+        let mut pool = JitPool::new(
+            PoolingMode::Reuse {
+                tolerance: Ratio::from_cents(1.0),
+            },
+            0..2,
+        );
+
+        assert_eq!(
+            pool.key_pressed("keyA", "locA", cents(10.0)),
+            Some((0, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyB", "locB", cents(20.0)),
+            Some((1, vec![]))
+        );
+
+        // Share the channel of keyB adopting its detuning
+        assert_eq!(
+            pool.key_pressed("keyC", "locC", cents(20.5)),
+            Some((1, vec![]))
+        );
+        assert_eq!(pool.find_detuning("keyC"), Some(Ratio::from_cents(20.0)));
+
+        // Sharing the same location is not possible
+        assert_eq!(
+            pool.key_pressed("keyD", "locB", cents(20.0)),
+            Some((0, vec![("keyA", "locA")]))
+        );
+
+        // The shared channel is freed when the last key is released
+        assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
+        assert_eq!(
+            pool.key_pressed("keyE", "locE", cents(30.0)),
+            Some((1, vec![("keyC", "locC")]))
+        );
+        assert_eq!(pool.key_released("keyD"), Some((0, "locB")));
+        assert_eq!(
+            pool.key_pressed("keyF", "locF", cents(40.0)),
+            Some((0, vec![]))
+        );
+    }
+
+    #[test]
+    fn updated_keys_retune_their_channel() {
+        let mut pool = JitPool::new(PoolingMode::Block, 0..2);
+        pool.tolerance = Some(Ratio::from_cents(0.5));
+
+        assert_eq!(
+            pool.key_pressed("keyA", "locA", cents(10.0)),
+            Some((0, vec![]))
+        );
+
+        let a4 = Pitch::from_hz(440.0);
+        assert_eq!(
+            pool.key_updated("keyA", a4 * Ratio::from_cents(20.0), |_| a4),
+            Some((0, "locA"))
+        );
+        assert_eq!(pool.key_updated("keyB", a4, |_| a4), None);
+
+        // The old detuning is no longer shared
+        assert_eq!(
+            pool.key_pressed("keyB", "locB", cents(10.0)),
+            Some((1, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyC", "locC", cents(20.2)),
+            Some((0, vec![]))
+        );
+    }
+
+    #[test]
+    fn tolerance_based_sharing() {
+        let mut pool = JitPool::new(PoolingMode::Block, 0..2);
+        pool.tolerance = Some(Ratio::from_cents(0.5));
+
+        assert_eq!(
+            pool.key_pressed("keyA", "locA", cents(10.0)),
+            Some((0, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyB", "locB", cents(10.4)),
+            Some((0, vec![]))
+        );
+        assert_eq!(
+            pool.key_pressed("keyC", "locC", cents(10.6)),
+            Some((1, vec![]))
+        );
+        assert_eq!(pool.key_pressed("keyD", "locD", cents(20.0)), None);
+        assert_eq!(
+            pool.key_pressed("keyE", "locE", cents(9.6)),
+            Some((0, vec![]))
+        );
+
+        assert_eq!(pool.find_detuning("keyB"), Some(Ratio::from_cents(10.0)));
+        assert_eq!(pool.find_detuning("keyC"), Some(Ratio::from_cents(10.6)));
+        assert_eq!(pool.find_detuning("keyE"), Some(Ratio::from_cents(10.0)));
+
+        assert_eq!(pool.key_released("keyA"), Some((0, "locA")));
+        assert_eq!(pool.key_released("keyB"), Some((0, "locB")));
+        assert_eq!(pool.key_pressed("keyD", "locD", cents(20.0)), None);
+        assert_eq!(pool.key_released("keyE"), Some((0, "locE")));
+        assert_eq!(
+            pool.key_pressed("keyD", "locD", cents(20.0)),
+            Some((0, vec![]))
+        );
+    }
+
+    #[test]
+    fn release_tails() {
+        let mut pool = JitPool::new(PoolingMode::Stop, 0..3);
+        pool.release_time = Some(Duration::from_secs(3600));
+
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+        assert_eq!(pool.key_released("keyA"), Some((0, "locA")));
+        assert_eq!(pool.find_key("keyA"), None);
+
+        // Release tails are only stolen if no other channel is free
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((2, vec![])));
+        assert_eq!(pool.key_pressed("keyD", "locD", props()), Some((0, vec![])));
+
+        assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
+        assert!(pool.free.is_empty());
+        pool.expire_release_tails(Instant::now() + Duration::from_secs(3600));
+        assert_eq!(pool.free, [1]);
+        assert_eq!(pool.key_pressed("keyE", "locE", props()), Some((1, vec![])));
+
+        pool.release_time = Some(Duration::ZERO);
+        assert_eq!(pool.key_released("keyC"), Some((2, "locC")));
+        assert_eq!(pool.key_pressed("keyF", "locF", props()), Some((2, vec![])));
+    }
+
+    #[test]
+    fn release_tails_at_given_time() {
+        let mut pool = JitPool::new(PoolingMode::Stop, 0..2);
+        pool.release_time = Some(Duration::from_secs(1));
+
+        let start = Instant::now();
+        pool.time = Some(start);
+        assert_eq!(pool.key_pressed("keyA", "locA", props()), Some((0, vec![])));
+        assert_eq!(pool.key_released("keyA"), Some((0, "locA")));
+
+        // The release tail of keyA is still sounding
+        pool.time = Some(start + Duration::from_millis(500));
+        assert_eq!(pool.key_pressed("keyB", "locB", props()), Some((1, vec![])));
+        assert_eq!(pool.key_released("keyB"), Some((1, "locB")));
+
+        // The release tail of keyA has ended but the release tail of keyB is still sounding
+        pool.time = Some(start + Duration::from_millis(1200));
+        assert_eq!(pool.key_pressed("keyC", "locC", props()), Some((0, vec![])));
+        assert_eq!(pool.free, []);
+    }
+
+    fn props() -> KeyProps {
+        KeyProps {
+            pitch: Pitch::from_hz(440.0),
+            velocity: 100,
+            detuning: Ratio::default(),
+        }
+    }
+
+    fn loud(velocity: u8) -> KeyProps {
+        KeyProps {
+            velocity,
+            ..props()
+        }
+    }
+
+    fn hz(hz: f64) -> KeyProps {
+        KeyProps {
+            pitch: Pitch::from_hz(hz),
+            ..props()
+        }
+    }
+
+    fn cents(cents: f64) -> KeyProps {
+        KeyProps {
+            detuning: Ratio::from_cents(cents),
+            ..props()
+        }
+    }
 }
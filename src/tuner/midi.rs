@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    midi::{ChannelMessage, ChannelMessageType},
+    midi::{ChannelMessage, ChannelMessageType, Midi2ChannelMessage, Midi2ChannelMessageType},
     mts::{
         self, ScaleOctaveTuning, ScaleOctaveTuningFormat, ScaleOctaveTuningMessage,
         ScaleOctaveTuningOptions, SingleNoteTuningChange, SingleNoteTuningChangeMessage,
@@ -58,11 +58,159 @@ impl<H> TunableMidi<H> {
             midi_tuning_creator: MidiTuningCreator::ChannelFineTuning,
         }
     }
+}
+
+impl<H: MidiTunerMessageHandler> TunableMidi<H> {
+    /// Creates a [`TunableMidi`] instance that detunes its channels via pitch bend.
+    ///
+    /// On creation, a *Pitch Bend Sensitivity* message of `pitch_bend_range` is sent on every channel s.t. the synthesizer does not need to be configured manually.
+    /// The 14-bit pitch-bend values are computed with respect to that range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessage;
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::note::Note;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuner::MidiTarget;
+    /// # use tune::tuner::TunableMidi;
+    /// # use tune::tuner::TunableSynth;
+    /// let mut messages = Vec::new();
+    ///
+    /// let midi_target = MidiTarget {
+    ///     handler: |message: tune::tuner::MidiTunerMessage| {
+    ///         message.send_to(|bytes| {
+    ///             let message = ChannelMessage::from_raw_message(bytes).unwrap();
+    ///             messages.push((message.channel(), message.message_type()))
+    ///         })
+    ///     },
+    ///     channels: vec![0, 1],
+    /// };
+    ///
+    /// let mut synth = TunableMidi::pitch_bend(midi_target, Ratio::from_semitones(12));
+    ///
+    /// let note = Note::from_midi_number(69);
+    /// synth.notes_detune(1, &[(note, Ratio::from_semitones(-3))]);
+    /// synth.notes_detune(0, &[(note, Ratio::from_semitones(12))]);
+    ///
+    /// drop(synth);
+    ///
+    /// assert_eq!(messages.len(), 2 * 4 + 2);
+    ///
+    /// // Pitch Bend Sensitivity: 12 semitones
+    /// assert_eq!(
+    ///     messages[2],
+    ///     (0, ChannelMessageType::ControlChange { controller: 0x06, value: 12 })
+    /// );
+    /// assert_eq!(
+    ///     messages[6],
+    ///     (1, ChannelMessageType::ControlChange { controller: 0x06, value: 12 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     messages[8..],
+    ///     [
+    ///         (1, ChannelMessageType::PitchBendChange { value: -2048 }),
+    ///         (0, ChannelMessageType::PitchBendChange { value: 8191 }),
+    ///     ]
+    /// );
+    /// ```
+    pub fn pitch_bend(mut midi_target: MidiTarget<H>, pitch_bend_range: Ratio) -> Self {
+        send_pitch_bend_sensitivity(&mut midi_target, pitch_bend_range);
+
+        Self {
+            midi_target,
+            midi_tuning_creator: MidiTuningCreator::PitchBend { pitch_bend_range },
+        }
+    }
+
+    /// Creates a [`TunableMidi`] instance that drives an MPE (MIDI Polyphonic Expression) zone.
+    ///
+    /// The channels of `midi_target` are used as member channels and are detuned via pitch bend.
+    /// On creation, an MPE Configuration Message is sent to `manager_channel` (0 for the lower zone, 15 for the upper zone), followed by a *Pitch Bend Sensitivity* message of `pitch_bend_range` on every member channel.
+    ///
+    /// Polyphonic attributes are sent as *Channel Pressure* on the member channel of the affected note.
+    /// Global attributes are sent to the manager channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tune::midi::ChannelMessage;
+    /// # use tune::midi::ChannelMessageType;
+    /// # use tune::note::Note;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuner::MidiTarget;
+    /// # use tune::tuner::TunableMidi;
+    /// # use tune::tuner::TunableSynth;
+    /// let mut messages = Vec::new();
+    ///
+    /// let midi_target = MidiTarget {
+    ///     handler: |message: tune::tuner::MidiTunerMessage| {
+    ///         message.send_to(|bytes| {
+    ///             let message = ChannelMessage::from_raw_message(bytes).unwrap();
+    ///             messages.push((message.channel(), message.message_type()))
+    ///         })
+    ///     },
+    ///     channels: vec![1, 2],
+    /// };
+    ///
+    /// let mut synth = TunableMidi::mpe(midi_target, 0, Ratio::from_semitones(48));
+    ///
+    /// let note = Note::from_midi_number(69);
+    /// synth.notes_detune(1, &[(note, Ratio::from_semitones(12))]);
+    /// synth.note_on(1, note, 100);
+    /// synth.note_attr(1, note, 42);
+    ///
+    /// drop(synth);
+    ///
+    /// assert_eq!(messages.len(), 3 + 2 * 4 + 3);
+    ///
+    /// // MPE Configuration Message: 2 member channels
+    /// assert_eq!(
+    ///     messages[2],
+    ///     (0, ChannelMessageType::ControlChange { controller: 0x06, value: 2 })
+    /// );
+    ///
+    /// // Pitch Bend Sensitivity: 48 semitones
+    /// assert_eq!(
+    ///     messages[5],
+    ///     (1, ChannelMessageType::ControlChange { controller: 0x06, value: 48 })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     messages[11..],
+    ///     [
+    ///         (2, ChannelMessageType::PitchBendChange { value: 2048 }),
+    ///         (2, ChannelMessageType::NoteOn { key: 69, velocity: 100 }),
+    ///         (2, ChannelMessageType::ChannelPressure { pressure: 42 }),
+    ///     ]
+    /// );
+    /// ```
+    pub fn mpe(
+        mut midi_target: MidiTarget<H>,
+        manager_channel: u8,
+        pitch_bend_range: Ratio,
+    ) -> Self {
+        let num_member_channels = u8::try_from(midi_target.channels.len()).unwrap_or(u8::MAX);
+
+        for channel_message in mts::mpe_configuration(manager_channel, num_member_channels)
+            .into_iter()
+            .flatten()
+        {
+            midi_target
+                .handler
+                .handle(MidiTunerMessage::new(channel_message));
+        }
+
+        send_pitch_bend_sensitivity(&mut midi_target, pitch_bend_range);
 
-    pub fn pitch_bend(midi_target: MidiTarget<H>) -> Self {
         Self {
             midi_target,
-            midi_tuning_creator: MidiTuningCreator::PitchBend,
+            midi_tuning_creator: MidiTuningCreator::Mpe {
+                manager_channel,
+                pitch_bend_range,
+            },
         }
     }
 }
@@ -80,6 +228,10 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
         self.midi_tuning_creator.group_by()
     }
 
+    fn note_velocity(&self, velocity: &u8) -> u8 {
+        *velocity
+    }
+
     fn notes_detune(&mut self, channel: usize, detuned_notes: &[(Note, Ratio)]) {
         self.midi_tuning_creator
             .create(&mut self.midi_target, channel, detuned_notes)
@@ -110,7 +262,10 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
     }
 
     fn note_attr(&mut self, channel: usize, affected_note: Note, pressure: u8) {
-        if let Some(affected_note) = affected_note.checked_midi_number() {
+        if let MidiTuningCreator::Mpe { .. } = self.midi_tuning_creator {
+            self.midi_target
+                .send(ChannelMessageType::ChannelPressure { pressure }, channel);
+        } else if let Some(affected_note) = affected_note.checked_midi_number() {
             self.midi_target.send(
                 ChannelMessageType::PolyphonicKeyPressure {
                     key: affected_note,
@@ -121,15 +276,187 @@ impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi<H> {
         }
     }
 
+    fn channel_attr(&mut self, channel: usize, message_type: ChannelMessageType) {
+        if self.midi_tuning_creator.allow_pitch_bend()
+            || !matches!(message_type, ChannelMessageType::PitchBendChange { .. })
+        {
+            self.midi_target.send(message_type, channel);
+        }
+    }
+
     fn global_attr(&mut self, message_type: ChannelMessageType) {
+        if let MidiTuningCreator::Mpe {
+            manager_channel, ..
+        } = self.midi_tuning_creator
+        {
+            self.midi_target
+                .handler
+                .handle_channel_message(message_type, manager_channel);
+            return;
+        }
+
         for channel in 0..self.num_channels() {
-            if self.midi_tuning_creator.allow_pitch_bend()
-                || !matches!(message_type, ChannelMessageType::PitchBendChange { .. })
-            {
-                self.midi_target.send(message_type, channel);
+            self.channel_attr(channel, message_type);
+        }
+    }
+}
+
+/// A [`TunableSynth`] that drives a MIDI 2.0 target using the *Pitch 7.25* per-note controller.
+///
+/// Every note can be tuned individually s.t. a single channel is sufficient unless the same note needs to sound in different pitches simultaneously.
+///
+/// # Examples
+///
+/// ```
+/// # use tune::midi::Midi2ChannelMessageType;
+/// # use tune::note::Note;
+/// # use tune::pitch::Ratio;
+/// # use tune::tuner::MidiTarget;
+/// # use tune::tuner::TunableMidi2;
+/// # use tune::tuner::TunableSynth;
+/// let mut packets = Vec::new();
+///
+/// let midi_target = MidiTarget {
+///     handler: |message: tune::tuner::MidiTunerMessage| {
+///         message.send_to(|bytes| packets.push(bytes.to_vec()))
+///     },
+///     channels: vec![0],
+/// };
+///
+/// let mut synth = TunableMidi2::per_note_pitch(midi_target, 0);
+///
+/// let note = Note::from_midi_number(69);
+/// synth.notes_detune(0, &[(note, Ratio::from_cents(50.0))]);
+/// synth.note_on(0, note, 100);
+///
+/// drop(synth);
+///
+/// assert_eq!(
+///     packets,
+///     [
+///         vec![0x40, 0xf0, 69, 0b10, 0, 0, 0, 0],              // Per-Note Management (detach)
+///         vec![0x40, 0x00, 69, 3, 0x8b, 0x00, 0x00, 0x00],     // Pitch 7.25 (69.5 semitones)
+///         vec![0x40, 0x90, 69, 0, 0xc9, 0x24, 0x00, 0x00],     // Note On
+///     ]
+/// );
+/// ```
+pub struct TunableMidi2<H> {
+    midi_target: MidiTarget<H>,
+    group: u8,
+    active_notes: HashSet<(usize, Note)>,
+    detunings: HashMap<(usize, Note), Ratio>,
+}
+
+impl<H> TunableMidi2<H> {
+    pub fn per_note_pitch(midi_target: MidiTarget<H>, group: u8) -> Self {
+        Self {
+            midi_target,
+            group,
+            active_notes: HashSet::new(),
+            detunings: HashMap::new(),
+        }
+    }
+}
+
+impl<H: MidiTunerMessageHandler> TunableMidi2<H> {
+    fn send_per_note_pitch(&mut self, channel: usize, note: Note, detuning: Ratio) {
+        if let Some(message) = note
+            .checked_midi_number()
+            .and_then(|key| Midi2ChannelMessageType::per_note_pitch(key, note.pitch() * detuning))
+        {
+            self.midi_target.send_midi_2(message, channel, self.group);
+        }
+    }
+}
+
+impl<H: MidiTunerMessageHandler> TunableSynth for TunableMidi2<H> {
+    type Result = ();
+    type NoteAttr = u8;
+    type GlobalAttr = ChannelMessageType;
+
+    fn num_channels(&self) -> usize {
+        self.midi_target.channels.len()
+    }
+
+    fn group_by(&self) -> GroupBy {
+        GroupBy::Note
+    }
+
+    fn note_velocity(&self, velocity: &u8) -> u8 {
+        *velocity
+    }
+
+    fn notes_detune(&mut self, channel: usize, detuned_notes: &[(Note, Ratio)]) {
+        for &(note, detuning) in detuned_notes {
+            // The detuning is kept until it is replaced s.t. every subsequent note-on is tuned, e.g. in ahead-of-time mode
+            self.detunings.insert((channel, note), detuning);
+
+            // The pitch of an inactive note is sent on note-on s.t. releasing notes are not affected
+            if self.active_notes.contains(&(channel, note)) {
+                self.send_per_note_pitch(channel, note, detuning);
             }
         }
     }
+
+    fn note_on(&mut self, channel: usize, started_note: Note, velocity: u8) {
+        let detuning = self
+            .detunings
+            .get(&(channel, started_note))
+            .copied()
+            .unwrap_or_default();
+
+        if let Some(key) = started_note.checked_midi_number() {
+            self.active_notes.insert((channel, started_note));
+            self.midi_target.send_midi_2(
+                Midi2ChannelMessageType::PerNoteManagement {
+                    key,
+                    detach: true,
+                    reset: false,
+                },
+                channel,
+                self.group,
+            );
+            self.send_per_note_pitch(channel, started_note, detuning);
+            self.midi_target.send_midi_2(
+                ChannelMessageType::NoteOn { key, velocity }.to_midi_2(),
+                channel,
+                self.group,
+            );
+        }
+    }
+
+    fn note_off(&mut self, channel: usize, stopped_note: Note, velocity: u8) {
+        self.active_notes.remove(&(channel, stopped_note));
+
+        if let Some(key) = stopped_note.checked_midi_number() {
+            self.midi_target.send_midi_2(
+                ChannelMessageType::NoteOff { key, velocity }.to_midi_2(),
+                channel,
+                self.group,
+            );
+        }
+    }
+
+    fn note_attr(&mut self, channel: usize, affected_note: Note, pressure: u8) {
+        if let Some(key) = affected_note.checked_midi_number() {
+            self.midi_target.send_midi_2(
+                ChannelMessageType::PolyphonicKeyPressure { key, pressure }.to_midi_2(),
+                channel,
+                self.group,
+            );
+        }
+    }
+
+    fn channel_attr(&mut self, channel: usize, message_type: ChannelMessageType) {
+        self.midi_target
+            .send_midi_2(message_type.to_midi_2(), channel, self.group);
+    }
+
+    fn global_attr(&mut self, message_type: ChannelMessageType) {
+        for channel in 0..self.num_channels() {
+            self.channel_attr(channel, message_type);
+        }
+    }
 }
 
 pub struct MidiTarget<H> {
@@ -143,6 +470,12 @@ impl<H: MidiTunerMessageHandler> MidiTarget<H> {
             .handle_channel_message(message, self.midi_channel(tuner_channel));
     }
 
+    fn send_midi_2(&mut self, message: Midi2ChannelMessageType, tuner_channel: usize, group: u8) {
+        if let Some(message) = message.in_channel(group, self.midi_channel(tuner_channel)) {
+            self.handler.handle(MidiTunerMessage::new(message));
+        }
+    }
+
     fn midi_channel(&self, tuner_channel: usize) -> u8 {
         self.channels[tuner_channel]
     }
@@ -165,7 +498,13 @@ enum MidiTuningCreator {
         octave_tunings: HashMap<usize, ScaleOctaveTuning>,
     },
     ChannelFineTuning,
-    PitchBend,
+    PitchBend {
+        pitch_bend_range: Ratio,
+    },
+    Mpe {
+        manager_channel: u8,
+        pitch_bend_range: Ratio,
+    },
 }
 
 impl MidiTuningCreator {
@@ -247,9 +586,12 @@ impl MidiTuningCreator {
                     }
                 }
             }
-            MidiTuningCreator::PitchBend => {
+            MidiTuningCreator::PitchBend { pitch_bend_range }
+            | MidiTuningCreator::Mpe {
+                pitch_bend_range, ..
+            } => {
                 for &(_, detuning) in detuned_notes {
-                    let channel_message = pitch_bend_message(detuning)
+                    let channel_message = pitch_bend_message(detuning, *pitch_bend_range)
                         .in_channel(midi_channel)
                         .unwrap();
                     target
@@ -264,7 +606,9 @@ impl MidiTuningCreator {
         match self {
             MidiTuningCreator::SingleNoteTuningChange { .. } => GroupBy::Note,
             MidiTuningCreator::ScaleOctaveTuning { .. } => GroupBy::NoteLetter,
-            MidiTuningCreator::ChannelFineTuning | MidiTuningCreator::PitchBend => GroupBy::Channel,
+            MidiTuningCreator::ChannelFineTuning
+            | MidiTuningCreator::PitchBend { .. }
+            | MidiTuningCreator::Mpe { .. } => GroupBy::Channel,
         }
     }
 
@@ -273,7 +617,7 @@ impl MidiTuningCreator {
             MidiTuningCreator::SingleNoteTuningChange { .. }
             | MidiTuningCreator::ScaleOctaveTuning { .. }
             | MidiTuningCreator::ChannelFineTuning => true,
-            MidiTuningCreator::PitchBend => false,
+            MidiTuningCreator::PitchBend { .. } | MidiTuningCreator::Mpe { .. } => false,
         }
     }
 }
@@ -289,11 +633,21 @@ impl MidiTunerMessage {
         }
     }
 
+    /// Sends the raw bytes of the message to the given `receiver`.
+    ///
+    /// MIDI 2.0 messages are sent as Universal MIDI Packets in big-endian byte order.
     pub fn send_to(&self, mut receiver: impl FnMut(&[u8])) {
         match &self.variant {
             MidiTunerMessageVariant::Channel(channel_message) => {
                 receiver(&channel_message.to_raw_message());
             }
+            MidiTunerMessageVariant::Ump(ump_message) => {
+                let [status_word, data_word] = ump_message.to_ump();
+                let mut bytes = [0; 8];
+                bytes[..4].copy_from_slice(&status_word.to_be_bytes());
+                bytes[4..].copy_from_slice(&data_word.to_be_bytes());
+                receiver(&bytes);
+            }
             MidiTunerMessageVariant::ScaleOctaveTuning(tuning_message) => {
                 receiver(tuning_message.sysex_bytes());
             }
@@ -308,6 +662,7 @@ impl MidiTunerMessage {
 
 enum MidiTunerMessageVariant {
     Channel(ChannelMessage),
+    Ump(Midi2ChannelMessage),
     ScaleOctaveTuning(ScaleOctaveTuningMessage),
     SingleNoteTuningChange(SingleNoteTuningChangeMessage),
 }
@@ -318,6 +673,12 @@ impl From<ChannelMessage> for MidiTunerMessageVariant {
     }
 }
 
+impl From<Midi2ChannelMessage> for MidiTunerMessageVariant {
+    fn from(v: Midi2ChannelMessage) -> Self {
+        Self::Ump(v)
+    }
+}
+
 impl From<ScaleOctaveTuningMessage> for MidiTunerMessageVariant {
     fn from(v: ScaleOctaveTuningMessage) -> Self {
         Self::ScaleOctaveTuning(v)
@@ -346,10 +707,63 @@ impl<F: FnMut(MidiTunerMessage)> MidiTunerMessageHandler for F {
     }
 }
 
-fn pitch_bend_message(detuning: Ratio) -> ChannelMessageType {
+fn send_pitch_bend_sensitivity(
+    midi_target: &mut MidiTarget<impl MidiTunerMessageHandler>,
+    pitch_bend_range: Ratio,
+) {
+    for tuner_channel in 0..midi_target.channels.len() {
+        let midi_channel = midi_target.midi_channel(tuner_channel);
+        for channel_message in mts::pitch_bend_sensitivity(midi_channel, pitch_bend_range)
+            .into_iter()
+            .flatten()
+        {
+            midi_target
+                .handler
+                .handle(MidiTunerMessage::new(channel_message));
+        }
+    }
+}
+
+fn pitch_bend_message(detuning: Ratio, pitch_bend_range: Ratio) -> ChannelMessageType {
     ChannelMessageType::PitchBendChange {
-        value: ((detuning.as_semitones() / 2.0 * 8192.0) as i16)
-            .max(-8192)
-            .min(8192),
+        value: (detuning.as_semitones() / pitch_bend_range.as_semitones() * 8192.0)
+            .round()
+            .clamp(-8192.0, 8191.0) as i16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_note_pitch_is_sent_on_every_note_on() {
+        let mut packets = Vec::new();
+
+        let midi_target = MidiTarget {
+            handler: |message: MidiTunerMessage| {
+                message.send_to(|bytes| packets.push(bytes.to_vec()))
+            },
+            channels: vec![0],
+        };
+
+        let mut synth = TunableMidi2::per_note_pitch(midi_target, 0);
+
+        let note = Note::from_midi_number(69);
+        synth.notes_detune(0, &[(note, Ratio::from_cents(50.0))]);
+        synth.note_on(0, note, 100);
+        synth.note_off(0, note, 100);
+        synth.note_on(0, note, 100);
+
+        drop(synth);
+
+        let pitch_packet = vec![0x40, 0x00, 69, 3, 0x8b, 0x00, 0x00, 0x00];
+        let note_on_packet = vec![0x40, 0x90, 69, 0, 0xc9, 0x24, 0x00, 0x00];
+
+        assert_eq!(packets.len(), 7);
+        assert_eq!(packets[1], pitch_packet);
+        assert_eq!(packets[2], note_on_packet);
+        assert_eq!(packets[5], pitch_packet);
+        assert_eq!(packets[6], note_on_packet);
     }
 }
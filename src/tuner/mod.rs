@@ -1,5 +1,6 @@
 //! Generate tuning maps to enhance the capabilities of synthesizers with limited tuning support.
 
+mod adaptive;
 mod aot;
 mod jit;
 mod midi;
@@ -11,7 +12,7 @@ use crate::{
     pitch::Ratio,
 };
 
-pub use self::{aot::*, jit::*, midi::*};
+pub use self::{adaptive::*, aot::*, jit::*, midi::*};
 
 /// A note-based multichannel synthesizer with note detuning capabilities.
 pub trait TunableSynth {
@@ -23,6 +24,13 @@ pub trait TunableSynth {
 
     fn group_by(&self) -> GroupBy;
 
+    /// Extracts the velocity of a note attribute s.t. it can be taken into account when stealing voices.
+    ///
+    /// The default implementation considers all notes to be equally loud.
+    fn note_velocity(&self, _attr: &Self::NoteAttr) -> u8 {
+        0
+    }
+
     fn notes_detune(&mut self, channel: usize, detuned_notes: &[(Note, Ratio)]) -> Self::Result;
 
     fn note_on(&mut self, channel: usize, started_note: Note, attr: Self::NoteAttr)
@@ -42,6 +50,13 @@ pub trait TunableSynth {
         attr: Self::NoteAttr,
     ) -> Self::Result;
 
+    /// Sets a channel-global attribute on the given `channel` only.
+    ///
+    /// The default implementation does not support channel-specific attributes and ignores the attribute.
+    fn channel_attr(&mut self, _channel: usize, _attr: Self::GlobalAttr) -> Self::Result {
+        Self::Result::ok()
+    }
+
     fn global_attr(&mut self, attr: Self::GlobalAttr) -> Self::Result;
 }
 
@@ -2,9 +2,13 @@
 
 #![allow(clippy::wrong_self_convention)] // Would require a breaking change. Fix when appropriate.
 
+use std::{collections::HashMap, hash::Hash, marker::PhantomData, ops::Range};
+
 use crate::{
+    key::PianoKey,
     note::{Note, NoteLetter},
     pitch::{Pitch, Pitched, Ratio},
+    scala::KbmRoot,
 };
 
 /// A [`Tuning`] maps keys or notes of type `K` to a [`Pitch`] or vice versa.
@@ -55,6 +59,187 @@ pub trait Tuning<K> {
     {
         LinearMapping { inner: self }
     }
+
+    /// Transposes all [`Pitch`]es of the current [`Tuning`] by the given `ratio`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let transposed = ConcertPitch::default().transposed_by(Ratio::from_float(1.5));
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// assert_approx_eq!(transposed.pitch_of(a4).as_hz(), 660.0);
+    ///
+    /// let approximation = transposed.find_by_pitch(Pitch::from_hz(666.0));
+    /// assert_eq!(approximation.approx_value, a4);
+    /// assert_approx_eq!(approximation.deviation.as_cents(), 15.667383);
+    /// ```
+    fn transposed_by(self, ratio: Ratio) -> Transposed<Self>
+    where
+        Self: Sized,
+    {
+        Transposed { inner: self, ratio }
+    }
+
+    /// Stretches all intervals measured from `origin` s.t. an octave becomes [`Ratio::octave`] stretched by `stretch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let stretched = ConcertPitch::default()
+    ///     .stretched_by(Pitch::from_hz(440.0), Ratio::from_cents(10.0));
+    ///
+    /// let a5 = NoteLetter::A.in_octave(5);
+    /// let stretched_a5_pitch = Pitch::from_hz(440.0) * Ratio::from_cents(1210.0);
+    /// assert_approx_eq!(stretched.pitch_of(a5).as_hz(), stretched_a5_pitch.as_hz());
+    ///
+    /// let approximation = stretched.find_by_pitch(stretched_a5_pitch);
+    /// assert_eq!(approximation.approx_value, a5);
+    /// assert_approx_eq!(approximation.deviation.as_cents(), 0.0);
+    /// ```
+    fn stretched_by(self, origin: impl Pitched, stretch: Ratio) -> Stretched<Self>
+    where
+        Self: Sized,
+    {
+        Stretched {
+            inner: self,
+            origin: origin.pitch(),
+            stretch,
+        }
+    }
+
+    /// Uses the current [`Tuning`] for all keys below `split_key` and `upper` for all other keys.
+    ///
+    /// [`Tuning::find_by_pitch`] returns the closest key found by either of the two tunings within its key range or the `split_key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let c4 = NoteLetter::C.in_octave(4);
+    /// let split = ConcertPitch::default()
+    ///     .split_at(c4, ConcertPitch::default().transposed_by(Ratio::from_cents(50.0)));
+    ///
+    /// let b3 = NoteLetter::B.in_octave(3);
+    /// assert_approx_eq!(split.pitch_of(b3).as_hz(), 246.941651);
+    /// assert_approx_eq!(split.pitch_of(c4).as_hz(), 269.291780);
+    ///
+    /// // The key B3 of the upper tuning is not used
+    /// let approximation = split.find_by_pitch(Pitch::from_hz(254.0));
+    /// assert_eq!(approximation.approx_value, b3);
+    /// assert_approx_eq!(approximation.deviation.as_cents(), 48.789968);
+    /// ```
+    fn split_at<U: Tuning<K>>(self, split_key: K, upper: U) -> Split<K, Self, U>
+    where
+        Self: Sized,
+    {
+        Split {
+            lower: self,
+            upper,
+            split_key,
+        }
+    }
+
+    /// Replaces the [`Pitch`]es of the given keys.
+    ///
+    /// [`Tuning::find_by_pitch`] returns the closest of the overridden keys and the key found by the current [`Tuning`], unless the latter is overridden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// let b4 = NoteLetter::B.in_octave(4);
+    ///
+    /// let overridden = ConcertPitch::default().with_overrides([(a4, Pitch::from_hz(500.0))]);
+    ///
+    /// assert_approx_eq!(overridden.pitch_of(a4).as_hz(), 500.0);
+    /// assert_approx_eq!(overridden.pitch_of(b4).as_hz(), 493.883301);
+    ///
+    /// assert_eq!(overridden.find_by_pitch(Pitch::from_hz(480.0)).approx_value, b4);
+    /// assert_eq!(overridden.find_by_pitch(Pitch::from_hz(499.0)).approx_value, a4);
+    /// ```
+    fn with_overrides(self, overrides: impl IntoIterator<Item = (K, Pitch)>) -> Overridden<K, Self>
+    where
+        Self: Sized,
+        K: Eq + Hash,
+    {
+        Overridden {
+            inner: self,
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+
+    /// Retunes every [`Pitch`] of the current [`Tuning`] to the closest [`Pitch`] of the `reference` [`Tuning`].
+    ///
+    /// [`Tuning::find_by_pitch`] searches the key in the current [`Tuning`] and measures the deviation from its retuned [`Pitch`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::KbmRoot;
+    /// # use tune::scala::Scl;
+    /// # use tune::tuning::Approximation;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let d4 = NoteLetter::D.in_octave(4);
+    ///
+    /// let edo_16 = Scl::builder()
+    ///     .push_ratio(Ratio::octave().divided_into_equal_steps(16))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let snapped = Tuning::<PianoKey>::snapped_to((edo_16, KbmRoot::from(d4)), ConcertPitch::default());
+    ///
+    /// // 3 steps of 16-EDO (225 cents) are closest to 2 semitones
+    /// let key_3_steps_above_d4 = d4.as_piano_key().plus_steps(3);
+    /// assert_approx_eq!(snapped.pitch_of(key_3_steps_above_d4).as_hz(), 329.627557);
+    ///
+    /// let approximation: Approximation<PianoKey> = snapped.find_by_pitch(Pitch::from_hz(330.0));
+    /// assert_eq!(approximation.approx_value, key_3_steps_above_d4);
+    /// assert_approx_eq!(approximation.deviation.as_cents(), 1.955001);
+    /// ```
+    fn snapped_to<N, R: Tuning<N>>(self, reference: R) -> SnappedTo<Self, R, N>
+    where
+        Self: Sized,
+    {
+        SnappedTo {
+            inner: self,
+            reference,
+            _reference_key: PhantomData,
+        }
+    }
 }
 
 /// `impl` forwarding for references.
@@ -140,6 +325,195 @@ impl<S: Scale> Tuning<i32> for SortedTuning<S> {
 pub trait KeyboardMapping<K> {
     /// Returns the [`Pitch`] of the provided key or note.
     fn maybe_pitch_of(&self, key: K) -> Option<Pitch>;
+
+    /// Transposes all [`Pitch`]es of the current [`KeyboardMapping`] by the given `ratio`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::KeyboardMapping;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let transposed = ConcertPitch::default()
+    ///     .as_linear_mapping()
+    ///     .transposed_by(Ratio::from_float(1.5));
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// assert_approx_eq!(transposed.maybe_pitch_of(a4).unwrap().as_hz(), 660.0);
+    /// ```
+    fn transposed_by(self, ratio: Ratio) -> Transposed<Self>
+    where
+        Self: Sized,
+    {
+        Transposed { inner: self, ratio }
+    }
+
+    /// Stretches all intervals measured from `origin` s.t. an octave becomes [`Ratio::octave`] stretched by `stretch`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::KeyboardMapping;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let stretched = ConcertPitch::default()
+    ///     .as_linear_mapping()
+    ///     .stretched_by(Pitch::from_hz(440.0), Ratio::from_cents(10.0));
+    ///
+    /// let a3 = NoteLetter::A.in_octave(3);
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// let e5 = NoteLetter::E.in_octave(5);
+    /// assert_approx_eq!(stretched.maybe_pitch_of(a4).unwrap().as_hz(), 440.0);
+    /// assert_approx_eq!(
+    ///     Ratio::between_pitches(stretched.maybe_pitch_of(a3).unwrap(), Pitch::from_hz(440.0)).as_cents(),
+    ///     1210.0
+    /// );
+    /// assert_approx_eq!(
+    ///     Ratio::between_pitches(Pitch::from_hz(440.0), stretched.maybe_pitch_of(e5).unwrap()).as_cents(),
+    ///     705.833333
+    /// );
+    /// ```
+    fn stretched_by(self, origin: impl Pitched, stretch: Ratio) -> Stretched<Self>
+    where
+        Self: Sized,
+    {
+        Stretched {
+            inner: self,
+            origin: origin.pitch(),
+            stretch,
+        }
+    }
+
+    /// Uses the current [`KeyboardMapping`] for all keys below `split_key` and `upper` for all other keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::Kbm;
+    /// # use tune::scala::Scl;
+    /// use tune::tuning::KeyboardMapping;
+    ///
+    /// let c4 = NoteLetter::C.in_octave(4);
+    ///
+    /// let edo_19 = Scl::builder()
+    ///     .push_ratio(Ratio::octave().divided_into_equal_steps(19))
+    ///     .build()
+    ///     .unwrap();
+    /// let ji_7_limit = Scl::builder()
+    ///     .push_fraction(8, 7)
+    ///     .push_fraction(5, 4)
+    ///     .push_fraction(4, 3)
+    ///     .push_fraction(3, 2)
+    ///     .push_fraction(7, 4)
+    ///     .push_fraction(2, 1)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let kbm = Kbm::builder(c4).build().unwrap();
+    ///
+    /// let split = (edo_19, &kbm).split_at(c4.as_piano_key(), (ji_7_limit, &kbm));
+    ///
+    /// let pitch_of = |midi_number| split.maybe_pitch_of(PianoKey::from_midi_number(midi_number)).unwrap();
+    /// assert_approx_eq!(pitch_of(59).as_hz(), 261.625565 / Ratio::octave().divided_into_equal_steps(19).as_float());
+    /// assert_approx_eq!(pitch_of(60).as_hz(), 261.625565);
+    /// assert_approx_eq!(pitch_of(61).as_hz(), 261.625565 * 8.0 / 7.0);
+    /// ```
+    fn split_at<U: KeyboardMapping<K>>(self, split_key: K, upper: U) -> Split<K, Self, U>
+    where
+        Self: Sized,
+    {
+        Split {
+            lower: self,
+            upper,
+            split_key,
+        }
+    }
+
+    /// Replaces the [`Pitch`]es of the given keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Pitch;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::KeyboardMapping;
+    /// use tune::tuning::Tuning;
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// let b4 = NoteLetter::B.in_octave(4);
+    ///
+    /// let overridden = ConcertPitch::default()
+    ///     .as_linear_mapping()
+    ///     .with_overrides([(a4, Pitch::from_hz(432.0))]);
+    ///
+    /// assert_approx_eq!(overridden.maybe_pitch_of(a4).unwrap().as_hz(), 432.0);
+    /// assert_approx_eq!(overridden.maybe_pitch_of(b4).unwrap().as_hz(), 493.883301);
+    /// ```
+    fn with_overrides(self, overrides: impl IntoIterator<Item = (K, Pitch)>) -> Overridden<K, Self>
+    where
+        Self: Sized,
+        K: Eq + Hash,
+    {
+        Overridden {
+            inner: self,
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+
+    /// Retunes every [`Pitch`] of the current [`KeyboardMapping`] to the closest [`Pitch`] of the `reference` [`Tuning`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::scala::Kbm;
+    /// # use tune::scala::Scl;
+    /// # use tune::tuning::ConcertPitch;
+    /// use tune::tuning::KeyboardMapping;
+    ///
+    /// let d4 = NoteLetter::D.in_octave(4);
+    ///
+    /// let edo_16 = Scl::builder()
+    ///     .push_ratio(Ratio::octave().divided_into_equal_steps(16))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let kbm = Kbm::builder(d4).build().unwrap();
+    ///
+    /// let snapped = KeyboardMapping::<PianoKey>::snapped_to((edo_16, kbm), ConcertPitch::default());
+    ///
+    /// // 3 steps of 16-EDO (225 cents) are closest to 2 semitones
+    /// let key_3_steps_above_d4 = d4.as_piano_key().plus_steps(3);
+    /// assert_approx_eq!(snapped.maybe_pitch_of(key_3_steps_above_d4).unwrap().as_hz(), 329.627557);
+    /// ```
+    fn snapped_to<N, R: Tuning<N>>(self, reference: R) -> SnappedTo<Self, R, N>
+    where
+        Self: Sized,
+    {
+        SnappedTo {
+            inner: self,
+            reference,
+            _reference_key: PhantomData,
+        }
+    }
 }
 
 /// `impl` forwarding for references.
@@ -149,6 +523,13 @@ impl<K, T: KeyboardMapping<K> + ?Sized> KeyboardMapping<K> for &T {
     }
 }
 
+/// `impl` forwarding for boxes.
+impl<K, T: KeyboardMapping<K> + ?Sized> KeyboardMapping<K> for Box<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        T::maybe_pitch_of(self, key)
+    }
+}
+
 /// Type adapter returned by [`Tuning::as_linear_mapping`].
 pub struct LinearMapping<T> {
     inner: T,
@@ -160,6 +541,365 @@ impl<K, T: Tuning<K>> KeyboardMapping<K> for LinearMapping<T> {
     }
 }
 
+/// Type adapter returned by [`Tuning::transposed_by`] and [`KeyboardMapping::transposed_by`].
+pub struct Transposed<T> {
+    inner: T,
+    ratio: Ratio,
+}
+
+impl<K, T: Tuning<K>> Tuning<K> for Transposed<T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.inner.pitch_of(key) * self.ratio
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        self.inner.find_by_pitch(pitch / self.ratio)
+    }
+}
+
+impl<K, T: KeyboardMapping<K>> KeyboardMapping<K> for Transposed<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner
+            .maybe_pitch_of(key)
+            .map(|pitch| pitch * self.ratio)
+    }
+}
+
+/// Type adapter returned by [`Tuning::stretched_by`] and [`KeyboardMapping::stretched_by`].
+pub struct Stretched<T> {
+    inner: T,
+    origin: Pitch,
+    stretch: Ratio,
+}
+
+impl<T> Stretched<T> {
+    fn stretch(&self, pitch: Pitch, num_repetitions: f64) -> Pitch {
+        self.origin * Ratio::between_pitches(self.origin, pitch).repeated(num_repetitions)
+    }
+
+    fn stretch_factor(&self) -> f64 {
+        Ratio::octave().stretched_by(self.stretch).as_octaves()
+    }
+}
+
+impl<K: Copy, T: Tuning<K>> Tuning<K> for Stretched<T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.stretch(self.inner.pitch_of(key), self.stretch_factor())
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let approx_value = self
+            .inner
+            .find_by_pitch(self.stretch(pitch, self.stretch_factor().recip()))
+            .approx_value;
+        Approximation {
+            approx_value,
+            deviation: Ratio::between_pitches(self.pitch_of(approx_value), pitch),
+        }
+    }
+}
+
+impl<K, T: KeyboardMapping<K>> KeyboardMapping<K> for Stretched<T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner
+            .maybe_pitch_of(key)
+            .map(|pitch| self.stretch(pitch, self.stretch_factor()))
+    }
+}
+
+/// Type adapter returned by [`Tuning::split_at`] and [`KeyboardMapping::split_at`].
+pub struct Split<K, L, U> {
+    lower: L,
+    upper: U,
+    split_key: K,
+}
+
+impl<K: Copy + PartialOrd, L: Tuning<K>, U: Tuning<K>> Tuning<K> for Split<K, L, U> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        if key < self.split_key {
+            self.lower.pitch_of(key)
+        } else {
+            self.upper.pitch_of(key)
+        }
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let lower = self.lower.find_by_pitch(pitch);
+        let upper = self.upper.find_by_pitch(pitch);
+        let split_key = Approximation {
+            approx_value: self.split_key,
+            deviation: Ratio::between_pitches(self.upper.pitch_of(self.split_key), pitch),
+        };
+
+        [
+            (lower.approx_value < self.split_key).then_some(lower),
+            (upper.approx_value >= self.split_key).then_some(upper),
+            Some(split_key),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by(|a, b| a.deviation.abs().total_cmp(&b.deviation.abs()))
+        .unwrap()
+    }
+}
+
+impl<K: PartialOrd, L: KeyboardMapping<K>, U: KeyboardMapping<K>> KeyboardMapping<K>
+    for Split<K, L, U>
+{
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        if key < self.split_key {
+            self.lower.maybe_pitch_of(key)
+        } else {
+            self.upper.maybe_pitch_of(key)
+        }
+    }
+}
+
+/// Type adapter returned by [`Tuning::with_overrides`] and [`KeyboardMapping::with_overrides`].
+pub struct Overridden<K, T> {
+    inner: T,
+    overrides: HashMap<K, Pitch>,
+}
+
+impl<K: Copy + Eq + Hash, T: Tuning<K>> Tuning<K> for Overridden<K, T> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        match self.overrides.get(&key) {
+            Some(&pitch) => pitch,
+            None => self.inner.pitch_of(key),
+        }
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let approximation = self.inner.find_by_pitch(pitch);
+
+        (!self.overrides.contains_key(&approximation.approx_value))
+            .then_some(approximation)
+            .into_iter()
+            .chain(
+                self.overrides
+                    .iter()
+                    .map(|(&key, &override_pitch)| Approximation {
+                        approx_value: key,
+                        deviation: Ratio::between_pitches(override_pitch, pitch),
+                    }),
+            )
+            .min_by(|a, b| a.deviation.abs().total_cmp(&b.deviation.abs()))
+            .unwrap()
+    }
+}
+
+impl<K: Eq + Hash, T: KeyboardMapping<K>> KeyboardMapping<K> for Overridden<K, T> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        match self.overrides.get(&key) {
+            Some(&pitch) => Some(pitch),
+            None => self.inner.maybe_pitch_of(key),
+        }
+    }
+}
+
+/// Type adapter returned by [`Tuning::snapped_to`] and [`KeyboardMapping::snapped_to`].
+pub struct SnappedTo<T, R, N> {
+    inner: T,
+    reference: R,
+    _reference_key: PhantomData<fn() -> N>,
+}
+
+impl<T, R: Tuning<N>, N> SnappedTo<T, R, N> {
+    fn snap(&self, pitch: Pitch) -> Pitch {
+        self.reference
+            .pitch_of(self.reference.find_by_pitch(pitch).approx_value)
+    }
+}
+
+impl<K: Copy, T: Tuning<K>, R: Tuning<N>, N> Tuning<K> for SnappedTo<T, R, N> {
+    fn pitch_of(&self, key: K) -> Pitch {
+        self.snap(self.inner.pitch_of(key))
+    }
+
+    fn find_by_pitch(&self, pitch: Pitch) -> Approximation<K> {
+        let approx_value = self.inner.find_by_pitch(pitch).approx_value;
+        Approximation {
+            approx_value,
+            deviation: Ratio::between_pitches(self.pitch_of(approx_value), pitch),
+        }
+    }
+}
+
+impl<K, T: KeyboardMapping<K>, R: Tuning<N>, N> KeyboardMapping<K> for SnappedTo<T, R, N> {
+    fn maybe_pitch_of(&self, key: K) -> Option<Pitch> {
+        self.inner.maybe_pitch_of(key).map(|pitch| self.snap(pitch))
+    }
+}
+
+/// A 12-tone piano tuning whose octaves are stretched according to the Railsback curve.
+///
+/// The partials of a stiff string are sharp w.r.t. the harmonic series, i.e. partial *n* sounds at *n* &middot; *f* &middot; &radic;(1 + *B* &middot; *n*²) where *B* is the inharmonicity coefficient of the string.
+/// Piano tuners compensate for this effect by tuning the octaves wider than 2/1.
+#[derive(Clone, Debug)]
+pub struct RailsbackCurve {
+    range: Range<PianoKey>,
+    pitches: Vec<Pitch>,
+}
+
+impl RailsbackCurve {
+    /// Creates a [`RailsbackCurve`] from the inharmonicity coefficient *B* of each key.
+    ///
+    /// Each octave is tuned s.t. the second partial of its lower key matches the first partial of its upper key.
+    /// The octave is then divided into 12 equal semitones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::RailsbackCurve;
+    /// use tune::tuning::KeyboardMapping;
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// let a4_key = a4.as_piano_key();
+    /// let full_range = PianoKey::from_midi_number(21)..PianoKey::from_midi_number(109);
+    ///
+    /// let unstretched = RailsbackCurve::from_inharmonicity(a4, full_range.clone(), |_| 0.0);
+    /// assert_approx_eq!(unstretched.maybe_pitch_of(a4_key.plus_steps(12)).unwrap().as_hz(), 880.0);
+    ///
+    /// let stretched = RailsbackCurve::from_inharmonicity(a4, full_range, |_| 0.001);
+    /// let octave = Ratio::between_pitches(
+    ///     stretched.maybe_pitch_of(a4_key).unwrap(),
+    ///     stretched.maybe_pitch_of(a4_key.plus_steps(12)).unwrap(),
+    /// );
+    /// assert_approx_eq!(octave.as_cents(), 1202.590377);
+    ///
+    /// // Keys outside the range are not mapped
+    /// assert!(stretched.maybe_pitch_of(PianoKey::from_midi_number(109)).is_none());
+    /// ```
+    pub fn from_inharmonicity(
+        kbm_root: impl Into<KbmRoot>,
+        range: Range<PianoKey>,
+        inharmonicity: impl Fn(PianoKey) -> f64,
+    ) -> Self {
+        let semitone_above = |key: PianoKey| {
+            let lower_b = inharmonicity(key);
+            let upper_b = inharmonicity(key.plus_steps(12));
+            Ratio::from_float(2.0 * ((1.0 + 4.0 * lower_b) / (1.0 + upper_b)).sqrt())
+                .divided_into_equal_steps(12)
+        };
+
+        Self::from_semitones(kbm_root.into(), range, semitone_above)
+    }
+
+    /// Creates a [`RailsbackCurve`] from measured deviations from 12-EDO at selected keys.
+    ///
+    /// The deviations are interpolated linearly between the given keys and extrapolated constantly beyond them.
+    /// The reference key of `kbm_root` always sounds at its reference pitch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use assert_approx_eq::assert_approx_eq;
+    /// # use tune::key::PianoKey;
+    /// # use tune::note::Note;
+    /// # use tune::note::NoteLetter;
+    /// # use tune::pitch::Ratio;
+    /// # use tune::tuning::RailsbackCurve;
+    /// use tune::tuning::KeyboardMapping;
+    ///
+    /// let a4 = NoteLetter::A.in_octave(4);
+    /// let full_range = PianoKey::from_midi_number(21)..PianoKey::from_midi_number(109);
+    ///
+    /// let measured = RailsbackCurve::from_measured_curve(
+    ///     a4,
+    ///     full_range,
+    ///     [
+    ///         (PianoKey::from_midi_number(21), Ratio::from_cents(-30.0)),
+    ///         (PianoKey::from_midi_number(69), Ratio::from_cents(0.0)),
+    ///         (PianoKey::from_midi_number(108), Ratio::from_cents(39.0)),
+    ///     ],
+    /// );
+    ///
+    /// let deviation_of = |midi_number| {
+    ///     let pitch = measured.maybe_pitch_of(PianoKey::from_midi_number(midi_number));
+    ///     Ratio::between_pitches(Note::from_midi_number(midi_number), pitch.unwrap()).as_cents()
+    /// };
+    /// assert_approx_eq!(deviation_of(21), -30.0);
+    /// assert_approx_eq!(deviation_of(45), -15.0);
+    /// assert_approx_eq!(deviation_of(69), 0.0);
+    /// assert_approx_eq!(deviation_of(95), 26.0);
+    /// ```
+    pub fn from_measured_curve(
+        kbm_root: impl Into<KbmRoot>,
+        range: Range<PianoKey>,
+        deviations: impl IntoIterator<Item = (PianoKey, Ratio)>,
+    ) -> Self {
+        let mut deviations: Vec<_> = deviations.into_iter().collect();
+        deviations.sort_by_key(|&(key, _)| key);
+
+        let deviation_of = |key: PianoKey| {
+            let upper_index = deviations.partition_point(|&(measured_key, _)| measured_key < key);
+            match (
+                upper_index.checked_sub(1).map(|index| deviations[index]),
+                deviations.get(upper_index),
+            ) {
+                (_, Some(&(upper_key, upper_deviation))) if upper_key == key => {
+                    upper_deviation.as_cents()
+                }
+                (Some((lower_key, lower_deviation)), Some(&(upper_key, upper_deviation))) => {
+                    let progress = f64::from(lower_key.num_keys_before(key))
+                        / f64::from(lower_key.num_keys_before(upper_key));
+                    lower_deviation.as_cents()
+                        + progress * (upper_deviation.as_cents() - lower_deviation.as_cents())
+                }
+                (Some((_, deviation)), None) | (None, Some(&(_, deviation))) => {
+                    deviation.as_cents()
+                }
+                (None, None) => 0.0,
+            }
+        };
+
+        let semitone_above = |key: PianoKey| {
+            Ratio::from_cents(100.0 + deviation_of(key.plus_steps(1)) - deviation_of(key))
+        };
+
+        Self::from_semitones(kbm_root.into(), range, semitone_above)
+    }
+
+    fn from_semitones(
+        kbm_root: KbmRoot,
+        range: Range<PianoKey>,
+        semitone_above: impl Fn(PianoKey) -> Ratio,
+    ) -> Self {
+        let pitches = (range.start.midi_number()..range.end.midi_number())
+            .map(|midi_number| {
+                let key = PianoKey::from_midi_number(midi_number);
+                let num_steps = kbm_root.ref_key.num_keys_before(key);
+                let mut pitch = kbm_root.ref_pitch;
+                for step in 0..num_steps {
+                    pitch = pitch * semitone_above(kbm_root.ref_key.plus_steps(step));
+                }
+                for step in num_steps..0 {
+                    pitch = pitch / semitone_above(kbm_root.ref_key.plus_steps(step));
+                }
+                pitch
+            })
+            .collect();
+
+        Self { range, pitches }
+    }
+
+    /// Returns the range of keys covered by the current [`RailsbackCurve`].
+    pub fn range(&self) -> Range<PianoKey> {
+        self.range.clone()
+    }
+}
+
+impl KeyboardMapping<PianoKey> for RailsbackCurve {
+    fn maybe_pitch_of(&self, key: PianoKey) -> Option<Pitch> {
+        let index = usize::try_from(self.range.start.num_keys_before(key)).ok()?;
+        self.pitches.get(index).copied()
+    }
+}
+
 /// The result of a find operation on [`Scale`]s or [`Tuning`]s.
 #[derive(Copy, Clone, Debug)]
 pub struct Approximation<K> {
@@ -0,0 +1,340 @@
+use std::{collections::BTreeMap, f64::consts::TAU, fs::File, io, path::PathBuf};
+
+use clap::Parser;
+use tune::{
+    layout::IsomorphicLayout,
+    note::NoteLetter,
+    pitch::{Pitch, Pitched, Ratio},
+    smf::Smf,
+};
+
+use crate::{
+    dto::{EdoMatchDto, MidiAnalysisDto, PitchMatchDto, ScaleMatchDto},
+    error::ResultExt,
+    output::OutputFormat,
+    scale::{self, TargetScaleCommand},
+    App, CliError, CliResult,
+};
+
+#[derive(Parser)]
+pub(crate) struct AnalyzeMidiOptions {
+    /// Standard MIDI File to analyze
+    input_file: PathBuf,
+
+    /// Largest EDO to consider when searching for matching EDOs
+    #[arg(long = "max-edo", default_value = "72")]
+    max_edo: u16,
+
+    /// Largest acceptable deviation of a note from the matched EDO or target scale
+    #[arg(long = "tolerance", default_value = "1c")]
+    tolerance: Ratio,
+
+    /// Match the notes against the given scale instead of searching for matching EDOs
+    #[command(subcommand)]
+    target_scale: Option<TargetScaleCommand>,
+}
+
+impl AnalyzeMidiOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let input_file =
+            File::open(&self.input_file).handle_error::<CliError>("Could not open input file")?;
+        let input = Smf::read(input_file).handle_error::<CliError>("Could not read input file")?;
+
+        let notes = input.notes();
+        if notes.is_empty() {
+            return Err("The file does not contain any notes".to_owned().into());
+        }
+
+        // Pitches are considered equal if they are less than 0.001 cents apart
+        let mut pitch_usage = BTreeMap::new();
+        for note in &notes {
+            let key = (note.pitch.as_hz().log2() * 1_200_000.0).round() as i64;
+            pitch_usage.entry(key).or_insert((note.pitch, 0)).1 += 1;
+        }
+        let pitch_usage: Vec<_> = pitch_usage.into_values().collect();
+
+        let edo_matches = match &self.target_scale {
+            None => Some(EdoMatch::find_all(
+                self.max_edo,
+                &pitch_usage,
+                self.tolerance,
+            )),
+            Some(_) => None,
+        };
+        let scale_match = match &self.target_scale {
+            None => None,
+            Some(target_scale) => Some(match_scale(target_scale, &pitch_usage, self.tolerance)?),
+        };
+
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&MidiAnalysisDto {
+                num_notes: notes.len(),
+                num_distinct_pitches: pitch_usage.len(),
+                lowest_pitch_in_hz: pitch_usage[0].0.as_hz(),
+                highest_pitch_in_hz: pitch_usage[pitch_usage.len() - 1].0.as_hz(),
+                tolerance_in_cents: self.tolerance.as_cents(),
+                edo_matches: edo_matches.map(|edo_matches| {
+                    edo_matches
+                        .iter()
+                        .map(|edo_match| EdoMatchDto {
+                            num_steps: edo_match.num_steps,
+                            max_deviation_in_cents: edo_match.max_deviation,
+                            d4_pitch_in_hz: edo_match.d4_pitch().as_hz(),
+                        })
+                        .collect()
+                }),
+                scale_match,
+            });
+        }
+
+        let mut printer = AnalysisPrinter {
+            app,
+            tolerance: self.tolerance,
+        };
+
+        printer.print_notes_summary(notes.len(), &pitch_usage)?;
+        printer.print_newline()?;
+
+        match (edo_matches, scale_match) {
+            (Some(edo_matches), _) => {
+                printer.print_edo_matches(self.max_edo, &edo_matches, &pitch_usage)
+            }
+            (_, Some(scale_match)) => printer.print_scale_match(&scale_match),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+fn match_scale(
+    target_scale: &TargetScaleCommand,
+    pitch_usage: &[(Pitch, usize)],
+    tolerance: Ratio,
+) -> CliResult<ScaleMatchDto> {
+    let (target_scl, target_kbm) = target_scale.to_tuning()?;
+
+    let mut max_deviation = Ratio::default();
+    let mut items = Vec::new();
+    for &(pitch, count) in pitch_usage {
+        let approximation = scale::find_mapped_key(&target_scl, &target_kbm, pitch)?;
+        let index = target_kbm
+            .kbm_root()
+            .ref_key
+            .num_keys_before(approximation.approx_value);
+
+        items.push(PitchMatchDto {
+            pitch_in_hz: pitch.as_hz(),
+            count,
+            target_key_midi_number: approximation.approx_value.midi_number(),
+            target_index: index,
+            deviation_in_cents: approximation.deviation.as_cents(),
+        });
+
+        if approximation.deviation.abs() > max_deviation {
+            max_deviation = approximation.deviation.abs();
+        }
+    }
+
+    Ok(ScaleMatchDto {
+        items,
+        max_deviation_in_cents: max_deviation.as_cents(),
+        matches: max_deviation <= tolerance,
+    })
+}
+
+struct AnalysisPrinter<'a, 'b> {
+    app: &'a mut App<'b>,
+    tolerance: Ratio,
+}
+
+impl AnalysisPrinter<'_, '_> {
+    fn print_newline(&mut self) -> io::Result<()> {
+        self.app.writeln("")
+    }
+
+    fn print_notes_summary(
+        &mut self,
+        num_notes: usize,
+        pitch_usage: &[(Pitch, usize)],
+    ) -> io::Result<()> {
+        self.app.writeln("==== Notes ====")?;
+        self.print_newline()?;
+
+        self.app
+            .writeln(format_args!("- number of notes: {num_notes}"))?;
+        self.app.writeln(format_args!(
+            "- number of distinct pitches: {}",
+            pitch_usage.len()
+        ))?;
+        self.app.writeln(format_args!(
+            "- lowest pitch: {:.3} Hz",
+            pitch_usage[0].0.as_hz()
+        ))?;
+        self.app.writeln(format_args!(
+            "- highest pitch: {:.3} Hz",
+            pitch_usage[pitch_usage.len() - 1].0.as_hz()
+        ))
+    }
+
+    fn print_edo_matches(
+        &mut self,
+        max_edo: u16,
+        edo_matches: &[EdoMatch],
+        pitch_usage: &[(Pitch, usize)],
+    ) -> CliResult {
+        self.app.writeln(format_args!(
+            "==== Matching EDOs (tolerance: {:.3}¢) ====",
+            self.tolerance.as_cents()
+        ))?;
+        self.print_newline()?;
+
+        if edo_matches.is_empty() {
+            self.app
+                .writeln(format_args!("No EDO up to {max_edo}-EDO matches the notes"))?;
+            return Ok(());
+        }
+
+        for edo_match in edo_matches {
+            self.app.writeln(format_args!(
+                "- {}-EDO: max. deviation {:.3}¢, D4 = {:.3} Hz",
+                edo_match.num_steps,
+                edo_match.max_deviation,
+                edo_match.d4_pitch().as_hz(),
+            ))?;
+        }
+        self.print_newline()?;
+
+        let best_match = &edo_matches[0];
+        let layouts = IsomorphicLayout::find_by_edo(best_match.num_steps);
+
+        self.app.writeln(format_args!(
+            "==== Note usage in {}-EDO ====",
+            best_match.num_steps
+        ))?;
+        self.print_newline()?;
+
+        let mut header = format!("{:>5} | {:>6}", "Step", "Notes");
+        for layout in &layouts {
+            header.push_str(&format!(" | {:<12}", layout.genchain().to_string()));
+        }
+        self.app.writeln(header.trim_end())?;
+
+        for (step, count) in best_match.step_usage(pitch_usage) {
+            let mut row = format!("{step:>4}. | {count:>5}x");
+            for layout in &layouts {
+                row.push_str(&format!(" | {:<12}", layout.get_note_name(step)));
+            }
+            self.app.writeln(row.trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    fn print_scale_match(&mut self, scale_match: &ScaleMatchDto) -> CliResult {
+        self.app.writeln(format_args!(
+            "  {pitch:-^21} ‖ {target:-^28}",
+            pitch = "Pitch",
+            target = "Target Scale"
+        ))?;
+
+        for item in &scale_match.items {
+            self.app.writeln(format_args!(
+                "  {pitch:>11.3} Hz {count:>5}x ‖ {target_midi:>4} | IDX {index:>5} | {deviation:>+8.3}¢",
+                pitch = item.pitch_in_hz,
+                count = item.count,
+                target_midi = item.target_key_midi_number,
+                index = item.target_index,
+                deviation = item.deviation_in_cents,
+            ))?;
+        }
+        self.print_newline()?;
+
+        self.app.writeln(format_args!(
+            "Max. deviation: {:.3}¢ ({})",
+            scale_match.max_deviation_in_cents,
+            if scale_match.matches {
+                "matches"
+            } else {
+                "does not match"
+            }
+        ))?;
+
+        Ok(())
+    }
+}
+
+struct EdoMatch {
+    num_steps: u16,
+    /// Offset of the EDO grid relative to D4 in steps, within ±0.5 steps.
+    offset: f64,
+    max_deviation: f64,
+}
+
+impl EdoMatch {
+    fn find_all(max_edo: u16, pitch_usage: &[(Pitch, usize)], tolerance: Ratio) -> Vec<EdoMatch> {
+        (1..=max_edo)
+            .map(|num_steps| EdoMatch::find(num_steps, pitch_usage))
+            .filter(|edo_match| edo_match.max_deviation <= tolerance.as_cents())
+            .collect()
+    }
+
+    /// Fits an EDO grid to the given pitches.
+    ///
+    /// The offset of the grid is the circular mean of the fractional step positions s.t. EDOs with an arbitrary reference pitch are detected.
+    fn find(num_steps: u16, pitch_usage: &[(Pitch, usize)]) -> EdoMatch {
+        let (sin_sum, cos_sum) = pitch_usage
+            .iter()
+            .map(|&(pitch, _)| TAU * position_in_steps(num_steps, pitch))
+            .fold((0.0, 0.0), |(sin_sum, cos_sum), angle| {
+                (sin_sum + angle.sin(), cos_sum + angle.cos())
+            });
+        let offset = wrap(sin_sum.atan2(cos_sum) / TAU);
+
+        let mut edo_match = EdoMatch {
+            num_steps,
+            offset,
+            max_deviation: 0.0,
+        };
+        edo_match.max_deviation = pitch_usage
+            .iter()
+            .map(|&(pitch, _)| edo_match.deviation_in_steps(pitch).abs())
+            .fold(0.0, f64::max)
+            * edo_match.step_size_in_cents();
+
+        edo_match
+    }
+
+    fn step_size_in_cents(&self) -> f64 {
+        1200.0 / f64::from(self.num_steps)
+    }
+
+    fn d4_pitch(&self) -> Pitch {
+        d4() * Ratio::from_cents(self.offset * self.step_size_in_cents())
+    }
+
+    fn deviation_in_steps(&self, pitch: Pitch) -> f64 {
+        wrap(position_in_steps(self.num_steps, pitch) - self.offset)
+    }
+
+    /// Counts the notes per step of the octave, starting at the step closest to D.
+    fn step_usage(&self, pitch_usage: &[(Pitch, usize)]) -> BTreeMap<u16, usize> {
+        let mut step_usage = BTreeMap::new();
+        for &(pitch, count) in pitch_usage {
+            let step = (position_in_steps(self.num_steps, pitch) - self.offset).round() as i64;
+            let step = step.rem_euclid(i64::from(self.num_steps)) as u16;
+            *step_usage.entry(step).or_default() += count;
+        }
+        step_usage
+    }
+}
+
+fn position_in_steps(num_steps: u16, pitch: Pitch) -> f64 {
+    Ratio::between_pitches(d4(), pitch).as_octaves() * f64::from(num_steps)
+}
+
+fn d4() -> Pitch {
+    NoteLetter::D.in_octave(4).pitch()
+}
+
+fn wrap(steps: f64) -> f64 {
+    steps - steps.round()
+}
@@ -0,0 +1,215 @@
+use clap::Parser;
+use tune::{chord::Chord, math, pitch::Ratio, temperament::Val};
+
+use crate::{
+    dto::{ChordStepsDto, ChordVoicingDto, ChordsDto, ChordsInValDto, FractionDto},
+    output::OutputFormat,
+    scale::TargetScaleCommand,
+    App, CliResult,
+};
+
+#[derive(Parser)]
+pub(crate) struct ChordsOptions {
+    /// Chord to search for given by its harmonics, e.g. 4:5:6. Can be repeated.
+    /// If not given, a selection of common 7-limit chords is used.
+    #[arg(long = "chord")]
+    chords: Vec<Chord>,
+
+    /// Maximum deviation of a single note from the ideal chord
+    #[arg(long = "tol", default_value = "10c")]
+    tolerance: Ratio,
+
+    /// Largest acceptable numerator or denominator of the root's nearest fraction (ignoring powers of two)
+    #[arg(long = "lim", default_value = "11")]
+    odd_limit: u16,
+
+    /// Step size of an equal-step tuning, e.g. 1:31:2.
+    /// Instead of searching a target scale, the chords are mapped to steps using the patent val of the tuning.
+    #[arg(long = "val")]
+    val_step_size: Option<Ratio>,
+
+    #[command(subcommand)]
+    target_scale: Option<TargetScaleCommand>,
+}
+
+const DEFAULT_CHORDS: &[&[u16]] = &[
+    &[4, 5, 6],
+    &[10, 12, 15],
+    &[6, 7, 9],
+    &[4, 5, 6, 7],
+    &[8, 10, 12, 15],
+    &[10, 12, 15, 18],
+];
+
+impl ChordsOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let chords = match self.chords.is_empty() {
+            true => DEFAULT_CHORDS
+                .iter()
+                .map(|&harmonics| Chord::from_harmonics(harmonics).unwrap())
+                .collect(),
+            false => self.chords.clone(),
+        };
+
+        let target_scale = match (self.val_step_size, &self.target_scale) {
+            (Some(step_size), None) => return print_chords_in_val(app, &chords, step_size),
+            (None, Some(target_scale)) => target_scale,
+            (Some(_), Some(_)) => {
+                return Err("--val cannot be combined with a target scale"
+                    .to_owned()
+                    .into())
+            }
+            (None, None) => {
+                return Err("Either --val or a target scale must be given"
+                    .to_owned()
+                    .into())
+            }
+        };
+
+        let (scl, kbm) = target_scale.to_tuning()?;
+        let tuning = (&scl, kbm.kbm_root());
+
+        let mut voicings = Vec::new();
+        for degree in 0..i32::from(scl.num_items()) {
+            let nearest_fraction = scl
+                .relative_pitch_of(degree)
+                .nearest_fraction(self.odd_limit);
+
+            for chord in &chords {
+                for voicing in chord.find_in(&tuning, [degree], self.tolerance) {
+                    voicings.push(ChordVoicingDto {
+                        root_degree: degree,
+                        root_fraction: FractionDto {
+                            numer: nearest_fraction.numer,
+                            denom: nearest_fraction.denom,
+                            deviation_in_cents: nearest_fraction.deviation.as_cents(),
+                            num_octaves: nearest_fraction.num_octaves,
+                        },
+                        chord: chord.to_string(),
+                        degrees: voicing.notes.clone(),
+                        deviations_in_cents: voicing
+                            .deviations
+                            .iter()
+                            .map(|deviation| deviation.as_cents())
+                            .collect(),
+                        max_deviation_in_cents: voicing.max_deviation().as_cents(),
+                    });
+                }
+            }
+        }
+
+        let dto = ChordsDto {
+            tolerance_in_cents: self.tolerance.abs().as_cents(),
+            voicings,
+        };
+
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&dto);
+        }
+
+        app.writeln(format_args!(
+            "Chords within {:.1}¢ in {}",
+            dto.tolerance_in_cents,
+            scl.description(),
+        ))?;
+
+        for voicing in &dto.voicings {
+            let notes: Vec<_> = voicing
+                .degrees
+                .iter()
+                .map(|note| format!("{note:>3}"))
+                .collect();
+            let deviations: Vec<_> = voicing
+                .deviations_in_cents
+                .iter()
+                .map(|deviation| format!("{deviation:>+6.1}¢"))
+                .collect();
+
+            app.writeln(format_args!(
+                "{degree:>3} | {numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ | \
+                 {chord:<14} | {notes} | {deviations} | max {max_deviation:.1}¢",
+                degree = voicing.root_degree,
+                numer = voicing.root_fraction.numer,
+                denom = voicing.root_fraction.denom,
+                fract_deviation = voicing.root_fraction.deviation_in_cents,
+                chord = voicing.chord,
+                notes = notes.join(" "),
+                deviations = deviations.join(" "),
+                max_deviation = voicing.max_deviation_in_cents,
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn print_chords_in_val(app: &mut App, chords: &[Chord], step_size: Ratio) -> CliResult {
+    let prime_limit = math::U8_PRIMES
+        .iter()
+        .copied()
+        .filter(|&prime| {
+            chords.iter().any(|chord| {
+                chord
+                    .harmonics()
+                    .iter()
+                    .any(|&harmonic| harmonic % u16::from(prime) == 0)
+            })
+        })
+        .max()
+        .unwrap_or(2);
+    let val = Val::patent(step_size, prime_limit);
+
+    let dto = ChordsInValDto {
+        step_size_in_cents: step_size.as_cents(),
+        val: val.values().to_vec(),
+        voicings: chords
+            .iter()
+            .filter_map(|chord| {
+                chord.voicing_in_val(&val).map(|voicing| ChordStepsDto {
+                    chord: chord.to_string(),
+                    steps: voicing.notes.clone(),
+                    deviations_in_cents: voicing
+                        .deviations
+                        .iter()
+                        .map(|deviation| deviation.as_cents())
+                        .collect(),
+                    max_deviation_in_cents: voicing.max_deviation().as_cents(),
+                })
+            })
+            .collect(),
+    };
+
+    if app.format != OutputFormat::Text {
+        return app.write_dto(&dto);
+    }
+
+    let val_values: Vec<_> = dto.val.iter().map(u16::to_string).collect();
+    app.writeln(format_args!(
+        "Chords in the patent val <{}| of step size {:+.1}c",
+        val_values.join(", "),
+        dto.step_size_in_cents,
+    ))?;
+
+    for voicing in &dto.voicings {
+        let steps: Vec<_> = voicing
+            .steps
+            .iter()
+            .map(|step| format!("{step:>3}"))
+            .collect();
+        let deviations: Vec<_> = voicing
+            .deviations_in_cents
+            .iter()
+            .map(|deviation| format!("{deviation:>+6.1}¢"))
+            .collect();
+
+        app.writeln(format_args!(
+            "{chord:<14} | {steps} | {deviations} | max {max_deviation:.1}¢",
+            chord = voicing.chord,
+            steps = steps.join(" "),
+            deviations = deviations.join(" "),
+            max_deviation = voicing.max_deviation_in_cents,
+        ))?;
+    }
+
+    Ok(())
+}
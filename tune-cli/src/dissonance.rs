@@ -0,0 +1,85 @@
+use clap::Parser;
+use tune::{
+    consonance::{HarmonicEntropy, Spectrum},
+    pitch::{Pitch, Ratio},
+};
+
+use crate::{
+    dto::{DissonanceDto, DissonancePointDto},
+    output::{OutputDto, OutputFormat},
+    App, CliResult,
+};
+
+#[derive(Parser)]
+pub(crate) struct DissonanceOptions {
+    /// Lowest interval of the curves
+    #[arg(long = "lo", default_value = "0c")]
+    lowest_interval: Ratio,
+
+    /// Highest interval of the curves
+    #[arg(long = "hi", default_value = "1200c")]
+    highest_interval: Ratio,
+
+    /// Distance between two sample points
+    #[arg(long = "step", default_value = "1c")]
+    step_size: Ratio,
+
+    /// Largest product of numerator and denominator of the fractions considered in the harmonic entropy model
+    #[arg(long = "he-lim", default_value = "10000")]
+    max_complexity: u32,
+
+    /// Standard deviation of the harmonic entropy model
+    #[arg(long = "he-spread", default_value = "17c")]
+    spread: Ratio,
+
+    /// Spectrum used for the sensory dissonance curve given as a list of partials with optional amplitudes, e.g. 1,2.76@0.5,5.4@0.25.
+    /// If not given, a harmonic spectrum with 7 partials and decaying amplitudes is used.
+    #[arg(long = "spectrum")]
+    spectrum: Option<Spectrum>,
+
+    /// Fundamental frequency used for the sensory dissonance curve
+    #[arg(long = "fund", default_value = "261.626Hz")]
+    fundamental: Pitch,
+}
+
+impl DissonanceOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let step_size_in_cents = self.step_size.abs().as_cents();
+        if step_size_in_cents < 0.001 {
+            return Err("Step size must be at least 0.001c".to_owned().into());
+        }
+
+        let harmonic_entropy =
+            HarmonicEntropy::new(self.max_complexity, self.spread).ok_or_else(|| {
+                "Harmonic entropy limit must be at least 1 and spread must be positive".to_owned()
+            })?;
+        let spectrum = self
+            .spectrum
+            .clone()
+            .unwrap_or_else(|| Spectrum::harmonic(7));
+
+        let lowest_cents = self.lowest_interval.as_cents();
+        let num_steps =
+            ((self.highest_interval.as_cents() - lowest_cents) / step_size_in_cents + 1e-6).floor();
+
+        let points = (0..=num_steps.max(0.0) as u32)
+            .map(|step| {
+                let interval =
+                    Ratio::from_cents(lowest_cents + f64::from(step) * step_size_in_cents);
+                DissonancePointDto {
+                    interval_in_cents: interval.as_cents(),
+                    harmonic_entropy: harmonic_entropy.of(interval),
+                    sensory_dissonance: spectrum.dissonance_of(self.fundamental, interval),
+                }
+            })
+            .collect();
+
+        let dto = DissonanceDto { points };
+
+        match app.format {
+            // The curves are always printed as CSV unless another structured format is requested
+            OutputFormat::Text => Ok(dto.csv_table().write(&mut app.output)?),
+            _ => app.write_dto(&dto),
+        }
+    }
+}
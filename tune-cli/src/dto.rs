@@ -1,13 +1,52 @@
 use io::Read;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, io};
-use tune::{key::PianoKey, pitch::Pitch, tuning::KeyboardMapping};
+use tune::{
+    key::PianoKey,
+    layout::Layer,
+    pitch::{Pitch, Ratio},
+    scala::{self, Kbm, KbmRoot, PitchValue, Scl},
+    tuning::KeyboardMapping,
+};
 
-use crate::{error::ResultExt, CliError, CliResult};
+use crate::{
+    error::ResultExt,
+    output::{self, CsvTable, OutputDto},
+    CliError, CliResult,
+};
 
+/// Tuning interchange format written by the `scale`, `scl` and `kbm` commands.
 #[derive(Debug, Deserialize, Serialize)]
 pub enum TuneDto {
+    /// A list of keys with their pitches
     Scale(ScaleDto),
+    /// A scale in scl format with its pitch values as declared
+    Scl(SclDto),
+    /// A keyboard mapping in kbm format including unmapped keys
+    Kbm(KbmDto),
+    /// A rank-2 temperament scale given by its generators
+    Rank2(Rank2Dto),
+}
+
+impl TuneDto {
+    pub fn to_scl(&self) -> Result<Scl, String> {
+        match self {
+            TuneDto::Scl(scl) => scl.to_scl(),
+            TuneDto::Rank2(rank2) => rank2.to_scl(),
+            TuneDto::Scale(_) | TuneDto::Kbm(_) => {
+                Err("Expected an Scl or a Rank2 scale".to_owned())
+            }
+        }
+    }
+
+    pub fn to_kbm(&self) -> Result<Kbm, String> {
+        match self {
+            TuneDto::Kbm(kbm) => kbm.to_kbm(),
+            TuneDto::Scale(_) | TuneDto::Scl(_) | TuneDto::Rank2(_) => {
+                Err("Expected a Kbm".to_owned())
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -19,9 +58,16 @@ pub struct ScaleDto {
 
 impl ScaleDto {
     pub fn read(input: impl Read) -> CliResult<ScaleDto> {
-        serde_yaml::from_reader(input)
-            .handle_error::<CliError>("Could not parse scale file")
-            .map(|TuneDto::Scale(scale): _| scale)
+        let tune_dto = serde_yaml::from_reader(input)
+            .handle_error::<CliError>("Could not parse scale file")?;
+        match tune_dto {
+            TuneDto::Scale(scale) => Ok(scale),
+            TuneDto::Scl(_) | TuneDto::Kbm(_) | TuneDto::Rank2(_) => Err(
+                "Could not parse scale file\nExpected a Scale, e.g. the output of `tune scale`"
+                    .to_owned()
+                    .into(),
+            ),
+        }
     }
 
     pub fn keys(&self) -> Vec<PianoKey> {
@@ -31,6 +77,16 @@ impl ScaleDto {
             .collect()
     }
 
+    pub fn degrees(&self) -> HashMap<PianoKey, i32> {
+        self.items
+            .iter()
+            .filter_map(|item| {
+                item.degree
+                    .map(|degree| (PianoKey::from_midi_number(item.key_midi_number), degree))
+            })
+            .collect()
+    }
+
     pub fn to_keyboard_mapping(&self) -> impl KeyboardMapping<PianoKey> {
         DtoKeyboardMapping {
             key_map: self
@@ -51,6 +107,802 @@ impl ScaleDto {
 pub struct ScaleItemDto {
     pub key_midi_number: i32,
     pub pitch_in_hz: f64,
+    /// Scale degree of the key if the scale is based on an scl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degree: Option<i32>,
+    /// Note name of the scale degree according to the best isomorphic layout of the scl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_name: Option<String>,
+    /// Color layer of the scale degree according to the best isomorphic layout of the scl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_layer: Option<LayerDto>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum LayerDto {
+    Natural,
+    Sharp(u16),
+    Flat(u16),
+    Enharmonic(u16),
+}
+
+impl From<Layer> for LayerDto {
+    fn from(layer: Layer) -> Self {
+        match layer {
+            Layer::Natural => LayerDto::Natural,
+            Layer::Sharp(index) => LayerDto::Sharp(index),
+            Layer::Flat(index) => LayerDto::Flat(index),
+            Layer::Enharmonic(index) => LayerDto::Enharmonic(index),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SclDto {
+    pub description: String,
+    /// Pitch values as declared in the scl file, excluding the unison and including the period
+    pub items: Vec<PitchValueDto>,
+}
+
+impl SclDto {
+    pub fn from_scl(scl: &Scl) -> Self {
+        SclDto {
+            description: scl.description().to_owned(),
+            items: scl
+                .pitch_values()
+                .iter()
+                .map(|&pitch_value| match pitch_value {
+                    PitchValue::Cents(cents) => PitchValueDto::Cents(cents),
+                    PitchValue::Fraction(numer, None) => PitchValueDto::Int(numer),
+                    PitchValue::Fraction(numer, Some(denom)) => {
+                        PitchValueDto::Fraction { numer, denom }
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_scl(&self) -> Result<Scl, String> {
+        self.items
+            .iter()
+            .fold(Scl::builder(), |builder, item| match *item {
+                PitchValueDto::Cents(cents) => builder.push_cents(cents),
+                PitchValueDto::Fraction { numer, denom } => builder.push_fraction(numer, denom),
+                PitchValueDto::Int(int) => builder.push_int(int),
+            })
+            .build_with_description(&self.description)
+            .handle_error("Could not create scale from Scl")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum PitchValueDto {
+    Cents(f64),
+    Fraction { numer: u32, denom: u32 },
+    Int(u32),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KbmDto {
+    pub ref_key_midi_number: i32,
+    pub ref_pitch_in_hz: f64,
+    pub root_offset: i32,
+    /// Lower key bound (inclusive)
+    pub lower_key_midi_number: i32,
+    /// Upper key bound (exclusive)
+    pub upper_key_midi_number: i32,
+    pub formal_octave: i16,
+    /// Scale degrees of the keyboard mapping entries where `null` stands for an unmapped key. An empty mapping is linear.
+    pub key_mapping: Vec<Option<i16>>,
+}
+
+impl KbmDto {
+    pub fn from_kbm(kbm: &Kbm) -> Self {
+        let kbm_root = kbm.kbm_root();
+        KbmDto {
+            ref_key_midi_number: kbm_root.ref_key.midi_number(),
+            ref_pitch_in_hz: kbm_root.ref_pitch.as_hz(),
+            root_offset: kbm_root.root_offset,
+            lower_key_midi_number: kbm.range().start.midi_number(),
+            upper_key_midi_number: kbm.range().end.midi_number(),
+            formal_octave: kbm.formal_octave(),
+            key_mapping: kbm.key_mapping().to_vec(),
+        }
+    }
+
+    pub fn to_kbm(&self) -> Result<Kbm, String> {
+        let kbm_root = KbmRoot {
+            ref_key: PianoKey::from_midi_number(self.ref_key_midi_number),
+            ref_pitch: Pitch::from_hz(self.ref_pitch_in_hz),
+            root_offset: self.root_offset,
+        };
+
+        let mut builder = Kbm::builder(kbm_root).range(
+            PianoKey::from_midi_number(self.lower_key_midi_number)
+                ..PianoKey::from_midi_number(self.upper_key_midi_number),
+        );
+        for item in &self.key_mapping {
+            builder = match *item {
+                Some(scale_degree) => builder.push_mapped_key(scale_degree),
+                None => builder.push_unmapped_key(),
+            };
+        }
+        if !self.key_mapping.is_empty() {
+            builder = builder.formal_octave(self.formal_octave);
+        }
+        builder
+            .build()
+            .handle_error("Could not create keyboard mapping from Kbm")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Rank2Dto {
+    pub description: String,
+    pub generator_in_cents: f64,
+    pub period_in_cents: f64,
+    pub num_pos_generations: u16,
+    pub num_neg_generations: u16,
+}
+
+impl Rank2Dto {
+    pub fn to_scl(&self) -> Result<Scl, String> {
+        scala::create_rank2_temperament_scale(
+            self.description.clone(),
+            Ratio::from_cents(self.generator_in_cents),
+            self.num_pos_generations,
+            self.num_neg_generations,
+            Ratio::from_cents(self.period_in_cents),
+        )
+        .handle_error("Could not create scale from Rank2")
+    }
+}
+
+impl OutputDto for TuneDto {
+    fn csv_table(&self) -> CsvTable {
+        match self {
+            TuneDto::Scale(scale) => {
+                let mut table = CsvTable::new([
+                    "key_midi_number",
+                    "pitch_in_hz",
+                    "degree",
+                    "note_name",
+                    "color_layer",
+                ]);
+                for item in &scale.items {
+                    table.push_row([
+                        item.key_midi_number.to_string(),
+                        item.pitch_in_hz.to_string(),
+                        item.degree
+                            .map(|degree| degree.to_string())
+                            .unwrap_or_default(),
+                        item.note_name.clone().unwrap_or_default(),
+                        item.color_layer
+                            .map(|color_layer| match color_layer {
+                                LayerDto::Natural => "natural".to_owned(),
+                                LayerDto::Sharp(index) => format!("sharp {index}"),
+                                LayerDto::Flat(index) => format!("flat {index}"),
+                                LayerDto::Enharmonic(index) => format!("enharmonic {index}"),
+                            })
+                            .unwrap_or_default(),
+                    ]);
+                }
+                table
+            }
+            TuneDto::Scl(scl) => {
+                let mut table = CsvTable::new(["degree", "pitch_value"]);
+                for (degree, item) in scl.items.iter().enumerate() {
+                    table.push_row([
+                        (degree + 1).to_string(),
+                        match *item {
+                            PitchValueDto::Cents(cents) => format!("{cents:?}"),
+                            PitchValueDto::Fraction { numer, denom } => format!("{numer}/{denom}"),
+                            PitchValueDto::Int(int) => int.to_string(),
+                        },
+                    ]);
+                }
+                table
+            }
+            TuneDto::Kbm(kbm) => {
+                let mut table = CsvTable::new(["index", "scale_degree"]);
+                for (index, item) in kbm.key_mapping.iter().enumerate() {
+                    table.push_row([
+                        index.to_string(),
+                        item.map(|degree| degree.to_string())
+                            .unwrap_or_else(|| "x".to_owned()),
+                    ]);
+                }
+                table
+            }
+            TuneDto::Rank2(rank2) => {
+                let mut table = CsvTable::new([
+                    "generator_in_cents",
+                    "period_in_cents",
+                    "num_pos_generations",
+                    "num_neg_generations",
+                ]);
+                table.push_row([
+                    rank2.generator_in_cents.to_string(),
+                    rank2.period_in_cents.to_string(),
+                    rank2.num_pos_generations.to_string(),
+                    rank2.num_neg_generations.to_string(),
+                ]);
+                table
+            }
+        }
+    }
+}
+
+/// A tuning composed of scales and tuning combinators.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum TuningDto {
+    /// A scale given by the arguments of the `scale` command, e.g. `ref-note 62 steps 1:16:2`
+    Scale(String),
+    Transpose {
+        ratio: String,
+        tuning: Box<TuningDto>,
+    },
+    Stretch {
+        origin_in_hz: f64,
+        stretch: String,
+        tuning: Box<TuningDto>,
+    },
+    Split {
+        key_midi_number: i32,
+        lower: Box<TuningDto>,
+        upper: Box<TuningDto>,
+    },
+    Override {
+        items: Vec<ScaleItemDto>,
+        tuning: Box<TuningDto>,
+    },
+    /// Retunes each pitch to the closest pitch of the `reference` scale, e.g. `ref-note 62 steps 1:12:2`
+    Snap {
+        reference: String,
+        tuning: Box<TuningDto>,
+    },
+}
+
+impl TuningDto {
+    pub fn read(input: impl Read) -> CliResult<TuningDto> {
+        serde_yaml::from_reader(input).handle_error::<CliError>("Could not parse tuning file")
+    }
+}
+
+/// A raw MIDI message with a time stamp relative to the start of a session.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MidiEventDto {
+    pub time_in_secs: f64,
+    pub message: Vec<u8>,
+}
+
+/// Output of the `dump` and `diff` commands.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ScaleTableDto {
+    pub root_key_midi_number: i32,
+    pub root_pitch_in_hz: Option<f64>,
+    pub items: Vec<ScaleTableItemDto>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<SclAnalysisDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ScaleTableItemDto {
+    pub key_midi_number: i32,
+    pub index: i32,
+    /// Scale degree of the source key, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degree: Option<i32>,
+    pub pitch_in_hz: f64,
+    pub nearest_fraction: FractionDto,
+    pub target_key_midi_number: i32,
+    /// Note name of the target key (`dump` command)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_note_name: Option<String>,
+    /// Index of the target key relative to the reference key of the target scale (`diff` command)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_index: Option<i32>,
+    pub deviation_in_cents: f64,
+}
+
+/// The nearest fraction of an interval ignoring powers of two.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FractionDto {
+    pub numer: u16,
+    pub denom: u16,
+    pub deviation_in_cents: f64,
+    pub num_octaves: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SclAnalysisDto {
+    pub constant_structure: bool,
+    pub proper: bool,
+    pub strictly_proper: bool,
+    pub myhill_property: bool,
+    pub distributionally_even: bool,
+    pub mos: bool,
+    pub interval_classes: Vec<IntervalClassDto>,
+    pub interval_vector: Vec<IntervalSizeDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IntervalClassDto {
+    pub num_steps: u16,
+    pub sizes: Vec<IntervalSizeDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IntervalSizeDto {
+    pub size_in_cents: f64,
+    pub count: u16,
+}
+
+impl OutputDto for ScaleTableDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "key_midi_number",
+            "index",
+            "degree",
+            "pitch_in_hz",
+            "fraction",
+            "fraction_deviation_in_cents",
+            "fraction_num_octaves",
+            "target_key_midi_number",
+            "target_note_name",
+            "target_index",
+            "deviation_in_cents",
+        ]);
+        for item in &self.items {
+            table.push_row([
+                item.key_midi_number.to_string(),
+                item.index.to_string(),
+                item.degree
+                    .map(|degree| degree.to_string())
+                    .unwrap_or_default(),
+                item.pitch_in_hz.to_string(),
+                format!(
+                    "{}/{}",
+                    item.nearest_fraction.numer, item.nearest_fraction.denom
+                ),
+                item.nearest_fraction.deviation_in_cents.to_string(),
+                item.nearest_fraction.num_octaves.to_string(),
+                item.target_key_midi_number.to_string(),
+                item.target_note_name.clone().unwrap_or_default(),
+                item.target_index
+                    .map(|index| index.to_string())
+                    .unwrap_or_default(),
+                item.deviation_in_cents.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `est` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EstDto {
+    pub step_size_in_cents: f64,
+    pub fret_constant: f64,
+    pub vals: Vec<EstValDto>,
+}
+
+/// Properties of the patent val or the b-val of an equal-step tuning.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EstValDto {
+    pub num_steps_per_octave: u16,
+    pub wart: String,
+    pub stretch_in_cents: f64,
+    pub odd_limit: u8,
+    pub values: Vec<u16>,
+    pub errors_in_cents: Vec<f64>,
+    pub errors_in_steps: Vec<f64>,
+    pub te_simple_badness: f64,
+    pub subgroup: Vec<u8>,
+    pub tempered_out_commas: Vec<CommaDto>,
+    pub interval_locations: Vec<IntervalLocationDto>,
+    pub notations: Vec<NotationDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommaDto {
+    pub prime_limit: u8,
+    pub fraction: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IntervalLocationDto {
+    pub fraction: String,
+    pub tempered_location: i32,
+    pub patent_location: f64,
+}
+
+/// Generalized notation of an equal-step tuning based on an isomorphic layout.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotationDto {
+    pub genchain: String,
+    pub num_cycles: u16,
+    pub primary_step: u16,
+    pub secondary_step: u16,
+    pub sharpness: i32,
+    pub scale_name: String,
+    pub note_names: Vec<String>,
+}
+
+impl OutputDto for EstDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "num_steps_per_octave",
+            "wart",
+            "step_size_in_cents",
+            "fret_constant",
+            "stretch_in_cents",
+            "odd_limit",
+            "values",
+            "errors_in_cents",
+            "te_simple_badness",
+            "subgroup",
+            "tempered_out_commas",
+            "notations",
+        ]);
+        for val in &self.vals {
+            table.push_row([
+                val.num_steps_per_octave.to_string(),
+                val.wart.clone(),
+                self.step_size_in_cents.to_string(),
+                self.fret_constant.to_string(),
+                val.stretch_in_cents.to_string(),
+                val.odd_limit.to_string(),
+                output::join(&val.values),
+                output::join(&val.errors_in_cents),
+                val.te_simple_badness.to_string(),
+                val.subgroup
+                    .iter()
+                    .map(u8::to_string)
+                    .collect::<Vec<_>>()
+                    .join("."),
+                output::join(val.tempered_out_commas.iter().map(|comma| &comma.fraction)),
+                output::join(val.notations.iter().map(|notation| &notation.genchain)),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `mos find` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MosesDto {
+    pub period_in_cents: f64,
+    pub generator_in_cents: f64,
+    pub moses: Vec<MosDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MosDto {
+    pub num_notes: u32,
+    pub num_large_steps: u16,
+    pub num_small_steps: u16,
+    pub large_step_in_cents: f64,
+    pub small_step_in_cents: f64,
+    pub step_ratio: f64,
+    /// Chroma of the MOS where positive values indicate a bright generator and negative values a dark generator
+    pub sharpness_in_cents: f64,
+    /// Whether the MOS is the best equal-step approximation found so far
+    pub best_approximation: bool,
+}
+
+impl OutputDto for MosesDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "num_notes",
+            "num_large_steps",
+            "num_small_steps",
+            "large_step_in_cents",
+            "small_step_in_cents",
+            "step_ratio",
+            "sharpness_in_cents",
+            "best_approximation",
+        ]);
+        for mos in &self.moses {
+            table.push_row([
+                mos.num_notes.to_string(),
+                mos.num_large_steps.to_string(),
+                mos.num_small_steps.to_string(),
+                mos.large_step_in_cents.to_string(),
+                mos.small_step_in_cents.to_string(),
+                mos.step_ratio.to_string(),
+                mos.sharpness_in_cents.to_string(),
+                mos.best_approximation.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `mos gen` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MosGeneratorsDto {
+    pub num_large_steps: u16,
+    pub num_small_steps: u16,
+    pub pattern: String,
+    pub period_in_cents: f64,
+    pub equalized_generator: MosGeneratorDto,
+    pub proper_generator: MosGeneratorDto,
+    pub collapsed_generator: MosGeneratorDto,
+}
+
+/// A generator of `num_steps` steps of an equal division of the period into `num_divisions` steps.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MosGeneratorDto {
+    pub num_steps: u16,
+    pub num_divisions: u16,
+    pub size_in_cents: f64,
+}
+
+impl OutputDto for MosGeneratorsDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "num_large_steps",
+            "num_small_steps",
+            "pattern",
+            "period_in_cents",
+            "generator",
+            "num_steps",
+            "num_divisions",
+            "size_in_cents",
+        ]);
+        for (name, generator) in [
+            ("equalized", &self.equalized_generator),
+            ("proper", &self.proper_generator),
+            ("collapsed", &self.collapsed_generator),
+        ] {
+            table.push_row([
+                self.num_large_steps.to_string(),
+                self.num_small_steps.to_string(),
+                self.pattern.clone(),
+                self.period_in_cents.to_string(),
+                name.to_owned(),
+                generator.num_steps.to_string(),
+                generator.num_divisions.to_string(),
+                generator.size_in_cents.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `chords` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChordsDto {
+    pub tolerance_in_cents: f64,
+    pub voicings: Vec<ChordVoicingDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChordVoicingDto {
+    pub root_degree: i32,
+    pub root_fraction: FractionDto,
+    pub chord: String,
+    pub degrees: Vec<i32>,
+    pub deviations_in_cents: Vec<f64>,
+    pub max_deviation_in_cents: f64,
+}
+
+impl OutputDto for ChordsDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "root_degree",
+            "root_fraction",
+            "chord",
+            "degrees",
+            "deviations_in_cents",
+            "max_deviation_in_cents",
+        ]);
+        for voicing in &self.voicings {
+            table.push_row([
+                voicing.root_degree.to_string(),
+                format!(
+                    "{}/{}",
+                    voicing.root_fraction.numer, voicing.root_fraction.denom
+                ),
+                voicing.chord.clone(),
+                output::join(&voicing.degrees),
+                output::join(&voicing.deviations_in_cents),
+                voicing.max_deviation_in_cents.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `chords` command when `--val` is given.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChordsInValDto {
+    pub step_size_in_cents: f64,
+    pub val: Vec<u16>,
+    pub voicings: Vec<ChordStepsDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChordStepsDto {
+    pub chord: String,
+    pub steps: Vec<i32>,
+    pub deviations_in_cents: Vec<f64>,
+    pub max_deviation_in_cents: f64,
+}
+
+impl OutputDto for ChordsInValDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new([
+            "chord",
+            "steps",
+            "deviations_in_cents",
+            "max_deviation_in_cents",
+        ]);
+        for voicing in &self.voicings {
+            table.push_row([
+                voicing.chord.clone(),
+                output::join(&voicing.steps),
+                output::join(&voicing.deviations_in_cents),
+                voicing.max_deviation_in_cents.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `dissonance` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DissonanceDto {
+    pub points: Vec<DissonancePointDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DissonancePointDto {
+    pub interval_in_cents: f64,
+    pub harmonic_entropy: f64,
+    pub sensory_dissonance: f64,
+}
+
+impl OutputDto for DissonanceDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new(["cents", "harmonic_entropy", "sensory_dissonance"]);
+        for point in &self.points {
+            table.push_row([
+                format!("{:.3}", point.interval_in_cents),
+                format!("{:.6}", point.harmonic_entropy),
+                format!("{:.6}", point.sensory_dissonance),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `mts` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MtsDto {
+    pub messages: Vec<MtsMessageDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MtsMessageDto {
+    /// The MIDI channel the message is addressed to. Not set for System Exclusive messages that affect all channels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u8>,
+    pub bytes: Vec<String>,
+}
+
+impl OutputDto for MtsDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new(["index", "channel", "bytes"]);
+        for (index, message) in self.messages.iter().enumerate() {
+            table.push_row([
+                index.to_string(),
+                message
+                    .channel
+                    .map(|channel| channel.to_string())
+                    .unwrap_or_default(),
+                output::join(&message.bytes),
+            ]);
+        }
+        table
+    }
+}
+
+/// Output of the `analyze-midi` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MidiAnalysisDto {
+    pub num_notes: usize,
+    pub num_distinct_pitches: usize,
+    pub lowest_pitch_in_hz: f64,
+    pub highest_pitch_in_hz: f64,
+    pub tolerance_in_cents: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edo_matches: Option<Vec<EdoMatchDto>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale_match: Option<ScaleMatchDto>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EdoMatchDto {
+    pub num_steps: u16,
+    pub max_deviation_in_cents: f64,
+    pub d4_pitch_in_hz: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ScaleMatchDto {
+    pub items: Vec<PitchMatchDto>,
+    pub max_deviation_in_cents: f64,
+    pub matches: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PitchMatchDto {
+    pub pitch_in_hz: f64,
+    pub count: usize,
+    pub target_key_midi_number: i32,
+    pub target_index: i32,
+    pub deviation_in_cents: f64,
+}
+
+impl OutputDto for MidiAnalysisDto {
+    fn csv_table(&self) -> CsvTable {
+        match &self.scale_match {
+            None => {
+                let mut table =
+                    CsvTable::new(["num_steps", "max_deviation_in_cents", "d4_pitch_in_hz"]);
+                for edo_match in self.edo_matches.iter().flatten() {
+                    table.push_row([
+                        edo_match.num_steps.to_string(),
+                        edo_match.max_deviation_in_cents.to_string(),
+                        edo_match.d4_pitch_in_hz.to_string(),
+                    ]);
+                }
+                table
+            }
+            Some(scale_match) => {
+                let mut table = CsvTable::new([
+                    "pitch_in_hz",
+                    "count",
+                    "target_key_midi_number",
+                    "target_index",
+                    "deviation_in_cents",
+                ]);
+                for item in &scale_match.items {
+                    table.push_row([
+                        item.pitch_in_hz.to_string(),
+                        item.count.to_string(),
+                        item.target_key_midi_number.to_string(),
+                        item.target_index.to_string(),
+                        item.deviation_in_cents.to_string(),
+                    ]);
+                }
+                table
+            }
+        }
+    }
+}
+
+/// Output of the `devices` command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MidiDevicesDto {
+    pub readable: Vec<String>,
+    pub writable: Vec<String>,
+}
+
+impl OutputDto for MidiDevicesDto {
+    fn csv_table(&self) -> CsvTable {
+        let mut table = CsvTable::new(["direction", "name"]);
+        for name in &self.readable {
+            table.push_row(["readable", name]);
+        }
+        for name in &self.writable {
+            table.push_row(["writable", name]);
+        }
+        table
+    }
 }
 
 struct DtoKeyboardMapping {
@@ -8,10 +8,24 @@ use tune::{
     layout::IsomorphicLayout,
     math,
     pitch::Ratio,
-    temperament::{self, CommaCatalog, Val},
+    temperament::{self, Comma, CommaCatalog, Val},
 };
 
-use crate::{App, CliResult};
+use crate::{
+    dto::{CommaDto, EstDto, EstValDto, IntervalLocationDto, NotationDto},
+    output::OutputFormat,
+    App, CliResult,
+};
+
+const INTERVALS_TO_LOCATE: [&str; 7] = [
+    "septimal minor third",
+    "minor third",
+    "major third",
+    "perfect fourth",
+    "perfect fifth",
+    "harmonic seventh",
+    "octave",
+];
 
 #[derive(Parser)]
 pub(crate) struct EstOptions {
@@ -29,6 +43,10 @@ pub(crate) struct EstOptions {
 
 impl EstOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&self.to_dto());
+        }
+
         let layouts = IsomorphicLayout::find_by_step_size(self.step_size);
 
         for print_b_val in [false, true] {
@@ -64,13 +82,9 @@ impl EstOptions {
                     printer.print_tempered_out_commas()?;
                     printer.print_newline()?;
 
-                    printer.print_interval_location("septimal minor third")?;
-                    printer.print_interval_location("minor third")?;
-                    printer.print_interval_location("major third")?;
-                    printer.print_interval_location("perfect fourth")?;
-                    printer.print_interval_location("perfect fifth")?;
-                    printer.print_interval_location("harmonic seventh")?;
-                    printer.print_interval_location("octave")?;
+                    for interval_name in INTERVALS_TO_LOCATE {
+                        printer.print_interval_location(interval_name)?;
+                    }
 
                     printer.print_newline()?;
 
@@ -86,6 +100,102 @@ impl EstOptions {
     }
 }
 
+impl EstOptions {
+    fn to_dto(&self) -> EstDto {
+        let layouts = IsomorphicLayout::find_by_step_size(self.step_size);
+        let catalog = CommaCatalog::new(temperament::huygens_fokker_intervals());
+
+        let mut vals = Vec::new();
+        for b_val in [false, true] {
+            let layouts: Vec<_> = layouts
+                .iter()
+                .filter(|layout| layout.b_val() == b_val)
+                .collect();
+            let Some(first_layout) = layouts.first() else {
+                continue;
+            };
+
+            let mut val = Val::patent(self.step_size, self.odd_limit);
+            if b_val {
+                val.pick_alternative(1);
+            }
+
+            vals.push(EstValDto {
+                num_steps_per_octave: val.values()[0],
+                wart: first_layout.wart().to_owned(),
+                stretch_in_cents: val.errors().next().unwrap().as_cents(),
+                odd_limit: self.odd_limit,
+                values: val.values().to_vec(),
+                errors_in_cents: val.errors().map(Ratio::as_cents).collect(),
+                errors_in_steps: val.errors_in_steps().collect(),
+                te_simple_badness: val.te_simple_badness(),
+                subgroup: val.subgroup(self.error_threshold).into_iter().collect(),
+                tempered_out_commas: tempered_out_commas(&val, &catalog)
+                    .map(|comma| CommaDto {
+                        prime_limit: comma.prime_limit(),
+                        fraction: fraction_of(comma),
+                        description: comma.description().to_owned(),
+                    })
+                    .collect(),
+                interval_locations: INTERVALS_TO_LOCATE
+                    .iter()
+                    .map(|&interval_name| {
+                        let interval = catalog.comma_for_name(interval_name).unwrap();
+                        IntervalLocationDto {
+                            fraction: fraction_of(interval),
+                            tempered_location: val.map(interval).unwrap_or_default(),
+                            patent_location: interval
+                                .as_ratio()
+                                .num_equal_steps_of_size(val.step_size())
+                                .round(),
+                        }
+                    })
+                    .collect(),
+                notations: layouts
+                    .iter()
+                    .map(|layout| NotationDto {
+                        genchain: layout.genchain().to_string(),
+                        num_cycles: layout.pergen().num_cycles(),
+                        primary_step: layout.mos().primary_step(),
+                        secondary_step: layout.mos().secondary_step(),
+                        sharpness: layout.mos().sharpness(),
+                        scale_name: layout.get_scale_name().to_owned(),
+                        note_names: (0..layout.pergen().period())
+                            .map(|index| layout.get_note_name(index))
+                            .collect(),
+                    })
+                    .collect(),
+            });
+        }
+
+        EstDto {
+            step_size_in_cents: self.step_size.as_cents(),
+            fret_constant: fret_constant(self.step_size),
+            vals,
+        }
+    }
+}
+
+fn tempered_out_commas<'a>(
+    val: &'a Val,
+    catalog: &'a CommaCatalog,
+) -> impl Iterator<Item = &'a Comma> + 'a {
+    math::U8_PRIMES
+        .iter()
+        .take_while(|&&limit| limit <= val.prime_limit())
+        .flat_map(|&limit| catalog.commas_for_limit(limit))
+        .filter(|comma| val.tempers_out(comma) && comma.as_fraction().is_some())
+}
+
+fn fraction_of(comma: &Comma) -> String {
+    let (numer, denom) = comma.as_fraction().unwrap();
+    format!("{numer}/{denom}")
+}
+
+fn fret_constant(step_size: Ratio) -> f64 {
+    step_size.as_float() / (step_size.as_float() - 1.0)
+}
+
 struct EstPrinter<'a, 'b> {
     app: &'a mut App<'b>,
     val: Val,
@@ -116,7 +226,7 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
     }
 
     fn print_basic_information(&mut self, step_size: Ratio) -> io::Result<()> {
-        let fret_constant = step_size.as_float() / (step_size.as_float() - 1.0);
+        let fret_constant = fret_constant(step_size);
         self.app.writeln(format_args!(
             "- step size: {step_size:#}\n\
              - fret constant: {fret_constant:.3}",
@@ -157,25 +267,13 @@ impl<'a, 'b> EstPrinter<'a, 'b> {
     }
 
     fn print_tempered_out_commas(&mut self) -> io::Result<()> {
-        let val = &self.val;
-
-        for &limit in math::U8_PRIMES
-            .iter()
-            .take_while(|&&limit| limit <= val.prime_limit())
-        {
-            for comma in self.catalog.commas_for_limit(limit) {
-                if self.val.tempers_out(comma) {
-                    if let Some((numer, denom)) = comma.as_fraction() {
-                        self.app.writeln(format_args!(
-                            "- tempers out {}-limit {}/{} ({})",
-                            comma.prime_limit(),
-                            numer,
-                            denom,
-                            comma.description()
-                        ))?;
-                    }
-                }
-            }
+        for comma in tempered_out_commas(&self.val, &self.catalog) {
+            self.app.writeln(format_args!(
+                "- tempers out {}-limit {} ({})",
+                comma.prime_limit(),
+                fraction_of(comma),
+                comma.description()
+            ))?;
         }
 
         Ok(())
@@ -1,3 +1,6 @@
+mod analyze;
+mod chords;
+mod dissonance;
 mod dto;
 mod error;
 mod est;
@@ -5,9 +8,13 @@ mod live;
 mod midi;
 mod mos;
 mod mts;
+mod output;
+mod pipeline;
 mod portable;
+mod retune;
 mod scala;
 mod scale;
+mod spec;
 
 use std::{
     fmt::{self, Display},
@@ -16,7 +23,10 @@ use std::{
     path::PathBuf,
 };
 
+use analyze::AnalyzeMidiOptions;
+use chords::ChordsOptions;
 use clap::Parser;
+use dissonance::DissonanceOptions;
 use error::ResultExt;
 use est::EstOptions;
 use futures::executor;
@@ -24,6 +34,8 @@ use io::Read;
 use live::LiveOptions;
 use mos::MosCommand;
 use mts::MtsOptions;
+use output::{OutputDto, OutputFormat};
+use retune::RetuneOptions;
 use scala::{KbmCommand, SclOptions};
 use scale::{DiffOptions, DumpOptions, ScaleCommand};
 
@@ -37,6 +49,11 @@ struct MainOptions {
     #[arg(long = "of")]
     output_file: Option<PathBuf>,
 
+    /// Output format. Analysis commands and `mts` can print structured data instead of text tables.
+    /// The MIDI commands `live` and `retune` only support text output
+    #[arg(long = "format", value_enum, default_value = "text", global = true)]
+    format: OutputFormat,
+
     #[command(subcommand)]
     command: MainCommand,
 }
@@ -71,6 +88,14 @@ enum MainCommand {
     #[command(name = "diff")]
     Diff(DiffOptions),
 
+    /// List the chords supported by each degree of a scale
+    #[command(name = "chords")]
+    Chords(ChordsOptions),
+
+    /// Print harmonic entropy and sensory dissonance curves in CSV format
+    #[command(name = "dissonance")]
+    Dissonance(DissonanceOptions),
+
     /// Print MIDI Tuning Standard messages and/or send them to MIDI devices
     #[command(name = "mts")]
     Mts(MtsOptions),
@@ -81,6 +106,15 @@ enum MainCommand {
     #[command(name = "live")]
     Live(LiveOptions),
 
+    /// Retune a Standard MIDI File s.t. it can be played back on synthesizers with limited tuning support.
+    /// This works the same way as the `live` command but reads the MIDI data from a file and writes the modified MIDI data to another file.
+    #[command(name = "retune")]
+    Retune(RetuneOptions),
+
+    /// Analyze the pitches used in a Standard MIDI File and find a matching EDO or compare them to a given scale
+    #[command(name = "analyze-midi")]
+    AnalyzeMidi(AnalyzeMidiOptions),
+
     /// List MIDI devices
     #[command(name = "devices")]
     Devices,
@@ -97,6 +131,7 @@ impl MainOptions {
             input: Box::new(io::stdin()),
             output,
             error: Box::new(io::stderr()),
+            format: self.format,
         };
 
         self.command.run(&mut app).await
@@ -113,10 +148,28 @@ impl MainCommand {
             MainCommand::Scale(options) => options.run(app),
             MainCommand::Dump(options) => options.run(app),
             MainCommand::Diff(options) => options.run(app),
+            MainCommand::Chords(options) => options.run(app),
+            MainCommand::Dissonance(options) => options.run(app),
             MainCommand::Mts(options) => options.run(app),
-            MainCommand::Live(options) => options.run(app).await,
-            MainCommand::Devices => midi::print_midi_devices(&mut app.output, "tune-cli")
-                .handle_error("Could not print MIDI devices"),
+            // The live retuning commands do not provide structured output
+            MainCommand::Live(options) => {
+                app.require_text_format()?;
+                options.run(app).await
+            }
+            MainCommand::Retune(options) => {
+                app.require_text_format()?;
+                options.run(app)
+            }
+            MainCommand::AnalyzeMidi(options) => options.run(app),
+            MainCommand::Devices => match app.format {
+                OutputFormat::Text => midi::print_midi_devices(&mut app.output, "tune-cli")
+                    .handle_error("Could not print MIDI devices"),
+                _ => {
+                    let devices = midi::list_midi_devices("tune-cli")
+                        .handle_error::<CliError>("Could not list MIDI devices")?;
+                    app.write_dto(&devices)
+                }
+            },
         }
     }
 }
@@ -153,6 +206,7 @@ pub fn run_in_wasm_env(
         input: Box::new(input),
         output: Box::new(output),
         error: Box::new(error),
+        format: OutputFormat::Text,
     };
 
     let command = match MainCommand::try_parse_from(args) {
@@ -177,6 +231,7 @@ struct App<'a> {
     input: Box<dyn 'a + Read>,
     output: Box<dyn 'a + Write>,
     error: Box<dyn 'a + Write>,
+    format: OutputFormat,
 }
 
 impl App<'_> {
@@ -195,6 +250,30 @@ impl App<'_> {
     pub fn read(&mut self) -> &mut dyn Read {
         &mut self.input
     }
+
+    /// Writes the given DTO in the requested [`OutputFormat`]. YAML is used if text output was requested.
+    pub fn write_dto(&mut self, dto: &impl OutputDto) -> CliResult {
+        match self.format {
+            OutputFormat::Text | OutputFormat::Yaml => serde_yaml::to_writer(&mut self.output, dto)
+                .handle_error("Could not write YAML output"),
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut self.output, dto)
+                    .handle_error::<CliError>("Could not write JSON output")?;
+                Ok(self.writeln("")?)
+            }
+            OutputFormat::Csv => Ok(dto.csv_table().write(&mut self.output)?),
+        }
+    }
+
+    /// Fails if a structured output format was requested for a command that only supports text output.
+    pub fn require_text_format(&self) -> CliResult {
+        match self.format {
+            OutputFormat::Text => Ok(()),
+            format => {
+                Err(format!("The output format `{format}` is not supported by this command").into())
+            }
+        }
+    }
 }
 
 pub type CliResult<T = ()> = Result<T, CliError>;
@@ -1,32 +1,63 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    hash::Hash,
+    path::PathBuf,
+    time::Duration,
+};
+
+use async_std::io;
 use clap::Parser;
 use flume::Sender;
+use futures::{future, FutureExt};
 use tune::{
     midi::{ChannelMessage, ChannelMessageType},
-    tuner::{AotTuner, JitTuner, MidiTarget, MidiTunerMessageHandler, PoolingMode},
+    pitch::{Pitch, Ratio},
+    tuner::{
+        AdaptiveTuner, AotTuner, JitTuner, MidiTarget, MidiTunerMessageHandler, PoolingMode,
+        TunableMidi, TunableSynth,
+    },
 };
 
 use crate::{
     error::ResultExt,
-    midi::{self, MidiInArgs, MidiOutArgs, MidiSource, MultiChannelOffset, TuningMethod},
+    midi::{
+        self, MidiClock, MidiEndpoint, MidiInArgs, MidiOutArgs, MidiSource, SmfRecorder,
+        TuningMethod,
+    },
+    pipeline::{Pipeline, PipelineEvent},
     App, CliError, CliResult, ScaleCommand,
 };
 
 #[derive(Parser)]
 pub(crate) struct LiveOptions {
-    /// MIDI input device
+    /// MIDI input device.
+    /// Use `file:<location>` to replay the MIDI events of a Standard MIDI File or a JSON event file (.json) instead.
+    /// The session ends when all events have been replayed.
     #[arg(long = "midi-in")]
-    midi_in_device: String,
+    midi_in: MidiEndpoint,
 
     #[command(flatten)]
     midi_in_args: MidiInArgs,
 
-    /// MIDI output device
+    /// YAML file describing how incoming MIDI events are transformed before they are tuned.
+    /// Supports key-range splits and channel assignments with individual scales, velocity curves, transpositions in scale degrees and controller remapping.
+    #[arg(long = "pipeline")]
+    pipeline_file: Option<PathBuf>,
+
+    /// MIDI output device.
+    /// Use `file:<location>` to write the MIDI output to a Standard MIDI File or a JSON event file (.json) instead.
     #[arg(long = "midi-out")]
-    midi_out_device: String,
+    midi_out: MidiEndpoint,
 
     #[command(flatten)]
     midi_out_args: MidiOutArgs,
 
+    /// Record the MIDI output to a Standard MIDI File.
+    /// The file is written when the session ends, i.e. when Enter or Ctrl-C is pressed or when all input events have been replayed.
+    #[arg(long = "record")]
+    record_file: Option<PathBuf>,
+
     #[command(subcommand)]
     mode: LiveMode,
 }
@@ -45,17 +76,55 @@ enum LiveMode {
     /// On the downside, the number of output channels cannot be changed by the user and might be a large number.
     #[command(name = "aot")]
     AheadOfTime(AheadOfTimeOptions),
+
+    /// Adaptive: Like just-in-time but the pitches of all sounding notes are continuously adjusted s.t. their intervals become just.
+    /// The shift of each note is limited by a maximum drift.
+    #[command(name = "adaptive")]
+    Adaptive(AdaptiveOptions),
 }
 
 #[derive(Parser)]
 struct JustInTimeOptions {
+    #[command(flatten)]
+    tuner: JitTunerOptions,
+
+    /// MIDI-out tuning method
+    #[arg(value_enum)]
+    method: TuningMethod,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
+#[derive(Parser)]
+pub(crate) struct JitTunerOptions {
     /// Describes what to do when a note is triggered that cannot be handled by any channel without tuning clashes.
     /// [block] Do not accept the new note. It will remain silent.
     /// [stop] Stop an old note and accept the new note.
     /// [ignore] Neither block nor stop. Accept that an old note receives an arbitrary tuning update.
+    /// [stop-quietest] Stop the note with the lowest velocity.
+    /// [stop-released] Stop a note that has been released while the sustain pedal is held. Otherwise, stop the oldest note.
+    /// [stop-closest] Stop the note that is closest in pitch to the new note.
+    /// [reuse:<cents>] Play the new note on a channel whose detuning deviates by at most <cents>. Otherwise, stop the oldest note.
     #[arg(long = "clash", default_value = "stop", value_parser = parse_mitigation)]
     clash_mitigation: PoolingMode,
 
+    /// Maximum deviation in cents s.t. a new note can share a channel with notes of a similar detuning.
+    /// The new note adopts the detuning of the shared channel.
+    #[arg(long = "tolerance")]
+    tolerance: Option<f64>,
+
+    /// Time in seconds that the channel of a released note is kept reserved s.t. the release tail of the note is not affected by tuning changes.
+    /// MIDI devices do not report when a note stops sounding, so the value should cover the release of the synthesizer patch.
+    #[arg(long = "release-time")]
+    release_time: Option<f64>,
+}
+
+#[derive(Parser)]
+struct AdaptiveOptions {
+    #[command(flatten)]
+    tuner: AdaptiveTunerOptions,
+
     /// MIDI-out tuning method
     #[arg(value_enum)]
     method: TuningMethod,
@@ -64,12 +133,58 @@ struct JustInTimeOptions {
     scale: ScaleCommand,
 }
 
-fn parse_mitigation(src: &str) -> Result<PoolingMode, &'static str> {
+#[derive(Parser)]
+pub(crate) struct AdaptiveTunerOptions {
+    /// Describes what to do when a note is triggered that cannot be handled by any channel without tuning clashes.
+    /// See `tune live jit --help` for the available modes.
+    #[arg(long = "clash", default_value = "stop", value_parser = parse_mitigation)]
+    clash_mitigation: PoolingMode,
+
+    /// Odd limit of the just intervals to adapt to
+    #[arg(long = "limit", default_value = "11")]
+    odd_limit: u16,
+
+    /// Maximum deviation in cents by which a note is shifted away from its nominal pitch
+    #[arg(long = "max-drift", default_value = "20")]
+    max_drift: f64,
+}
+
+impl JitTunerOptions {
+    pub fn create_tuner<K: Copy + Eq + Hash, S: TunableSynth>(&self, synth: S) -> JitTuner<K, S> {
+        let mut tuner = JitTuner::start(synth, self.clash_mitigation);
+        tuner.set_tolerance(self.tolerance.map(Ratio::from_cents));
+        tuner.set_release_time(self.release_time.map(Duration::from_secs_f64));
+        tuner
+    }
+}
+
+impl AdaptiveTunerOptions {
+    pub fn create_tuner<K, S: TunableSynth>(&self, synth: S) -> AdaptiveTuner<K, S> {
+        AdaptiveTuner::start(
+            synth,
+            self.clash_mitigation,
+            self.odd_limit,
+            Ratio::from_cents(self.max_drift),
+        )
+    }
+}
+
+pub(crate) fn parse_mitigation(src: &str) -> Result<PoolingMode, &'static str> {
+    const INVALID_MODE: &str = "Invalid mode. Should be `block`, `stop`, `ignore`, `stop-quietest`, `stop-released`, `stop-closest` or `reuse:<cents>`";
+
     Ok(match &*src.to_lowercase() {
         "block" => PoolingMode::Block,
         "stop" => PoolingMode::Stop,
         "ignore" => PoolingMode::Ignore,
-        _ => return Err("Invalid mode. Should be `block`, `stop` or `ignore`"),
+        "stop-quietest" => PoolingMode::StopQuietest,
+        "stop-released" => PoolingMode::StopReleased,
+        "stop-closest" => PoolingMode::StopClosest,
+        other => match other.strip_prefix("reuse:").map(str::parse) {
+            Some(Ok(cents)) => PoolingMode::Reuse {
+                tolerance: Ratio::from_cents(cents),
+            },
+            _ => return Err(INVALID_MODE),
+        },
     })
 }
 
@@ -88,7 +203,12 @@ impl LiveOptions {
         let (midi_send, midi_recv) = flume::unbounded();
         let (status_send, status_recv) = flume::unbounded();
 
-        let handler = move |message| midi_send.send(message).unwrap();
+        let clock = MidiClock::for_input(&self.midi_in);
+
+        let handler = {
+            let clock = clock.clone();
+            move |message| midi_send.send((clock.elapsed(), message)).unwrap()
+        };
 
         let source = self.midi_in_args.get_midi_source()?;
         let target = self.midi_out_args.get_midi_target(handler)?;
@@ -96,27 +216,27 @@ impl LiveOptions {
         let in_chans = source.channels.clone();
         let out_chans = target.channels.clone();
 
+        let midi_in = LiveInput {
+            endpoint: self.midi_in.clone(),
+            clock,
+            source,
+            pipeline_file: self.pipeline_file,
+        };
+
         match &self.mode {
-            LiveMode::JustInTime(options) => options.run(
-                app,
-                source,
-                target,
-                self.midi_in_device,
-                self.midi_out_args,
-                status_send.clone(),
-            )?,
-            LiveMode::AheadOfTime(options) => options.run(
-                app,
-                source,
-                target,
-                self.midi_in_device,
-                self.midi_out_args,
-                status_send.clone(),
-            )?,
+            LiveMode::JustInTime(options) => {
+                options.run(app, midi_in, target, self.midi_out_args, status_send)?
+            }
+            LiveMode::AheadOfTime(options) => {
+                options.run(app, midi_in, target, self.midi_out_args, status_send)?
+            }
+            LiveMode::Adaptive(options) => {
+                options.run(app, midi_in, target, self.midi_out_args, status_send)?
+            }
         };
 
         let (out_device, mut out_connection) =
-            midi::connect_to_out_device("tune-cli", &self.midi_out_device)
+            midi::connect_to_out_endpoint("tune-cli", &self.midi_out)
                 .handle_error::<CliError>("Could not connect to MIDI output device")?;
 
         app.writeln(format_args!("Sending MIDI data to {out_device}"))?;
@@ -134,18 +254,65 @@ impl LiveOptions {
                 .join(", ")
         ))?;
 
-        futures::join!(
-            async {
-                while let Ok(message) = midi_recv.recv_async().await {
-                    message.send_to(|message| out_connection.send(message).unwrap());
-                }
-            },
-            async {
-                while let Ok(status) = status_recv.recv_async().await {
-                    app.writeln(status).unwrap();
-                }
+        // When replaying a file, the session ends as soon as all events have been processed
+        let stop_on_enter = matches!(self.midi_in, MidiEndpoint::Device(_));
+
+        let (stop_send, stop_recv) = flume::bounded(1);
+        ctrlc::set_handler(move || {
+            let _ = stop_send.try_send(());
+        })
+        .handle_error::<CliError>("Could not install Ctrl-C handler")?;
+
+        let mut recorder = SmfRecorder::real_time();
+        if let Some(record_file) = &self.record_file {
+            if stop_on_enter {
+                app.writeln(format_args!(
+                    "Recording to {}. Press Enter or Ctrl-C to stop.",
+                    record_file.display()
+                ))?;
+            } else {
+                app.writeln(format_args!("Recording to {}", record_file.display()))?;
             }
-        );
+        }
+
+        futures::select! {
+            _ = future::join(
+                async {
+                    while let Ok((time, message)) = midi_recv.recv_async().await {
+                        message.send_to(|raw_message| {
+                            if self.record_file.is_some() {
+                                recorder.record_at(time, raw_message);
+                            }
+                            out_connection.send(time, raw_message).unwrap();
+                        });
+                    }
+                },
+                async {
+                    while let Ok(status) = status_recv.recv_async().await {
+                        app.writeln(status).unwrap();
+                    }
+                },
+            ).fuse() => {},
+            () = async {
+                if self.record_file.is_some() && stop_on_enter {
+                    let _ = io::stdin().read_line(&mut String::new()).await;
+                } else {
+                    future::pending().await
+                }
+            }.fuse() => {},
+            _ = stop_recv.recv_async().fuse() => {},
+        }
+
+        // The recording is written before closing the output s.t. it is not lost if closing fails
+        if let Some(record_file) = &self.record_file {
+            recorder.into_smf().write(File::create(record_file)?)?;
+            app.writeln(format_args!(
+                "Recording written to {}",
+                record_file.display()
+            ))?;
+        }
+
+        out_connection.close()?;
 
         Ok(())
     }
@@ -155,50 +322,51 @@ impl JustInTimeOptions {
     fn run(
         &self,
         app: &mut App,
-        source: MidiSource,
+        midi_in: LiveInput,
         target: MidiTarget<impl MidiTunerMessageHandler + Send + 'static>,
-        midi_in_device: String,
         midi_out_args: MidiOutArgs,
         status_send: Sender<String>,
     ) -> CliResult<()> {
-        let tuning = self.scale.to_scale(app)?.tuning;
+        let scale = self.scale.to_scale(app)?;
+        let pipeline = Pipeline::load(app, midi_in.pipeline_file.as_deref(), scale)?;
 
-        let synth = midi_out_args.create_synth(target, self.method);
-        let mut tuner = JitTuner::start(synth, self.clash_mitigation);
+        let synth = midi_out_args.create_synth(target, self.method)?;
+        let tuner = self.tuner.create_tuner(synth);
+
+        let mut handler = TuningEventHandler::new(tuner, self.method);
 
         connect_to_in_device(
-            midi_in_device,
-            source,
-            move |message_type, offset| match message_type {
-                ChannelMessageType::NoteOff { key, velocity }
-                | ChannelMessageType::NoteOn {
-                    key,
-                    velocity: velocity @ 0,
-                } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_off(piano_key, velocity);
-                }
-                ChannelMessageType::NoteOn { key, velocity } => {
-                    let piano_key = offset.get_piano_key(key);
-                    if let Some(pitch) = tuning.maybe_pitch_of(piano_key) {
-                        tuner.note_on(piano_key, pitch, velocity);
-                    }
-                }
-                ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_attr(piano_key, pressure);
-                }
-                message_type @ (ChannelMessageType::ControlChange { .. }
-                | ChannelMessageType::ProgramChange { .. }
-                | ChannelMessageType::ChannelPressure { .. }
-                | ChannelMessageType::PitchBendChange { .. }) => {
-                    tuner.global_attr(message_type);
-                }
-            },
+            midi_in,
+            pipeline,
+            move |event, channel| handler.handle(event, channel),
             move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
-        );
+        )
+    }
+}
 
-        Ok(())
+impl AdaptiveOptions {
+    fn run(
+        &self,
+        app: &mut App,
+        midi_in: LiveInput,
+        target: MidiTarget<impl MidiTunerMessageHandler + Send + 'static>,
+        midi_out_args: MidiOutArgs,
+        status_send: Sender<String>,
+    ) -> CliResult<()> {
+        let scale = self.scale.to_scale(app)?;
+        let pipeline = Pipeline::load(app, midi_in.pipeline_file.as_deref(), scale)?;
+
+        let synth = midi_out_args.create_synth(target, self.method)?;
+        let tuner = self.tuner.create_tuner(synth);
+
+        let mut handler = TuningEventHandler::new(tuner, self.method);
+
+        connect_to_in_device(
+            midi_in,
+            pipeline,
+            move |event, channel| handler.handle(event, channel),
+            move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
+        )
     }
 }
 
@@ -206,18 +374,20 @@ impl AheadOfTimeOptions {
     fn run(
         &self,
         app: &mut App,
-        source: MidiSource,
+        midi_in: LiveInput,
         target: MidiTarget<impl MidiTunerMessageHandler + Send + 'static>,
-        midi_in_device: String,
         midi_out_args: MidiOutArgs,
         status_send: Sender<String>,
     ) -> CliResult<()> {
         let scale = self.scale.to_scale(app)?;
+        let pipeline = Pipeline::load(app, midi_in.pipeline_file.as_deref(), scale)?;
 
-        let synth = midi_out_args.create_synth(target, self.method);
+        let synth = midi_out_args.create_synth(target, self.method)?;
         let mut tuner = AotTuner::start(synth);
 
-        let required_channels = tuner.set_tuning(&*scale.tuning, scale.keys).unwrap();
+        let required_channels = tuner
+            .set_tuning(&pipeline, pipeline.keys(&midi_in.source))
+            .unwrap();
         if tuner.tuned() {
             app.writeln(format_args!(
                 "Tuning requires {required_channels} MIDI channels"
@@ -230,59 +400,184 @@ impl AheadOfTimeOptions {
             .into());
         }
 
+        let mut handler = TuningEventHandler::new(tuner, self.method);
+
         connect_to_in_device(
-            midi_in_device,
-            source,
-            move |message_type, offset| match message_type {
-                ChannelMessageType::NoteOff { key, velocity }
-                | ChannelMessageType::NoteOn {
-                    key,
-                    velocity: velocity @ 0,
-                } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_off(piano_key, velocity);
-                }
-                ChannelMessageType::NoteOn { key, velocity } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_on(piano_key, velocity);
-                }
-                ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
-                    let piano_key = offset.get_piano_key(key);
-                    tuner.note_attr(piano_key, pressure);
-                }
-                message_type @ (ChannelMessageType::ControlChange { .. }
-                | ChannelMessageType::ProgramChange { .. }
-                | ChannelMessageType::ChannelPressure { .. }
-                | ChannelMessageType::PitchBendChange { .. }) => {
-                    tuner.global_attr(message_type);
-                }
-            },
+            midi_in,
+            pipeline,
+            move |event, channel| handler.handle(event, channel),
             move |status| status_send.send(format!("[MIDI-in] {status}")).unwrap(),
-        );
+        )
+    }
+}
 
-        Ok(())
+pub(crate) const SUSTAIN_CONTROLLER: u8 = 64;
+
+// When using MPE, channel pressure and timbre messages are forwarded to the notes of the same input channel only
+const MPE_TIMBRE_CONTROLLER: u8 = 74;
+
+/// Forwards [`PipelineEvent`]s to the notes of a [`LiveTuner`].
+pub(crate) struct TuningEventHandler<K, T> {
+    tuner: T,
+    per_note_expressions: bool,
+    active_keys: HashMap<u8, HashSet<K>>,
+}
+
+impl<K: Copy + Eq + Hash, T: LiveTuner<K>> TuningEventHandler<K, T> {
+    pub fn new(tuner: T, method: TuningMethod) -> Self {
+        Self {
+            tuner,
+            per_note_expressions: matches!(method, TuningMethod::Mpe),
+            active_keys: HashMap::new(),
+        }
+    }
+
+    pub fn tuner_mut(&mut self) -> &mut T {
+        &mut self.tuner
+    }
+
+    pub fn handle(&mut self, event: PipelineEvent<K>, channel: u8) {
+        match event {
+            PipelineEvent::NoteOff { key, velocity } => {
+                self.active_keys.entry(channel).or_default().remove(&key);
+                self.tuner.note_off(key, velocity);
+            }
+            PipelineEvent::NoteOn {
+                key,
+                pitch: Some(pitch),
+                velocity,
+            } => {
+                self.active_keys.entry(channel).or_default().insert(key);
+                self.tuner.note_on(key, pitch, velocity);
+            }
+            PipelineEvent::NoteOn { pitch: None, .. } => {}
+            PipelineEvent::KeyPressure { key, pressure } => {
+                self.tuner.note_attr(key, pressure);
+            }
+            PipelineEvent::Channel(
+                message_type @ (ChannelMessageType::ChannelPressure { .. }
+                | ChannelMessageType::ControlChange {
+                    controller: MPE_TIMBRE_CONTROLLER,
+                    ..
+                }),
+            ) if self.per_note_expressions => {
+                for &key in self.active_keys.get(&channel).into_iter().flatten() {
+                    self.tuner.note_channel_attr(key, message_type);
+                }
+            }
+            PipelineEvent::Channel(
+                message_type @ ChannelMessageType::ControlChange {
+                    controller: SUSTAIN_CONTROLLER,
+                    value,
+                },
+            ) => {
+                self.tuner.sustain(value >= 64);
+                self.tuner.global_attr(message_type);
+            }
+            PipelineEvent::Channel(message_type) => {
+                self.tuner.global_attr(message_type);
+            }
+        }
     }
 }
 
-fn connect_to_in_device(
-    port_name: String,
+/// Common interface of the tuners that can be driven by a [`TuningEventHandler`].
+pub(crate) trait LiveTuner<K> {
+    fn note_on(&mut self, key: K, pitch: Pitch, velocity: u8);
+
+    fn note_off(&mut self, key: K, velocity: u8);
+
+    fn note_attr(&mut self, key: K, pressure: u8);
+
+    fn note_channel_attr(&mut self, key: K, message_type: ChannelMessageType);
+
+    fn global_attr(&mut self, message_type: ChannelMessageType);
+
+    fn sustain(&mut self, sustain: bool);
+}
+
+/// Implements [`LiveTuner`] by forwarding to the inherent methods of the given tuner.
+macro_rules! impl_live_tuner {
+    ($tuner:ident) => {
+        impl_live_tuner!($tuner {
+            fn note_on(&mut self, key: K, pitch: Pitch, velocity: u8) {
+                self.note_on(key, pitch, velocity);
+            }
+
+            fn sustain(&mut self, sustain: bool) {
+                self.sustain(sustain);
+            }
+        });
+    };
+    ($tuner:ident { $($methods:item)* }) => {
+        impl<K: Copy + Eq + Hash, H: MidiTunerMessageHandler> LiveTuner<K>
+            for $tuner<K, TunableMidi<H>>
+        {
+            $($methods)*
+
+            fn note_off(&mut self, key: K, velocity: u8) {
+                self.note_off(key, velocity);
+            }
+
+            fn note_attr(&mut self, key: K, pressure: u8) {
+                self.note_attr(key, pressure);
+            }
+
+            fn note_channel_attr(&mut self, key: K, message_type: ChannelMessageType) {
+                self.note_channel_attr(key, message_type);
+            }
+
+            fn global_attr(&mut self, message_type: ChannelMessageType) {
+                self.global_attr(message_type);
+            }
+        }
+    };
+}
+
+impl_live_tuner!(JitTuner);
+impl_live_tuner!(AdaptiveTuner);
+// The pitches of an AotTuner are fixed at startup
+impl_live_tuner!(AotTuner {
+    fn note_on(&mut self, key: K, _pitch: Pitch, velocity: u8) {
+        self.note_on(key, velocity);
+    }
+
+    fn sustain(&mut self, _sustain: bool) {}
+});
+
+struct LiveInput {
+    endpoint: MidiEndpoint,
+    clock: MidiClock,
     source: MidiSource,
-    mut callback: impl FnMut(ChannelMessageType, MultiChannelOffset) + Send + 'static,
+    pipeline_file: Option<PathBuf>,
+}
+
+fn connect_to_in_device(
+    midi_in: LiveInput,
+    pipeline: Pipeline,
+    mut callback: impl FnMut(PipelineEvent, u8) + Send + 'static,
     status: impl FnMut(String) + Send + 'static,
-) {
-    midi::start_in_connect_loop(
+) -> CliResult {
+    let source = midi_in.source;
+
+    midi::connect_to_in_endpoint(
         "tune-cli".to_owned(),
-        port_name,
+        midi_in.endpoint,
+        &midi_in.clock,
         move |raw_message| {
             if let Some(parsed_message) = ChannelMessage::from_raw_message(raw_message) {
-                if source.channels.contains(&parsed_message.channel()) {
-                    callback(
+                let channel = parsed_message.channel();
+                if source.channels.contains(&channel) {
+                    if let Some(event) = pipeline.process(
+                        channel,
+                        source.get_offset(channel),
                         parsed_message.message_type(),
-                        source.get_offset(parsed_message.channel()),
-                    );
+                    ) {
+                        callback(event, channel);
+                    }
                 }
             }
         },
         status,
-    );
+    )
 }
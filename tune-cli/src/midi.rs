@@ -1,9 +1,13 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
     error::Error,
+    fs::File,
     io,
+    path::{Path, PathBuf},
+    str::FromStr,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_std::task;
@@ -13,12 +17,16 @@ use serde::{Deserialize, Serialize};
 use tune::{
     key::PianoKey,
     mts::ScaleOctaveTuningFormat,
-    tuner::{MidiTarget, TunableMidi},
+    pitch::Ratio,
+    smf::{Smf, SmfEvent, SmfMessage, SmfTrack},
+    tuner::{MidiTarget, MidiTunerMessage, MidiTunerMessageHandler, TunableMidi},
 };
 
 use crate::{
+    dto::{MidiDevicesDto, MidiEventDto},
+    error::ResultExt,
     portable::{self, SendTask},
-    CliResult,
+    CliError, CliResult,
 };
 
 #[derive(Parser)]
@@ -73,6 +81,8 @@ impl MultiChannelOffset {
 
 const DEFAULT_OUT_CHANNEL: u8 = 0;
 const DEFAULT_NUM_OUT_CHANS: u8 = 9;
+const DEFAULT_PITCH_BEND_RANGE: f64 = 2.0;
+const DEFAULT_MPE_BEND_RANGE: f64 = 48.0;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Parser)]
 pub struct MidiOutArgs {
@@ -94,6 +104,11 @@ pub struct MidiOutArgs {
     /// Wraps around at tuning program number 127.
     #[arg(long = "tun-pg", default_value = "0")]
     pub tuning_program: u8,
+
+    /// Pitch-bend range in semitones to be configured on the output channels when using the `pitch-bend` or `mpe` tuning method, in the range [1..128).
+    /// [default: 2 for `pitch-bend`, 48 for `mpe`]
+    #[arg(long = "bend-range")]
+    pub pitch_bend_range: Option<f64>,
 }
 
 impl Default for MidiOutArgs {
@@ -103,6 +118,7 @@ impl Default for MidiOutArgs {
             num_out_channels: DEFAULT_NUM_OUT_CHANS,
             device_id: Default::default(),
             tuning_program: Default::default(),
+            pitch_bend_range: Default::default(),
         }
     }
 }
@@ -115,8 +131,12 @@ impl MidiOutArgs {
         })
     }
 
-    pub fn create_synth<H>(&self, target: MidiTarget<H>, method: TuningMethod) -> TunableMidi<H> {
-        match method {
+    pub fn create_synth<H: MidiTunerMessageHandler>(
+        &self,
+        target: MidiTarget<H>,
+        method: TuningMethod,
+    ) -> CliResult<TunableMidi<H>> {
+        Ok(match method {
             TuningMethod::FullKeyboard => TunableMidi::single_note_tuning_change(
                 target,
                 false,
@@ -154,11 +174,340 @@ impl MidiOutArgs {
                 ScaleOctaveTuningFormat::TwoByte,
             ),
             TuningMethod::ChannelFineTuning => TunableMidi::channel_fine_tuning(target),
-            TuningMethod::PitchBend => TunableMidi::pitch_bend(target),
+            TuningMethod::PitchBend => {
+                TunableMidi::pitch_bend(target, self.pitch_bend_range(DEFAULT_PITCH_BEND_RANGE)?)
+            }
+            TuningMethod::Mpe => TunableMidi::mpe(
+                target,
+                self.mpe_manager_channel()?,
+                self.pitch_bend_range(DEFAULT_MPE_BEND_RANGE)?,
+            ),
+        })
+    }
+
+    fn pitch_bend_range(&self, default_range: f64) -> CliResult<Ratio> {
+        let pitch_bend_range = self.pitch_bend_range.unwrap_or(default_range);
+        if !(1.0..128.0).contains(&pitch_bend_range) {
+            return Err("Pitch-bend range is not in the range [1..128) semitones"
+                .to_owned()
+                .into());
+        }
+        Ok(Ratio::from_semitones(pitch_bend_range))
+    }
+
+    /// The lower zone is used if the output channels start at channel 1. The upper zone is used if the output channels end at channel 14.
+    fn mpe_manager_channel(&self) -> CliResult<u8> {
+        if self.num_out_channels > 15 {
+            return Err("An MPE zone cannot have more than 15 member channels"
+                .to_owned()
+                .into());
+        }
+        if self.out_channel == 1 {
+            return Ok(0);
+        }
+        if u16::from(self.out_channel) + u16::from(self.num_out_channels) == 15 {
+            return Ok(15);
         }
+        Err(
+            "MPE member channels must start at channel 1 (lower zone) or end at channel 14 (upper zone)"
+                .to_owned()
+                .into(),
+        )
     }
 }
 
+/// Collects MIDI messages and arranges them in a multi-track [`Smf`].
+///
+/// The first track contains all messages that are not channel messages, e.g. tempo or System Exclusive messages.
+/// Channel messages are arranged in one track per MIDI channel.
+pub struct SmfRecorder {
+    ticks_per_quarter: u16,
+    global_track: SmfTrack,
+    channel_tracks: BTreeMap<u8, SmfTrack>,
+}
+
+impl SmfRecorder {
+    pub fn new(ticks_per_quarter: u16) -> Self {
+        Self {
+            ticks_per_quarter,
+            global_track: SmfTrack::default(),
+            channel_tracks: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a recorder for real-time messages with a tempo of 120 BPM and a resolution of about 1 ms.
+    pub fn real_time() -> Self {
+        let mut recorder = Self::new(REAL_TIME_TICKS_PER_QUARTER);
+        recorder.record_message(0, SmfMessage::Tempo(REAL_TIME_MICROS_PER_QUARTER));
+        recorder
+    }
+
+    pub fn record(&mut self, ticks: u64, message: &MidiTunerMessage) {
+        message.send_to(|raw_message| self.record_raw(ticks, raw_message));
+    }
+
+    /// Records a message at the given time relative to the start of the recording.
+    ///
+    /// The time is converted to ticks using the tempo of [`SmfRecorder::real_time`].
+    pub fn record_at(&mut self, time: Duration, raw_message: &[u8]) {
+        let ticks_per_second = f64::from(REAL_TIME_TICKS_PER_QUARTER) * 1_000_000.0
+            / f64::from(REAL_TIME_MICROS_PER_QUARTER);
+        let ticks = time.as_secs_f64() * ticks_per_second;
+        self.record_raw(ticks.round() as u64, raw_message);
+    }
+
+    fn record_raw(&mut self, ticks: u64, raw_message: &[u8]) {
+        if let Some(message) = SmfMessage::from_raw_message(raw_message) {
+            self.record_message(ticks, message);
+        }
+    }
+
+    pub fn record_message(&mut self, ticks: u64, message: SmfMessage) {
+        let track = match &message {
+            SmfMessage::Channel(channel_message) => self
+                .channel_tracks
+                .entry(channel_message.channel())
+                .or_default(),
+            _ => &mut self.global_track,
+        };
+        track.events.push(SmfEvent { ticks, message });
+    }
+
+    pub fn into_smf(self) -> Smf {
+        let mut tracks = vec![self.global_track];
+        for (channel, mut track) in self.channel_tracks {
+            track.events.insert(
+                0,
+                SmfEvent {
+                    ticks: 0,
+                    message: SmfMessage::TrackName(format!("Channel {channel}")),
+                },
+            );
+            tracks.push(track);
+        }
+
+        Smf {
+            ticks_per_quarter: self.ticks_per_quarter,
+            tracks,
+        }
+    }
+}
+
+// 120 BPM with a resolution of about 1 ms
+const REAL_TIME_TICKS_PER_QUARTER: u16 = 480;
+const REAL_TIME_MICROS_PER_QUARTER: u32 = 500_000;
+
+const FILE_ENDPOINT_PREFIX: &str = "file:";
+
+/// A MIDI device or a file that acts as a stand-in for a MIDI device.
+///
+/// Files are specified with a `file:` prefix. Files with a `.json` extension contain a list of [`MidiEventDto`]s.
+/// All other files are treated as Standard MIDI Files.
+#[derive(Clone, Debug)]
+pub enum MidiEndpoint {
+    Device(String),
+    File(PathBuf),
+}
+
+impl FromStr for MidiEndpoint {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.strip_prefix(FILE_ENDPOINT_PREFIX) {
+            Some(location) => MidiEndpoint::File(location.into()),
+            None => MidiEndpoint::Device(s.to_owned()),
+        })
+    }
+}
+
+/// Provides the time of MIDI messages relative to the start of a session.
+///
+/// When MIDI events are replayed from a file, the clock follows the time stamps of the file s.t. a session can be reproduced deterministically.
+#[derive(Clone)]
+pub enum MidiClock {
+    RealTime(Instant),
+    Virtual(Arc<Mutex<Duration>>),
+}
+
+impl MidiClock {
+    pub fn for_input(midi_in: &MidiEndpoint) -> Self {
+        match midi_in {
+            MidiEndpoint::Device(_) => MidiClock::RealTime(Instant::now()),
+            MidiEndpoint::File(_) => MidiClock::Virtual(Default::default()),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            MidiClock::RealTime(start_time) => start_time.elapsed(),
+            MidiClock::Virtual(time) => *time.lock().unwrap(),
+        }
+    }
+
+    fn advance_to(&self, new_time: Duration) {
+        if let MidiClock::Virtual(time) = self {
+            *time.lock().unwrap() = new_time;
+        }
+    }
+}
+
+/// Forwards the MIDI messages of the given input endpoint to `callback`.
+///
+/// Devices are connected in a background task (see [`start_in_connect_loop`]).
+/// Files are replayed immediately without any delay. Before each event, `clock` is advanced to the time stamp of the event.
+pub fn connect_to_in_endpoint(
+    client_name: String,
+    midi_in: MidiEndpoint,
+    clock: &MidiClock,
+    mut callback: impl FnMut(&[u8]) + Send + 'static,
+    mut report_status: impl FnMut(String) + Send + 'static,
+) -> CliResult {
+    match midi_in {
+        MidiEndpoint::Device(fuzzy_port_name) => {
+            start_in_connect_loop(client_name, fuzzy_port_name, callback, report_status);
+        }
+        MidiEndpoint::File(location) => {
+            let events = read_midi_events(&location)?;
+            report_status(format!(
+                "Replaying {} MIDI events from {}",
+                events.len(),
+                location.display()
+            ));
+            for (time, raw_message) in events {
+                clock.advance_to(time);
+                callback(&raw_message);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub enum MidiOutConnection {
+    Device(MidiOutputConnection),
+    File {
+        location: PathBuf,
+        events: Vec<(Duration, Vec<u8>)>,
+    },
+}
+
+impl MidiOutConnection {
+    pub fn send(&mut self, time: Duration, raw_message: &[u8]) -> MidiResult<()> {
+        match self {
+            MidiOutConnection::Device(connection) => connection.send(raw_message)?,
+            MidiOutConnection::File { events, .. } => events.push((time, raw_message.to_vec())),
+        }
+        Ok(())
+    }
+
+    /// Closes the connection. In the case of a file, the collected MIDI events are written.
+    pub fn close(self) -> CliResult {
+        match self {
+            MidiOutConnection::Device(connection) => {
+                connection.close();
+                Ok(())
+            }
+            MidiOutConnection::File { location, events } => write_midi_events(&location, &events),
+        }
+    }
+}
+
+pub fn connect_to_out_endpoint(
+    client_name: &str,
+    midi_out: &MidiEndpoint,
+) -> MidiResult<(String, MidiOutConnection)> {
+    match midi_out {
+        MidiEndpoint::Device(fuzzy_port_name) => {
+            let (port_name, connection) = connect_to_out_device(client_name, fuzzy_port_name)?;
+            Ok((port_name, MidiOutConnection::Device(connection)))
+        }
+        MidiEndpoint::File(location) => Ok((
+            location.display().to_string(),
+            MidiOutConnection::File {
+                location: location.clone(),
+                events: Vec::new(),
+            },
+        )),
+    }
+}
+
+fn read_midi_events(location: &Path) -> CliResult<Vec<(Duration, Vec<u8>)>> {
+    let file = File::open(location).handle_error::<CliError>("Could not open MIDI file")?;
+
+    if is_json_file(location) {
+        let events: Vec<MidiEventDto> =
+            serde_json::from_reader(file).handle_error::<CliError>("Could not parse MIDI file")?;
+        let mut events = events
+            .into_iter()
+            .map(|event| {
+                Duration::try_from_secs_f64(event.time_in_secs)
+                    .map(|time| (time, event.message))
+                    .handle_error::<CliError>("Invalid MIDI event time")
+            })
+            .collect::<CliResult<Vec<_>>>()?;
+        events.sort_by_key(|&(time, _)| time);
+        return Ok(events);
+    }
+
+    let smf = Smf::read(file).handle_error::<CliError>("Could not read MIDI file")?;
+
+    let mut smf_events: Vec<_> = smf.tracks.iter().flat_map(|track| &track.events).collect();
+    smf_events.sort_by_key(|event| event.ticks);
+
+    let mut events = Vec::new();
+    let mut micros_per_quarter = REAL_TIME_MICROS_PER_QUARTER;
+    let mut curr_ticks = 0;
+    let mut curr_time = Duration::ZERO;
+
+    for event in smf_events {
+        let nanos_per_tick =
+            f64::from(micros_per_quarter) * 1000.0 / f64::from(smf.ticks_per_quarter);
+        curr_time += Duration::from_nanos(
+            ((event.ticks - curr_ticks) as f64 * nanos_per_tick).round() as u64,
+        );
+        curr_ticks = event.ticks;
+
+        match &event.message {
+            SmfMessage::Channel(message) => {
+                events.push((curr_time, message.to_raw_message().to_vec()))
+            }
+            SmfMessage::SysEx(message) => events.push((curr_time, message.clone())),
+            SmfMessage::Tempo(tempo) => micros_per_quarter = *tempo,
+            SmfMessage::TrackName(_) | SmfMessage::Meta { .. } => {}
+        }
+    }
+
+    Ok(events)
+}
+
+fn write_midi_events(location: &Path, events: &[(Duration, Vec<u8>)]) -> CliResult {
+    let file = File::create(location).handle_error::<CliError>("Could not create MIDI file")?;
+
+    if is_json_file(location) {
+        let events: Vec<_> = events
+            .iter()
+            .map(|(time, raw_message)| MidiEventDto {
+                time_in_secs: time.as_secs_f64(),
+                message: raw_message.clone(),
+            })
+            .collect();
+        return serde_json::to_writer_pretty(file, &events)
+            .handle_error("Could not write MIDI file");
+    }
+
+    let mut recorder = SmfRecorder::real_time();
+    for (time, raw_message) in events {
+        recorder.record_at(*time, raw_message);
+    }
+    Ok(recorder.into_smf().write(file)?)
+}
+
+fn is_json_file(location: &Path) -> bool {
+    location
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
 fn get_channels(
     description: &str,
     first_channel: u8,
@@ -220,6 +569,9 @@ pub enum TuningMethod {
     #[value(name = "pitch-bend")]
     #[serde(rename = "pitch-bend")]
     PitchBend,
+    #[value(name = "mpe")]
+    #[serde(rename = "mpe")]
+    Mpe,
 }
 
 pub type MidiResult<T> = Result<T, MidiError>;
@@ -244,21 +596,39 @@ impl<T: Error> From<T> for MidiError {
 }
 
 pub fn print_midi_devices(mut dst: impl io::Write, client_name: &str) -> MidiResult<()> {
-    let midi_input = MidiInput::new(client_name)?;
+    let devices = list_midi_devices(client_name)?;
+
     writeln!(dst, "Readable MIDI devices:")?;
-    for port in midi_input.ports() {
-        writeln!(dst, "- {}", midi_input.port_name(&port)?)?;
+    for name in devices.readable {
+        writeln!(dst, "- {name}")?;
     }
 
-    let midi_output = MidiOutput::new(client_name)?;
     writeln!(dst, "Writable MIDI devices:")?;
-    for port in midi_output.ports() {
-        writeln!(dst, "- {}", midi_output.port_name(&port)?)?;
+    for name in devices.writable {
+        writeln!(dst, "- {name}")?;
     }
 
     Ok(())
 }
 
+pub fn list_midi_devices(client_name: &str) -> MidiResult<MidiDevicesDto> {
+    let midi_input = MidiInput::new(client_name)?;
+    let readable = midi_input
+        .ports()
+        .iter()
+        .map(|port| midi_input.port_name(port))
+        .collect::<Result<_, _>>()?;
+
+    let midi_output = MidiOutput::new(client_name)?;
+    let writable = midi_output
+        .ports()
+        .iter()
+        .map(|port| midi_output.port_name(port))
+        .collect::<Result<_, _>>()?;
+
+    Ok(MidiDevicesDto { readable, writable })
+}
+
 pub fn start_in_connect_loop(
     client_name: String,
     fuzzy_port_name: String,
@@ -397,3 +767,67 @@ fn find_port_by_name<IO: MidiIO>(
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tune::{midi::ChannelMessageType, note::Note, tuner::TunableSynth};
+
+    use super::*;
+
+    #[test]
+    fn record_tracks_by_channel() {
+        let mut messages = Vec::new();
+
+        let mut synth = TunableMidi::single_note_tuning_change(
+            MidiTarget {
+                handler: |message: MidiTunerMessage| messages.push(message),
+                channels: vec![3, 4],
+            },
+            false,
+            127,
+            0,
+        );
+
+        let note = Note::from_midi_number(69);
+        synth.notes_detune(1, &[(note, Ratio::from_cents(10.0))]);
+        synth.note_on(1, note, 100);
+        synth.note_off(1, note, 100);
+        drop(synth);
+
+        let mut recorder = SmfRecorder::new(480);
+        recorder.record_message(0, SmfMessage::Tempo(500_000));
+        for (ticks, message) in messages.iter().enumerate() {
+            recorder.record(ticks as u64 * 10, message);
+        }
+
+        let smf = recorder.into_smf();
+        assert_eq!(smf.ticks_per_quarter, 480);
+        assert_eq!(smf.tracks.len(), 2);
+
+        let global_track = &smf.tracks[0].events;
+        assert_eq!(global_track.len(), 2);
+        assert_eq!(global_track[0].message, SmfMessage::Tempo(500_000));
+        assert!(matches!(global_track[1].message, SmfMessage::SysEx(_)));
+
+        let channel_track = &smf.tracks[1].events;
+        assert_eq!(channel_track.len(), messages.len());
+        assert_eq!(
+            channel_track[0].message,
+            SmfMessage::TrackName("Channel 4".to_owned())
+        );
+        assert_eq!(
+            channel_track[channel_track.len() - 1],
+            SmfEvent {
+                ticks: 10 * (messages.len() as u64 - 1),
+                message: SmfMessage::Channel(
+                    ChannelMessageType::NoteOff {
+                        key: 69,
+                        velocity: 100
+                    }
+                    .in_channel(4)
+                    .unwrap()
+                ),
+            }
+        );
+    }
+}
@@ -3,7 +3,11 @@ use std::cmp::Ordering;
 use clap::Parser;
 use tune::{math, pergen::Mos, pitch::Ratio};
 
-use crate::{App, CliResult};
+use crate::{
+    dto::{MosDto, MosGeneratorDto, MosGeneratorsDto, MosesDto},
+    output::OutputFormat,
+    App, CliResult,
+};
 
 #[derive(Parser)]
 pub(crate) enum MosCommand {
@@ -41,48 +45,83 @@ pub(crate) struct FindMosesOptions {
 
 impl FindMosesOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
-        let mut best_step_ratio = f64::INFINITY;
+        let dto = self.to_dto();
 
-        for mut mos in
-            Mos::<f64>::new_genesis(self.generator.num_equal_steps_of_size(self.period)).children()
-        {
-            let sharpness_indicator = match mos.sharpness().partial_cmp(&0.0) {
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&dto);
+        }
+
+        for mos in &dto.moses {
+            let sharpness_indicator = match mos.sharpness_in_cents.partial_cmp(&0.0) {
                 Some(Ordering::Greater) => "+",
                 Some(Ordering::Less) => "-",
                 Some(Ordering::Equal) | None => "=",
             };
 
+            app.write(format_args!(
+                "({sharpness_indicator}) num_notes = {}, {}L{}s, L = {:#.0}, s = {:#.0}, L/s = {:.2}",
+                mos.num_notes,
+                mos.num_large_steps,
+                mos.num_small_steps,
+                Ratio::from_cents(mos.large_step_in_cents),
+                Ratio::from_cents(mos.small_step_in_cents),
+                mos.step_ratio,
+            ))?;
+
+            if mos.best_approximation {
+                app.write(" (*)")?;
+            }
+            app.writeln("")?;
+        }
+
+        app.writeln("(+/-) = bright / dark generator")?;
+        app.writeln("(*) = best equal-step approximation so far")?;
+
+        Ok(())
+    }
+
+    fn to_dto(&self) -> MosesDto {
+        let mut moses = Vec::new();
+        let mut best_step_ratio = f64::INFINITY;
+
+        for mut mos in
+            Mos::<f64>::new_genesis(self.generator.num_equal_steps_of_size(self.period)).children()
+        {
+            let generator_sharpness = self.period.repeated(mos.sharpness());
+
             if mos.primary_step() < mos.secondary_step() {
                 mos = mos.mirror();
             }
 
-            let primary_step = self.period.repeated(mos.primary_step());
-            let secondary_step = self.period.repeated(mos.secondary_step());
             let sharpness = self.period.repeated(mos.sharpness());
             let step_ratio = mos.primary_step() / mos.secondary_step();
 
-            app.write(format_args!(
-                "({sharpness_indicator}) num_notes = {}, {}L{}s, L = {primary_step:#.0}, s = {secondary_step:#.0}, L/s = {step_ratio:.2}",
-                mos.num_steps(),
-                mos.num_primary_steps(),
-                mos.num_secondary_steps(),
-            ))?;
-
-            if step_ratio < best_step_ratio {
+            let best_approximation = step_ratio < best_step_ratio;
+            if best_approximation {
                 best_step_ratio = step_ratio;
-                app.write(" (*)")?;
             }
-            app.writeln("")?;
+
+            moses.push(MosDto {
+                num_notes: mos.num_steps(),
+                num_large_steps: mos.num_primary_steps(),
+                num_small_steps: mos.num_secondary_steps(),
+                large_step_in_cents: self.period.repeated(mos.primary_step()).as_cents(),
+                small_step_in_cents: self.period.repeated(mos.secondary_step()).as_cents(),
+                step_ratio,
+                sharpness_in_cents: generator_sharpness.as_cents(),
+                best_approximation,
+            });
 
             if sharpness.abs() < self.threshold {
                 break;
             }
         }
 
-        app.writeln("(+/-) = bright / dark generator")?;
-        app.writeln("(*) = best equal-step approximation so far")?;
-
-        Ok(())
+        MosesDto {
+            period_in_cents: self.period.as_cents(),
+            generator_in_cents: self.generator.as_cents(),
+            moses,
+        }
     }
 }
 
@@ -101,6 +140,40 @@ pub(crate) struct FindGeneratorsOptions {
 
 impl FindGeneratorsOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
+        let dto = self.to_dto();
+
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&dto);
+        }
+
+        let format_generator = |generator: &MosGeneratorDto| {
+            format!(
+                "{}\\{} ({:#.0})",
+                generator.num_steps,
+                generator.num_divisions,
+                Ratio::from_cents(generator.size_in_cents)
+            )
+        };
+
+        app.writeln(format_args!(
+            "{}L{}s ({}): \
+            period={:#.0}, \
+            equalized_gen = {}, \
+            proper_gen = {}, \
+            collapsed_gen = {}",
+            dto.num_large_steps,
+            dto.num_small_steps,
+            dto.pattern,
+            self.period,
+            format_generator(&dto.equalized_generator),
+            format_generator(&dto.proper_generator),
+            format_generator(&dto.collapsed_generator),
+        ))?;
+
+        Ok(())
+    }
+
+    fn to_dto(&self) -> MosGeneratorsDto {
         let large_gen = Mos::<u16>::new_collapsed(self.num_large_steps, self.num_small_steps)
             .genesis()
             .primary_step();
@@ -108,38 +181,35 @@ impl FindGeneratorsOptions {
             .genesis()
             .secondary_step();
 
-        app.writeln(format_args!(
-            "{}L{}s ({}): \
-            period={:#.0}, \
-            equalized_gen = {}\\{} ({:#.0}), \
-            proper_gen = {}\\{} ({:#.0}), \
-            collapsed_gen = {}\\{} ({:#.0})",
-            self.num_large_steps,
-            self.num_small_steps,
-            ls_pattern(
+        let generator = |num_steps, num_divisions| MosGeneratorDto {
+            num_steps,
+            num_divisions,
+            size_in_cents: self
+                .period
+                .repeated(num_steps)
+                .divided_into_equal_steps(num_divisions)
+                .as_cents(),
+        };
+
+        MosGeneratorsDto {
+            num_large_steps: self.num_large_steps,
+            num_small_steps: self.num_small_steps,
+            pattern: ls_pattern(
                 large_gen + small_gen,
                 self.num_large_steps,
-                self.num_small_steps
+                self.num_small_steps,
             ),
-            self.period,
-            large_gen + small_gen,
-            self.num_large_steps + self.num_small_steps,
-            self.period
-                .repeated(large_gen + small_gen)
-                .divided_into_equal_steps(self.num_large_steps + self.num_small_steps),
-            2 * large_gen + small_gen,
-            2 * self.num_large_steps + self.num_small_steps,
-            self.period
-                .repeated(2 * large_gen + small_gen)
-                .divided_into_equal_steps(2 * self.num_large_steps + self.num_small_steps),
-            large_gen,
-            self.num_large_steps,
-            self.period
-                .repeated(large_gen)
-                .divided_into_equal_steps(self.num_large_steps),
-        ))?;
-
-        Ok(())
+            period_in_cents: self.period.as_cents(),
+            equalized_generator: generator(
+                large_gen + small_gen,
+                self.num_large_steps + self.num_small_steps,
+            ),
+            proper_generator: generator(
+                2 * large_gen + small_gen,
+                2 * self.num_large_steps + self.num_small_steps,
+            ),
+            collapsed_generator: generator(large_gen, self.num_large_steps),
+        }
     }
 }
 
@@ -8,15 +8,17 @@ use clap::Parser;
 use midir::MidiOutputConnection;
 use tune::{
     mts::{
-        ScaleOctaveTuningFormat, ScaleOctaveTuningOptions, SingleNoteTuningChangeMessage,
-        SingleNoteTuningChangeOptions,
+        BulkTuningDumpMessage, BulkTuningDumpOptions, ScaleOctaveTuningFormat,
+        ScaleOctaveTuningOptions, SingleNoteTuningChangeMessage, SingleNoteTuningChangeOptions,
     },
     tuner::AotTuningModel,
 };
 
 use crate::{
+    dto::{MtsDto, MtsMessageDto},
     error::ResultExt,
     midi::{self, DeviceIdArg},
+    output::OutputFormat,
     App, CliError, CliResult, ScaleCommand,
 };
 
@@ -44,6 +46,10 @@ enum MtsCommand {
     #[command(name = "full-rt")]
     FullKeyboardRt(FullKeyboardOptions),
 
+    /// Retune a MIDI device (Bulk Tuning Dump)
+    #[command(name = "bulk")]
+    BulkDump(BulkDumpOptions),
+
     /// Retune a MIDI device (Scale/Octave Tuning, 1 byte format).
     /// If necessary, multiple tuning messages are distributed over multiple channels.
     #[command(name = "octave-1")]
@@ -86,6 +92,23 @@ struct FullKeyboardOptions {
     scale: ScaleCommand,
 }
 
+#[derive(Parser)]
+struct BulkDumpOptions {
+    #[command(flatten)]
+    device_id: DeviceIdArg,
+
+    /// Tuning program that should be affected
+    #[arg(long = "tun-pg", default_value = "0")]
+    tuning_program: u8,
+
+    /// Name of the tuning (at most 16 ASCII characters)
+    #[arg(long = "name", default_value = "")]
+    name: String,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
 #[derive(Parser)]
 struct OctaveOptions {
     #[command(flatten)]
@@ -139,11 +162,14 @@ impl MtsOptions {
                 .map(|target_port| midi::connect_to_out_device("tune-cli", target_port))
                 .transpose()
                 .handle_error::<CliError>("Could not connect to MIDI output device")?,
+
+            messages: Vec::new(),
         };
 
         match &self.command {
             MtsCommand::FullKeyboard(options) => options.run(app, &mut outputs, false),
             MtsCommand::FullKeyboardRt(options) => options.run(app, &mut outputs, true),
+            MtsCommand::BulkDump(options) => options.run(app, &mut outputs),
             MtsCommand::Octave1(options) => {
                 options.run(app, &mut outputs, false, ScaleOctaveTuningFormat::OneByte)
             }
@@ -158,7 +184,15 @@ impl MtsOptions {
             }
             MtsCommand::TuningProgram(options) => options.run(app, &mut outputs),
             MtsCommand::TuningBank(options) => options.run(app, &mut outputs),
+        }?;
+
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&MtsDto {
+                messages: outputs.messages,
+            });
         }
+
+        Ok(())
     }
 }
 
@@ -181,7 +215,7 @@ impl FullKeyboardOptions {
 
         for message in tuning_message.sysex_bytes() {
             app.errln(format_args!("== SysEx start =="))?;
-            outputs.write_midi_message(app, message)?;
+            outputs.write_midi_message(app, None, message)?;
             app.errln(format_args!("== SysEx end =="))?;
         }
         app.errln(format_args!(
@@ -197,6 +231,30 @@ impl FullKeyboardOptions {
     }
 }
 
+impl BulkDumpOptions {
+    fn run(&self, app: &mut App, outputs: &mut Outputs) -> CliResult {
+        let scale = self.scale.to_scale(app)?;
+        let options = BulkTuningDumpOptions {
+            device_id: self.device_id.device_id,
+            tuning_program: self.tuning_program,
+            name: self.name.clone(),
+        };
+
+        let tuning_message = BulkTuningDumpMessage::from_tuning(&options, &*scale.tuning)
+            .handle_error::<CliError>("Could not apply bulk tuning dump")?;
+
+        app.errln(format_args!("== SysEx start =="))?;
+        outputs.write_midi_message(app, None, tuning_message.sysex_bytes())?;
+        app.errln(format_args!("== SysEx end =="))?;
+        app.errln(format_args!(
+            "Number of out-of-range notes: {}",
+            tuning_message.out_of_range_notes().len()
+        ))?;
+
+        Ok(())
+    }
+}
+
 impl OctaveOptions {
     fn run(
         &self,
@@ -233,7 +291,7 @@ impl OctaveOptions {
                 .handle_error::<CliError>("Could not apply octave tuning")?;
 
             app.errln(format_args!("== SysEx start (channel {channel}) =="))?;
-            outputs.write_midi_message(app, tuning_message.sysex_bytes())?;
+            outputs.write_midi_message(app, Some(channel), tuning_message.sysex_bytes())?;
             app.errln(format_args!("== SysEx end =="))?;
         }
 
@@ -250,7 +308,7 @@ impl TuningProgramOptions {
                 .enumerate()
         {
             app.errln(format_args!("== RPN part {enumeration} =="))?;
-            outputs.write_midi_message(app, &message.to_raw_message())?;
+            outputs.write_midi_message(app, Some(self.midi_channel), &message.to_raw_message())?;
         }
         app.errln(format_args!("== Tuning program change end =="))?;
 
@@ -267,7 +325,7 @@ impl TuningBankOptions {
                 .enumerate()
         {
             app.errln(format_args!("== RPN part {enumeration} =="))?;
-            outputs.write_midi_message(app, &message.to_raw_message())?;
+            outputs.write_midi_message(app, Some(self.midi_channel), &message.to_raw_message())?;
         }
         app.errln(format_args!("== Tuning bank change end =="))?;
 
@@ -278,12 +336,25 @@ impl TuningBankOptions {
 struct Outputs {
     open_file: Option<File>,
     midi_out: Option<(String, MidiOutputConnection)>,
+    messages: Vec<MtsMessageDto>,
 }
 
 impl Outputs {
-    fn write_midi_message(&mut self, app: &mut App, message: &[u8]) -> CliResult {
-        for byte in message {
-            app.writeln(format_args!("0x{byte:02x}"))?;
+    fn write_midi_message(
+        &mut self,
+        app: &mut App,
+        channel: Option<u8>,
+        message: &[u8],
+    ) -> CliResult {
+        if app.format == OutputFormat::Text {
+            for byte in message {
+                app.writeln(format_args!("0x{byte:02x}"))?;
+            }
+        } else {
+            self.messages.push(MtsMessageDto {
+                channel,
+                bytes: message.iter().map(|byte| format!("0x{byte:02x}")).collect(),
+            });
         }
         if let Some(open_file) = &mut self.open_file {
             open_file.write_all(message)?;
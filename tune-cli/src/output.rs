@@ -0,0 +1,107 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, Write},
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Format in which a command writes its output.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+
+    /// YAML document
+    Yaml,
+
+    /// JSON document
+    Json,
+
+    /// CSV table with a header row
+    Csv,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_possible_value().unwrap().get_name())
+    }
+}
+
+/// Structured output of a command that can be written in a machine-readable [`OutputFormat`].
+pub(crate) trait OutputDto: Serialize {
+    /// The table written when the CSV format is requested. Nested lists are flattened into rows or joined by spaces.
+    fn csv_table(&self) -> CsvTable;
+}
+
+pub(crate) struct CsvTable {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    pub fn new(header: impl Into<Vec<&'static str>>) -> Self {
+        Self {
+            header: header.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = impl ToString>) {
+        self.rows
+            .push(row.into_iter().map(|field| field.to_string()).collect());
+    }
+
+    pub fn write(&self, mut dst: impl Write) -> io::Result<()> {
+        write_csv_record(&mut dst, &self.header)?;
+        for row in &self.rows {
+            write_csv_record(&mut dst, row)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_csv_record(dst: &mut impl Write, fields: &[impl AsRef<str>]) -> io::Result<()> {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            write!(dst, ",")?;
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n']) {
+            write!(dst, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            write!(dst, "{field}")?;
+        }
+    }
+    writeln!(dst)
+}
+
+/// Joins list values s.t. they fit into a single CSV field.
+pub(crate) fn join(values: impl IntoIterator<Item = impl ToString>) -> String {
+    values
+        .into_iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        let mut table = CsvTable::new(["name", "value"]);
+        table.push_row(["plain", "1"]);
+        table.push_row(["with, comma", "say \"hi\""]);
+
+        let mut output = Vec::new();
+        table.write(&mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "name,value\nplain,1\n\"with, comma\",\"say \"\"hi\"\"\"\n"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    path::Path,
+};
+
+use serde::Deserialize;
+use tune::{key::PianoKey, midi::ChannelMessageType, pitch::Pitch, tuning::KeyboardMapping};
+
+use crate::{
+    error::ResultExt,
+    midi::{MidiSource, MultiChannelOffset},
+    scale::Scale,
+    App, CliError, CliResult, ScaleCommand,
+};
+
+/// Transformation of the incoming MIDI events applied before the events reach the tuner.
+///
+/// The keyboard is split into zones, each with its own channels, key range, velocity curve, transposition and scale.
+/// A note is handled by the first zone that accepts its channel and key.
+pub(crate) struct Pipeline {
+    controllers: BTreeMap<u8, u8>,
+    zones: Vec<Zone>,
+    scales: Vec<Scale>,
+}
+
+struct Zone {
+    channels: Option<Vec<u8>>,
+    keys: KeyRangeDto,
+    transpose: i32,
+    velocity: VelocityCurveDto,
+    scale_index: usize,
+}
+
+/// A key in the context of the zone that triggered it.
+///
+/// Zones can use different scales s.t. the same [`PianoKey`] can have different pitches in different zones.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct ZoneKey {
+    zone: usize,
+    key: PianoKey,
+}
+
+/// A MIDI event after it has passed the [`Pipeline`].
+pub(crate) enum PipelineEvent<K = ZoneKey> {
+    NoteOff {
+        key: K,
+        velocity: u8,
+    },
+    NoteOn {
+        key: K,
+        pitch: Option<Pitch>,
+        velocity: u8,
+    },
+    KeyPressure {
+        key: K,
+        pressure: u8,
+    },
+    /// Any event that does not refer to a specific key.
+    Channel(ChannelMessageType),
+}
+
+impl Pipeline {
+    /// Creates a [`Pipeline`] from the YAML file at `location`.
+    ///
+    /// Zones without an explicit scale use `default_scale`. If no file is given, a single zone forwards all events unmodified.
+    pub fn load(app: &mut App, location: Option<&Path>, default_scale: Scale) -> CliResult<Self> {
+        let pipeline_dto = match location {
+            Some(location) => {
+                let file = File::open(location)
+                    .handle_error::<CliError>("Could not read pipeline file")?;
+                serde_yaml::from_reader(file)
+                    .handle_error::<CliError>("Could not parse pipeline file")?
+            }
+            None => PipelineDto {
+                controllers: BTreeMap::new(),
+                zones: vec![ZoneDto::default()],
+            },
+        };
+
+        let mut scales = vec![default_scale];
+        let mut zones = Vec::new();
+        for zone_dto in pipeline_dto.zones {
+            let scale_index = match zone_dto.scale {
+                Some(scale_spec) => {
+                    scales.push(ScaleCommand::from_args(&scale_spec)?.to_scale(app)?);
+                    scales.len() - 1
+                }
+                None => 0,
+            };
+            zones.push(Zone {
+                channels: zone_dto.channels,
+                keys: zone_dto.keys,
+                transpose: zone_dto.transpose,
+                velocity: zone_dto.velocity,
+                scale_index,
+            });
+        }
+
+        Ok(Pipeline {
+            controllers: pipeline_dto.controllers,
+            zones,
+            scales,
+        })
+    }
+
+    /// All keys that can be reached through the zones from the given `source` and that can be mapped to a pitch.
+    ///
+    /// The keys of each zone are limited to the keys of the zone's scale.
+    pub fn keys(&self, source: &MidiSource) -> Vec<ZoneKey> {
+        let scale_keys: Vec<HashSet<_>> = self
+            .scales
+            .iter()
+            .map(|scale| scale.keys.iter().copied().collect())
+            .collect();
+
+        let mut keys = Vec::new();
+        let mut visited = HashSet::new();
+        for &channel in &source.channels {
+            let offset = source.get_offset(channel);
+            for key in 0..=max_midi_value() {
+                if let Some((zone, zone_key)) = self.find_zone_key(channel, &offset, key) {
+                    if scale_keys[zone.scale_index].contains(&zone_key.key)
+                        && self.maybe_pitch_of(zone_key).is_some()
+                        && visited.insert(zone_key)
+                    {
+                        keys.push(zone_key);
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    /// Transforms the incoming event. Returns [`None`] if the event is not accepted by any zone.
+    pub fn process(
+        &self,
+        channel: u8,
+        offset: MultiChannelOffset,
+        message_type: ChannelMessageType,
+    ) -> Option<PipelineEvent> {
+        let zone_key = |key| self.find_zone_key(channel, &offset, key);
+
+        Some(match message_type {
+            ChannelMessageType::NoteOff { key, velocity }
+            | ChannelMessageType::NoteOn {
+                key,
+                velocity: velocity @ 0,
+            } => PipelineEvent::NoteOff {
+                key: zone_key(key)?.1,
+                velocity,
+            },
+            ChannelMessageType::NoteOn { key, velocity } => {
+                let (zone, key) = zone_key(key)?;
+                PipelineEvent::NoteOn {
+                    key,
+                    pitch: self.maybe_pitch_of(key),
+                    velocity: zone.velocity.apply(velocity),
+                }
+            }
+            ChannelMessageType::PolyphonicKeyPressure { key, pressure } => {
+                PipelineEvent::KeyPressure {
+                    key: zone_key(key)?.1,
+                    pressure,
+                }
+            }
+            _ if !self.zones.iter().any(|zone| zone.accepts_channel(channel)) => return None,
+            ChannelMessageType::ControlChange { controller, value } => {
+                PipelineEvent::Channel(ChannelMessageType::ControlChange {
+                    controller: self
+                        .controllers
+                        .get(&controller)
+                        .copied()
+                        .unwrap_or(controller),
+                    value,
+                })
+            }
+            message_type @ (ChannelMessageType::ProgramChange { .. }
+            | ChannelMessageType::ChannelPressure { .. }
+            | ChannelMessageType::PitchBendChange { .. }) => PipelineEvent::Channel(message_type),
+        })
+    }
+}
+
+impl Pipeline {
+    fn find_zone_key(
+        &self,
+        channel: u8,
+        offset: &MultiChannelOffset,
+        key: u8,
+    ) -> Option<(&Zone, ZoneKey)> {
+        self.zones
+            .iter()
+            .enumerate()
+            .find(|(_, zone)| zone.accepts_channel(channel) && zone.keys.contains(key))
+            .map(|(zone_index, zone)| {
+                let zone_key = ZoneKey {
+                    zone: zone_index,
+                    key: offset.get_piano_key(key).plus_steps(zone.transpose),
+                };
+                (zone, zone_key)
+            })
+    }
+}
+
+impl KeyboardMapping<ZoneKey> for Pipeline {
+    fn maybe_pitch_of(&self, key: ZoneKey) -> Option<Pitch> {
+        let zone = self.zones.get(key.zone)?;
+        self.scales[zone.scale_index].tuning.maybe_pitch_of(key.key)
+    }
+}
+
+impl Zone {
+    fn accepts_channel(&self, channel: u8) -> bool {
+        self.channels
+            .as_ref()
+            .map_or(true, |channels| channels.contains(&channel))
+    }
+}
+
+#[derive(Deserialize)]
+struct PipelineDto {
+    /// Maps the controller numbers of incoming control change events to new controller numbers
+    #[serde(default)]
+    controllers: BTreeMap<u8, u8>,
+
+    zones: Vec<ZoneDto>,
+}
+
+#[derive(Default, Deserialize)]
+struct ZoneDto {
+    /// Input channels handled by the zone. All channels are handled if absent.
+    channels: Option<Vec<u8>>,
+
+    /// Input keys handled by the zone
+    #[serde(default)]
+    keys: KeyRangeDto,
+
+    /// Transposition in scale degrees, i.e. keys of the keyboard mapping
+    #[serde(default)]
+    transpose: i32,
+
+    #[serde(default)]
+    velocity: VelocityCurveDto,
+
+    /// Scale in the same format as the scale arguments of the command line, e.g. `ref-note 62 steps 1:16:2`
+    scale: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KeyRangeDto {
+    #[serde(default)]
+    from: u8,
+
+    #[serde(default = "max_midi_value")]
+    to: u8,
+}
+
+impl Default for KeyRangeDto {
+    fn default() -> Self {
+        Self {
+            from: 0,
+            to: max_midi_value(),
+        }
+    }
+}
+
+impl KeyRangeDto {
+    fn contains(&self, key: u8) -> bool {
+        (self.from..=self.to).contains(&key)
+    }
+}
+
+/// Maps the input velocity range 1..=127 to `min..=max` following the power curve given by `exponent`.
+#[derive(Deserialize)]
+struct VelocityCurveDto {
+    #[serde(default = "linear_exponent")]
+    exponent: f64,
+
+    #[serde(default = "min_note_on_velocity")]
+    min: u8,
+
+    #[serde(default = "max_midi_value")]
+    max: u8,
+}
+
+impl Default for VelocityCurveDto {
+    fn default() -> Self {
+        Self {
+            exponent: linear_exponent(),
+            min: min_note_on_velocity(),
+            max: max_midi_value(),
+        }
+    }
+}
+
+impl VelocityCurveDto {
+    fn apply(&self, velocity: u8) -> u8 {
+        let normalized = f64::from(velocity.saturating_sub(1)) / 126.0;
+        let min = f64::from(self.min);
+        let max = f64::from(self.max);
+        let velocity = min + (max - min) * normalized.powf(self.exponent);
+
+        // A velocity of 0 would turn the note-on event into a note-off event
+        (velocity.round() as u8).clamp(min_note_on_velocity(), max_midi_value())
+    }
+}
+
+fn linear_exponent() -> f64 {
+    1.0
+}
+
+fn min_note_on_velocity() -> u8 {
+    1
+}
+
+fn max_midi_value() -> u8 {
+    127
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn velocity_curves() {
+        let linear = VelocityCurveDto::default();
+        assert!((1..=127).all(|velocity| linear.apply(velocity) == velocity));
+
+        let compressed = VelocityCurveDto {
+            exponent: 1.0,
+            min: 64,
+            max: 100,
+        };
+        assert_eq!(compressed.apply(1), 64);
+        assert_eq!(compressed.apply(64), 82);
+        assert_eq!(compressed.apply(127), 100);
+
+        let soft = VelocityCurveDto {
+            exponent: 2.0,
+            min: 1,
+            max: 127,
+        };
+        assert_eq!(soft.apply(1), 1);
+        assert_eq!(soft.apply(64), 33);
+        assert_eq!(soft.apply(127), 127);
+
+        let fixed = VelocityCurveDto {
+            exponent: 1.0,
+            min: 0,
+            max: 0,
+        };
+        assert_eq!(fixed.apply(100), 1);
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    fs::File,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use flume::Receiver;
+use tune::{
+    key::PianoKey,
+    midi::ChannelMessageType,
+    pitch::Pitch,
+    smf::{Smf, SmfEvent, SmfMessage},
+    tuner::{AotTuner, MidiTarget, MidiTunerMessage, MidiTunerMessageHandler},
+};
+
+use crate::{
+    error::ResultExt,
+    live::{self, TuningEventHandler},
+    midi::{MidiInArgs, MidiOutArgs, MidiSource, MultiChannelOffset, SmfRecorder, TuningMethod},
+    pipeline::PipelineEvent,
+    App, CliError, CliResult, ScaleCommand,
+};
+
+const DEFAULT_MICROS_PER_QUARTER: u32 = 500_000;
+
+#[derive(Parser)]
+pub(crate) struct RetuneOptions {
+    /// Standard MIDI File to read the MIDI events from
+    input_file: PathBuf,
+
+    /// Standard MIDI File to write the retuned MIDI events to
+    output_file: PathBuf,
+
+    #[command(flatten)]
+    midi_in_args: MidiInArgs,
+
+    #[command(flatten)]
+    midi_out_args: MidiOutArgs,
+
+    #[command(subcommand)]
+    mode: RetuneMode,
+}
+
+#[derive(Parser)]
+enum RetuneMode {
+    /// Just-in-time: Tracks which notes are active and injects tuning messages into the stream of MIDI events.
+    /// See `tune live jit --help` for more info.
+    #[command(name = "jit")]
+    JustInTime(JustInTimeOptions),
+
+    /// Adaptive: Like just-in-time but shifts the notes of the scale such that the intervals between the active notes become pure.
+    /// See `tune live adaptive --help` for more info.
+    #[command(name = "adaptive")]
+    Adaptive(AdaptiveOptions),
+
+    /// Ahead-of-time: Sends all necessary tuning messages at the beginning of the file.
+    /// See `tune live aot --help` for more info.
+    #[command(name = "aot")]
+    AheadOfTime(AheadOfTimeOptions),
+}
+
+#[derive(Parser)]
+struct JustInTimeOptions {
+    #[command(flatten)]
+    tuner: live::JitTunerOptions,
+
+    /// MIDI-out tuning method
+    #[arg(value_enum)]
+    method: TuningMethod,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
+#[derive(Parser)]
+struct AdaptiveOptions {
+    #[command(flatten)]
+    tuner: live::AdaptiveTunerOptions,
+
+    /// MIDI-out tuning method
+    #[arg(value_enum)]
+    method: TuningMethod,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
+#[derive(Parser)]
+struct AheadOfTimeOptions {
+    /// MIDI-out tuning method
+    #[arg(value_enum)]
+    method: TuningMethod,
+
+    #[command(subcommand)]
+    scale: ScaleCommand,
+}
+
+impl RetuneOptions {
+    pub fn run(&self, app: &mut App) -> CliResult {
+        let input_file =
+            File::open(&self.input_file).handle_error::<CliError>("Could not open input file")?;
+        let input = Smf::read(input_file).handle_error::<CliError>("Could not read input file")?;
+
+        let (send, recv) = flume::unbounded();
+        let target = self
+            .midi_out_args
+            .get_midi_target(move |message| send.send(message).unwrap())?;
+
+        let source = self.midi_in_args.get_midi_source()?;
+
+        let mut events: Vec<_> = input
+            .tracks
+            .into_iter()
+            .flat_map(|track| track.events)
+            .collect();
+        events.sort_by_key(|event| event.ticks);
+
+        let mut overlapping_channels: Vec<_> = events
+            .iter()
+            .filter_map(|event| match &event.message {
+                SmfMessage::Channel(message) => Some(message.channel()),
+                _ => None,
+            })
+            .filter(|channel| {
+                !source.channels.contains(channel) && target.channels.contains(channel)
+            })
+            .collect();
+        overlapping_channels.sort();
+        overlapping_channels.dedup();
+        if !overlapping_channels.is_empty() {
+            return Err(format!(
+                "MIDI channels {overlapping_channels:?} are not retuned but overlap with the output channels {:?}",
+                target.channels
+            )
+            .into());
+        }
+
+        let mut retuner = Retuner {
+            source,
+            messages: recv,
+            recorder: SmfRecorder::new(input.ticks_per_quarter),
+            ticks_per_quarter: input.ticks_per_quarter,
+        };
+
+        match &self.mode {
+            RetuneMode::JustInTime(options) => {
+                options.run(app, &mut retuner, &self.midi_out_args, target, &events)?
+            }
+            RetuneMode::Adaptive(options) => {
+                options.run(app, &mut retuner, &self.midi_out_args, target, &events)?
+            }
+            RetuneMode::AheadOfTime(options) => {
+                options.run(app, &mut retuner, &self.midi_out_args, target, &events)?
+            }
+        }
+
+        let output_file = File::create(&self.output_file)
+            .handle_error::<CliError>("Could not create output file")?;
+        retuner.recorder.into_smf().write(output_file)?;
+
+        Ok(())
+    }
+}
+
+impl JustInTimeOptions {
+    fn run(
+        &self,
+        app: &mut App,
+        retuner: &mut Retuner,
+        midi_out_args: &MidiOutArgs,
+        target: MidiTarget<impl MidiTunerMessageHandler>,
+        events: &[SmfEvent],
+    ) -> CliResult {
+        let tuning = self.scale.to_scale(app)?.tuning;
+
+        let synth = midi_out_args.create_synth(target, self.method)?;
+        let tuner = self.tuner.create_tuner(synth);
+
+        let mut handler = TuningEventHandler::new(tuner, self.method);
+
+        let start = Instant::now();
+        retuner.retune(
+            events,
+            |event, channel, time| {
+                handler.tuner_mut().set_time(Some(start + time));
+                handler.handle(event, channel)
+            },
+            |key| tuning.maybe_pitch_of(key),
+        );
+
+        Ok(())
+    }
+}
+
+impl AdaptiveOptions {
+    fn run(
+        &self,
+        app: &mut App,
+        retuner: &mut Retuner,
+        midi_out_args: &MidiOutArgs,
+        target: MidiTarget<impl MidiTunerMessageHandler>,
+        events: &[SmfEvent],
+    ) -> CliResult {
+        let tuning = self.scale.to_scale(app)?.tuning;
+
+        let synth = midi_out_args.create_synth(target, self.method)?;
+        let tuner = self.tuner.create_tuner(synth);
+
+        let mut handler = TuningEventHandler::new(tuner, self.method);
+
+        retuner.retune(
+            events,
+            |event, channel, _| handler.handle(event, channel),
+            |key| tuning.maybe_pitch_of(key),
+        );
+
+        Ok(())
+    }
+}
+
+impl AheadOfTimeOptions {
+    fn run(
+        &self,
+        app: &mut App,
+        retuner: &mut Retuner,
+        midi_out_args: &MidiOutArgs,
+        target: MidiTarget<impl MidiTunerMessageHandler>,
+        events: &[SmfEvent],
+    ) -> CliResult {
+        let scale = self.scale.to_scale(app)?;
+
+        let synth = midi_out_args.create_synth(target, self.method)?;
+        let mut tuner = AotTuner::start(synth);
+
+        let required_channels = tuner.set_tuning(&*scale.tuning, scale.keys).unwrap();
+        if tuner.tuned() {
+            app.writeln(format_args!(
+                "Tuning requires {required_channels} MIDI channels"
+            ))?
+        } else {
+            let available_channels = midi_out_args.num_out_channels;
+            return Err(format!(
+                "Tuning requires {required_channels} MIDI channels but only {available_channels} MIDI channels are available",
+            )
+            .into());
+        }
+
+        let mut handler = TuningEventHandler::new(tuner, self.method);
+
+        retuner.retune(
+            events,
+            |event, channel, _| handler.handle(event, channel),
+            |key| scale.tuning.maybe_pitch_of(key),
+        );
+
+        Ok(())
+    }
+}
+
+struct Retuner {
+    source: MidiSource,
+    messages: Receiver<MidiTunerMessage>,
+    recorder: SmfRecorder,
+    ticks_per_quarter: u16,
+}
+
+impl Retuner {
+    /// Feeds the channel messages of the source channels into `process` and records the messages emitted by the tuner.
+    ///
+    /// The pitch of each started note is determined by `pitch_of` and the time elapsed since the start of the file is passed to `process` as well.
+    /// All other events are copied unchanged, except for track names which are replaced by the recorder.
+    fn retune(
+        &mut self,
+        events: &[SmfEvent],
+        mut process: impl FnMut(PipelineEvent<PianoKey>, u8, Duration),
+        pitch_of: impl Fn(PianoKey) -> Option<Pitch>,
+    ) {
+        self.record_tuner_messages(0);
+
+        let mut micros_per_quarter = DEFAULT_MICROS_PER_QUARTER;
+        let mut curr_ticks = 0;
+        let mut curr_time = Duration::ZERO;
+
+        for event in events {
+            let nanos_per_tick =
+                f64::from(micros_per_quarter) * 1000.0 / f64::from(self.ticks_per_quarter);
+            curr_time += Duration::from_nanos(
+                ((event.ticks - curr_ticks) as f64 * nanos_per_tick).round() as u64,
+            );
+            curr_ticks = event.ticks;
+
+            match &event.message {
+                SmfMessage::Channel(message)
+                    if self.source.channels.contains(&message.channel()) =>
+                {
+                    let channel = message.channel();
+                    let offset = self.source.get_offset(channel);
+                    process(
+                        to_event(message.message_type(), offset, &pitch_of),
+                        channel,
+                        curr_time,
+                    );
+                }
+                SmfMessage::Tempo(tempo) => {
+                    micros_per_quarter = *tempo;
+                    self.recorder
+                        .record_message(event.ticks, event.message.clone());
+                }
+                SmfMessage::TrackName(_) => {}
+                message => self.recorder.record_message(event.ticks, message.clone()),
+            }
+            self.record_tuner_messages(event.ticks);
+        }
+    }
+
+    fn record_tuner_messages(&mut self, ticks: u64) {
+        for message in self.messages.try_iter() {
+            self.recorder.record(ticks, &message);
+        }
+    }
+}
+
+fn to_event(
+    message_type: ChannelMessageType,
+    offset: MultiChannelOffset,
+    pitch_of: impl Fn(PianoKey) -> Option<Pitch>,
+) -> PipelineEvent<PianoKey> {
+    match message_type {
+        ChannelMessageType::NoteOff { key, velocity }
+        | ChannelMessageType::NoteOn {
+            key,
+            velocity: velocity @ 0,
+        } => PipelineEvent::NoteOff {
+            key: offset.get_piano_key(key),
+            velocity,
+        },
+        ChannelMessageType::NoteOn { key, velocity } => {
+            let key = offset.get_piano_key(key);
+            PipelineEvent::NoteOn {
+                key,
+                pitch: pitch_of(key),
+                velocity,
+            }
+        }
+        ChannelMessageType::PolyphonicKeyPressure { key, pressure } => PipelineEvent::KeyPressure {
+            key: offset.get_piano_key(key),
+            pressure,
+        },
+        message_type @ (ChannelMessageType::ControlChange { .. }
+        | ChannelMessageType::ProgramChange { .. }
+        | ChannelMessageType::ChannelPressure { .. }
+        | ChannelMessageType::PitchBendChange { .. }) => PipelineEvent::Channel(message_type),
+    }
+}
@@ -5,12 +5,21 @@ use std::{
 
 use clap::Parser;
 use tune::{
+    consonance::Spectrum,
     key::PianoKey,
-    pitch::{Ratio, RatioExpression, RatioExpressionVariant},
-    scala::{self, Kbm, KbmImportError, KbmRoot, Scl, SclBuildError, SclImportError, SegmentType},
+    pitch::{Pitch, Ratio, RatioExpression, RatioExpressionVariant},
+    scala::{
+        self, HistoricalTemperament, Kbm, KbmImportError, KbmRoot, Scl, SclBuildError,
+        SclImportError, SegmentType,
+    },
 };
 
-use crate::{error::ResultExt, App, CliError, CliResult};
+use crate::{
+    dto::{KbmDto, Rank2Dto, SclDto, TuneDto},
+    error::ResultExt,
+    output::OutputFormat,
+    App, CliError, CliResult,
+};
 
 #[derive(Parser)]
 pub(crate) struct SclOptions {
@@ -24,11 +33,33 @@ pub(crate) struct SclOptions {
 
 impl SclOptions {
     pub fn run(self, app: &mut App) -> CliResult {
-        Ok(app.write(format_args!("{}", self.scl.to_scl(self.name)?.export()))?)
+        let scl = self.scl.to_scl(self.name)?;
+
+        if app.format == OutputFormat::Text {
+            return Ok(app.write(format_args!("{}", scl.export()))?);
+        }
+
+        let dto = match self.scl {
+            SclCommand::Rank2Temperament {
+                generator,
+                num_pos_generations,
+                num_neg_generations,
+                period,
+            } => TuneDto::Rank2(Rank2Dto {
+                description: scl.description().to_owned(),
+                generator_in_cents: generator.as_cents(),
+                period_in_cents: period.as_cents(),
+                num_pos_generations,
+                num_neg_generations,
+            }),
+            _ => TuneDto::Scl(SclDto::from_scl(&scl)),
+        };
+
+        app.write_dto(&dto)
     }
 }
 
-#[derive(Parser)]
+#[derive(Clone, Parser)]
 pub enum SclCommand {
     /// Scale with custom step sizes
     #[command(name = "steps")]
@@ -74,12 +105,98 @@ pub enum SclCommand {
         neji_divisions: Option<u16>,
     },
 
-    /// Import scl file
+    /// Historical 12-note temperament
+    #[command(name = "hist")]
+    Historical {
+        /// Name of the temperament: pythagorean, third-comma, quarter-comma, sixth-comma, werckmeister3, kirnberger3, vallotti or young
+        temperament: HistoricalTemperament,
+
+        /// Number of semitones above C at which the scale starts, e.g. 2 if the reference note is a D
+        #[arg(long = "rot", default_value = "0")]
+        rotation: u8,
+    },
+
+    /// Scale made of the minima of the dissonance curve of a timbre
+    #[command(name = "spectrum")]
+    Spectrum {
+        /// Partials of the timbre with optional amplitudes, e.g. 1,2.76@0.5,5.4@0.25
+        spectrum: Spectrum,
+
+        /// Period of the scale, e.g. the pseudo-octave of the timbre
+        #[arg(long = "per", default_value = "2")]
+        period: Ratio,
+
+        /// Fundamental frequency used to evaluate the dissonance curve
+        #[arg(long = "fund", default_value = "261.626Hz")]
+        fundamental: Pitch,
+    },
+
+    /// Import scl file or a YAML/JSON file written by `tune --format yaml scl`
     #[command(name = "scl-file")]
     UseSclFile {
         /// The location of the file to import
         scl_file_location: PathBuf,
     },
+
+    /// Derive a new scale from an scl file
+    #[command(name = "transform")]
+    Transform {
+        /// The location of the file to transform
+        scl_file_location: PathBuf,
+
+        #[command(subcommand)]
+        transformation: SclTransformation,
+    },
+}
+
+#[derive(Clone, Parser)]
+pub enum SclTransformation {
+    /// Rotate the scale s.t. the given degree becomes the new root
+    #[command(name = "mode")]
+    Mode {
+        /// Degree of the new root, e.g. 5 for the Aeolian mode of a major scale
+        degree: u16,
+    },
+
+    /// Reflect all intervals s.t. the steps of the scale appear in reverse order
+    #[command(name = "invert")]
+    Invert,
+
+    /// Keep the given degrees only (the root and the period are always kept)
+    #[command(name = "subset")]
+    Subset {
+        /// Degrees to keep, e.g. 2,4,7,9
+        #[arg(use_value_delimiter = true)]
+        degrees: Vec<u16>,
+    },
+
+    /// Add the pitches of another scl file within the period of the current scale
+    #[command(name = "merge")]
+    Merge {
+        /// The location of the file to merge
+        other_scl_file_location: PathBuf,
+
+        /// Pitches closer than this tolerance to an existing pitch are discarded
+        #[arg(long = "tol", default_value = "1c")]
+        tolerance: Ratio,
+    },
+
+    /// Stack notes that are a fixed number of degrees apart to form a chord
+    #[command(name = "chord")]
+    Chord {
+        /// Number of degrees between two notes of the chord, e.g. 2 for a tertian chord in a diatonic scale
+        step: u16,
+
+        /// Number of notes of the chord
+        num_notes: u16,
+    },
+
+    /// Replace the period of the scale (pitches not below the new period are removed)
+    #[command(name = "period")]
+    Period {
+        /// The new period, e.g. 3 or 1:13:3
+        period: Ratio,
+    },
 }
 
 impl SclCommand {
@@ -120,6 +237,17 @@ impl SclCommand {
                 )
                 .handle_error("Could not create harmonic scale")
             }
+            &SclCommand::Historical {
+                temperament,
+                rotation,
+            } => scala::create_historical_temperament_scale(description, temperament, rotation)
+                .handle_error("Could not create historical scale"),
+            SclCommand::Spectrum {
+                spectrum,
+                period,
+                fundamental,
+            } => scala::create_spectrum_scale(description, spectrum, *fundamental, *period)
+                .handle_error("Could not create spectrum scale"),
             SclCommand::UseSclFile { scl_file_location } => {
                 let mut scale = import_scl_file(scl_file_location)?;
                 if let Some(description) = description {
@@ -127,6 +255,28 @@ impl SclCommand {
                 }
                 Ok(scale)
             }
+            SclCommand::Transform {
+                scl_file_location,
+                transformation,
+            } => {
+                let scl = import_scl_file(scl_file_location)?;
+                let mut scale = match transformation {
+                    &SclTransformation::Mode { degree } => scl.mode(degree),
+                    SclTransformation::Invert => scl.inverted(),
+                    SclTransformation::Subset { degrees } => scl.subset(degrees.iter().copied()),
+                    SclTransformation::Merge {
+                        other_scl_file_location,
+                        tolerance,
+                    } => scl.merged_with(&import_scl_file(other_scl_file_location)?, *tolerance),
+                    &SclTransformation::Chord { step, num_notes } => scl.stacked(step, num_notes),
+                    &SclTransformation::Period { period } => scl.with_period(period),
+                }
+                .handle_error::<CliError>("Could not transform scale")?;
+                if let Some(description) = description {
+                    scale.set_description(description)
+                }
+                Ok(scale)
+            }
         }
     }
 }
@@ -183,7 +333,12 @@ pub(crate) enum KbmCommand {
 impl KbmCommand {
     pub fn run(&self, app: &mut App) -> CliResult {
         let KbmCommand::WithRefNote { kbm } = self;
-        Ok(app.write(format_args!("{}", kbm.to_kbm()?.export()))?)
+        let kbm = kbm.to_kbm()?;
+
+        match app.format {
+            OutputFormat::Text => Ok(app.write(format_args!("{}", kbm.export()))?),
+            _ => app.write_dto(&TuneDto::Kbm(KbmDto::from_kbm(&kbm))),
+        }
     }
 }
 
@@ -275,6 +430,12 @@ impl KbmRootOptions {
 }
 
 fn import_scl_file(file_name: &Path) -> Result<Scl, String> {
+    if is_tune_file(file_name) {
+        return import_tune_file(file_name)?
+            .to_scl()
+            .map_err(|err| format!("Unsupported tune file {file_name:#?}: {err}"));
+    }
+
     File::open(file_name)
         .map_err(SclImportError::IoError)
         .and_then(Scl::import)
@@ -295,6 +456,12 @@ fn import_scl_file(file_name: &Path) -> Result<Scl, String> {
 }
 
 pub fn import_kbm_file(file_name: &Path) -> Result<Kbm, String> {
+    if is_tune_file(file_name) {
+        return import_tune_file(file_name)?
+            .to_kbm()
+            .map_err(|err| format!("Unsupported tune file {file_name:#?}: {err}"));
+    }
+
     File::open(file_name)
         .map_err(KbmImportError::IoError)
         .and_then(Kbm::import)
@@ -313,3 +480,19 @@ pub fn import_kbm_file(file_name: &Path) -> Result<Kbm, String> {
             }
         })
 }
+
+/// Files in YAML or JSON format written by `tune --format` can be imported in place of scl or kbm files.
+fn is_tune_file(file_name: &Path) -> bool {
+    file_name.extension().is_some_and(|extension| {
+        ["yml", "yaml", "json"]
+            .iter()
+            .any(|tune_extension| extension.eq_ignore_ascii_case(tune_extension))
+    })
+}
+
+fn import_tune_file(file_name: &Path) -> Result<TuneDto, String> {
+    let file = File::open(file_name)
+        .map_err(|err| format!("Could not read tune file {file_name:#?}: {err}"))?;
+    serde_yaml::from_reader(file)
+        .map_err(|err| format!("Could not parse tune file {file_name:#?}: {err}"))
+}
@@ -1,21 +1,30 @@
 use std::{
+    collections::HashMap,
     fs::File,
-    io,
+    io, iter,
     path::{Path, PathBuf},
 };
 
 use clap::Parser;
 use tune::{
     key::PianoKey,
+    layout::IsomorphicLayout,
+    math,
+    note::Note,
     pitch::{Pitch, Pitched, Ratio},
-    scala::{KbmRoot, Scl},
-    tuning::{KeyboardMapping, Tuning},
+    scala::{Kbm, KbmRoot, Scl},
+    tuning::{Approximation, KeyboardMapping, RailsbackCurve, Tuning},
 };
 
 use crate::{
-    dto::{ScaleDto, ScaleItemDto, TuneDto},
+    dto::{
+        FractionDto, IntervalClassDto, IntervalSizeDto, LayerDto, ScaleDto, ScaleItemDto,
+        ScaleTableDto, ScaleTableItemDto, SclAnalysisDto, TuneDto, TuningDto,
+    },
     error::ResultExt,
+    output::OutputFormat,
     scala::{self, KbmOptions, KbmRootOptions, SclCommand},
+    spec::TuningSpec,
     App, CliError, CliResult,
 };
 
@@ -34,7 +43,7 @@ pub(crate) enum ScaleCommand {
     /// Use a kbm file
     #[command(name = "kbm-file")]
     UseKbmFile {
-        /// The location of the kbm file to import
+        /// The location of the kbm file to import (or a YAML/JSON file written by `tune --format yaml kbm`)
         kbm_file_location: PathBuf,
 
         #[command(subcommand)]
@@ -48,16 +57,90 @@ pub(crate) enum ScaleCommand {
         scale_file_location: PathBuf,
     },
 
+    /// Use a tuning file in YAML format composing scales with transpositions, stretches, key-range splits, key overrides and retuning to a reference scale
+    #[command(name = "tuning-file")]
+    UseTuningFile {
+        /// The location of the YAML file to import
+        tuning_file_location: PathBuf,
+    },
+
+    /// Use a tuning given as a single expression, e.g. 31edo@A4=432Hz, harm 8 16 @ C4 or file:foo.scl + kbm:bar.kbm
+    #[command(name = "spec")]
+    UseTuningSpec {
+        #[command(flatten)]
+        tuning_spec: TuningSpecOptions,
+    },
+
+    /// Use a 12-tone piano tuning with octaves stretched according to the Railsback curve
+    #[command(name = "piano")]
+    Piano(PianoOptions),
+
     /// Read a scale file from stdin in YAML format
     #[command(name = "stdin")]
     ReadStdin,
 }
 
+#[derive(Parser)]
+pub(crate) struct TuningSpecOptions {
+    /// The tuning expression: <scl> [@ <ref-note>[=<pitch>]] [+ kbm:<file>].
+    /// <scl> is one of <n>edo, <n>ed<ratio>, steps <ratios>, rank2 <generator> <num-pos> [<num-neg>] [per <period>],
+    /// harm [utonal] <segment-start> [<segment-size>] [neji <divisions>], hist <temperament> [rot <semitones>] or file:<scl-file>
+    #[arg(required = true, num_args = 1.., allow_hyphen_values = true, trailing_var_arg = true)]
+    tuning_spec: Vec<String>,
+}
+
+impl TuningSpecOptions {
+    fn to_tuning_spec(&self) -> CliResult<TuningSpec> {
+        Ok(self.tuning_spec.join(" ").parse()?)
+    }
+}
+
+#[derive(Parser)]
+pub(crate) struct PianoOptions {
+    /// Reference note that should sound at its original or a custom pitch, e.g. 69@440Hz
+    ref_note: KbmRoot,
+
+    /// Lower key bound (inclusive)
+    #[arg(long = "lo-key", default_value = "21")]
+    lower_key_bound: i32,
+
+    /// Upper key bound (exclusive)
+    #[arg(long = "up-key", default_value = "109")]
+    upper_key_bound: i32,
+
+    /// Inharmonicity coefficient B of the string of the reference note
+    #[arg(long = "inharm", default_value = "0.0004")]
+    inharmonicity: f64,
+
+    /// Number of keys over which the inharmonicity coefficient doubles when going up the keyboard
+    #[arg(long = "inharm-doubling", default_value = "8.5")]
+    inharmonicity_doubling: f64,
+
+    /// Measured deviations from 12-EDO in cents at selected keys, e.g. 21:-30,69:0,108:39.
+    /// Overrides the inharmonicity model.
+    #[arg(long = "curve", use_value_delimiter = true, value_parser = parse_curve_point)]
+    curve: Option<Vec<(PianoKey, Ratio)>>,
+}
+
+fn parse_curve_point(s: &str) -> Result<(PianoKey, Ratio), String> {
+    const INVALID_POINT: &str = "Invalid curve point. Should be <midi-number>:<cents>";
+
+    let (midi_number, cents) = s.split_once(':').ok_or(INVALID_POINT)?;
+    Ok((
+        PianoKey::from_midi_number(midi_number.parse::<i32>().map_err(|_| INVALID_POINT)?),
+        Ratio::from_cents(cents.parse::<f64>().map_err(|_| INVALID_POINT)?),
+    ))
+}
+
 #[derive(Parser)]
 pub(crate) struct DumpOptions {
     #[command(flatten)]
     limit: LimitOptions,
 
+    /// Print a report on the structural properties of the scale (requires an scl-based scale)
+    #[arg(long = "analyze")]
+    analyze: bool,
+
     #[command(subcommand)]
     scale: ScaleCommand,
 }
@@ -92,7 +175,7 @@ enum SourceScaleCommand {
 }
 
 #[derive(Parser)]
-enum TargetScaleCommand {
+pub(crate) enum TargetScaleCommand {
     /// Use a linear keyboard mapping with the given reference note
     #[command(name = "ref-note")]
     WithRefNote {
@@ -102,6 +185,13 @@ enum TargetScaleCommand {
         #[command(subcommand)]
         scl: SclCommand,
     },
+
+    /// Use a tuning given as a single expression, e.g. 31edo@A4=432Hz
+    #[command(name = "spec")]
+    UseTuningSpec {
+        #[command(flatten)]
+        tuning_spec: TuningSpecOptions,
+    },
 }
 
 #[derive(Parser)]
@@ -115,31 +205,42 @@ pub(crate) struct Scale {
     pub origin: PianoKey,
     pub keys: Vec<PianoKey>,
     pub tuning: Box<dyn KeyboardMapping<PianoKey> + Send>,
+    /// Scale degrees of the keys, if known
+    pub degrees: HashMap<PianoKey, i32>,
 }
 
 impl Scale {
     fn from_kbm_and_scl(kbm: &KbmOptions, scl: &SclCommand) -> CliResult<Self> {
-        let kbm = kbm.to_kbm()?;
-        Ok(Scale {
-            origin: kbm
-                .kbm_root()
-                .ref_key
-                .plus_steps(kbm.kbm_root().root_offset),
-            keys: kbm.range_iter().collect(),
-            tuning: Box::new((scl.to_scl(None)?, kbm)),
-        })
+        Ok(Scale::from_scl_and_kbm(scl.to_scl(None)?, kbm.to_kbm()?))
     }
 
     fn from_kbm_file_and_scl(kbm_file_location: &Path, scl: &SclCommand) -> CliResult<Self> {
-        let kbm = scala::import_kbm_file(kbm_file_location)?;
-        Ok(Scale {
+        Ok(Scale::from_scl_and_kbm(
+            scl.to_scl(None)?,
+            scala::import_kbm_file(kbm_file_location)?,
+        ))
+    }
+
+    fn from_tuning_spec(tuning_spec: &TuningSpec) -> CliResult<Self> {
+        Ok(Scale::from_scl_and_kbm(
+            tuning_spec.to_scl()?,
+            tuning_spec.to_kbm()?,
+        ))
+    }
+
+    fn from_scl_and_kbm(scl: Scl, kbm: Kbm) -> Self {
+        Scale {
             origin: kbm
                 .kbm_root()
                 .ref_key
                 .plus_steps(kbm.kbm_root().root_offset),
             keys: kbm.range_iter().collect(),
-            tuning: Box::new((scl.to_scl(None)?, kbm)),
-        })
+            degrees: kbm
+                .range_iter()
+                .filter_map(|key| kbm.scale_degree_of(key).map(|degree| (key, degree)))
+                .collect(),
+            tuning: Box::new((scl, kbm)),
+        }
     }
 
     fn from_scale_file(scale_file_location: &Path) -> CliResult<Self> {
@@ -150,15 +251,156 @@ impl Scale {
             origin: PianoKey::from_midi_number(scale_dto.root_key_midi_number),
             keys: scale_dto.keys(),
             tuning: Box::new(scale_dto.to_keyboard_mapping()),
+            degrees: scale_dto.degrees(),
+        })
+    }
+
+    fn from_tuning_file(app: &mut App, tuning_file_location: &Path) -> CliResult<Self> {
+        let file = File::open(tuning_file_location)
+            .handle_error::<CliError>("Could not read tuning file")?;
+        Scale::from_tuning_dto(app, &TuningDto::read(file)?)
+    }
+
+    fn from_tuning_dto(app: &mut App, tuning_dto: &TuningDto) -> CliResult<Self> {
+        Ok(match tuning_dto {
+            TuningDto::Scale(args) => ScaleCommand::from_args(args)?.to_scale(app)?,
+            TuningDto::Transpose { ratio, tuning } => {
+                let scale = Scale::from_tuning_dto(app, tuning)?;
+                Scale {
+                    tuning: Box::new(scale.tuning.transposed_by(parse_ratio(ratio)?)),
+                    ..scale
+                }
+            }
+            TuningDto::Stretch {
+                origin_in_hz,
+                stretch,
+                tuning,
+            } => {
+                let scale = Scale::from_tuning_dto(app, tuning)?;
+                Scale {
+                    tuning: Box::new(
+                        scale
+                            .tuning
+                            .stretched_by(Pitch::from_hz(*origin_in_hz), parse_ratio(stretch)?),
+                    ),
+                    ..scale
+                }
+            }
+            TuningDto::Split {
+                key_midi_number,
+                lower,
+                upper,
+            } => {
+                let split_key = PianoKey::from_midi_number(*key_midi_number);
+                let lower = Scale::from_tuning_dto(app, lower)?;
+                let upper = Scale::from_tuning_dto(app, upper)?;
+                Scale {
+                    origin: if lower.origin < split_key {
+                        lower.origin
+                    } else {
+                        upper.origin
+                    },
+                    keys: lower
+                        .keys
+                        .into_iter()
+                        .filter(|&key| key < split_key)
+                        .chain(upper.keys.into_iter().filter(|&key| key >= split_key))
+                        .collect(),
+                    tuning: Box::new(lower.tuning.split_at(split_key, upper.tuning)),
+                    degrees: lower
+                        .degrees
+                        .into_iter()
+                        .filter(|&(key, _)| key < split_key)
+                        .chain(
+                            upper
+                                .degrees
+                                .into_iter()
+                                .filter(|&(key, _)| key >= split_key),
+                        )
+                        .collect(),
+                }
+            }
+            TuningDto::Override { items, tuning } => {
+                let scale = Scale::from_tuning_dto(app, tuning)?;
+                let overrides: Vec<_> = items
+                    .iter()
+                    .map(|item| {
+                        (
+                            PianoKey::from_midi_number(item.key_midi_number),
+                            Pitch::from_hz(item.pitch_in_hz),
+                        )
+                    })
+                    .collect();
+
+                let mut keys = scale.keys;
+                keys.extend(overrides.iter().map(|&(key, _)| key));
+                keys.sort();
+                keys.dedup();
+
+                // Overridden keys no longer sound at their scale degree
+                let mut degrees = scale.degrees;
+                for (key, _) in &overrides {
+                    degrees.remove(key);
+                }
+
+                Scale {
+                    origin: scale.origin,
+                    keys,
+                    tuning: Box::new(scale.tuning.with_overrides(overrides)),
+                    degrees,
+                }
+            }
+            TuningDto::Snap { reference, tuning } => {
+                let scale = Scale::from_tuning_dto(app, tuning)?;
+                let (scl, kbm) = TargetScaleCommand::from_args(reference)?.to_tuning()?;
+                Scale {
+                    tuning: Box::new(
+                        scale
+                            .tuning
+                            .snapped_to::<PianoKey, _>((scl, kbm.kbm_root())),
+                    ),
+                    ..scale
+                }
+            }
         })
     }
 
+    fn from_piano_options(options: &PianoOptions) -> Self {
+        let ref_key = options.ref_note.ref_key;
+
+        // The reference key is always covered s.t. the pitches can be displayed relative to it
+        let range = PianoKey::from_midi_number(options.lower_key_bound).min(ref_key)
+            ..PianoKey::from_midi_number(options.upper_key_bound).max(ref_key.plus_steps(1));
+
+        let curve = match &options.curve {
+            Some(curve) => {
+                RailsbackCurve::from_measured_curve(options.ref_note, range, curve.iter().copied())
+            }
+            None => RailsbackCurve::from_inharmonicity(options.ref_note, range, |key| {
+                options.inharmonicity
+                    * 2f64.powf(
+                        f64::from(ref_key.num_keys_before(key)) / options.inharmonicity_doubling,
+                    )
+            }),
+        };
+
+        Scale {
+            origin: ref_key,
+            keys: (options.lower_key_bound..options.upper_key_bound)
+                .map(PianoKey::from_midi_number)
+                .collect(),
+            tuning: Box::new(curve),
+            degrees: HashMap::new(),
+        }
+    }
+
     fn from_stdin(app: &mut App) -> CliResult<Self> {
         let scale_dto = ScaleDto::read(app.read())?;
         Ok(Scale {
             origin: PianoKey::from_midi_number(scale_dto.root_key_midi_number),
             keys: scale_dto.keys(),
             tuning: Box::new(scale_dto.to_keyboard_mapping()),
+            degrees: scale_dto.degrees(),
         })
     }
 }
@@ -174,24 +416,72 @@ impl ScaleCommand {
             ScaleCommand::UseScaleFile {
                 scale_file_location,
             } => Scale::from_scale_file(scale_file_location),
+            ScaleCommand::UseTuningFile {
+                tuning_file_location,
+            } => Scale::from_tuning_file(app, tuning_file_location),
+            ScaleCommand::UseTuningSpec { tuning_spec } => {
+                Scale::from_tuning_spec(&tuning_spec.to_tuning_spec()?)
+            }
+            ScaleCommand::Piano(options) => Ok(Scale::from_piano_options(options)),
             ScaleCommand::ReadStdin => Scale::from_stdin(app),
         }
     }
 
+    /// Parses the arguments of the `scale` command given as a single string, e.g. `ref-note 62 steps 1:16:2`.
+    ///
+    /// The arguments are split like in a shell s.t. quoted arguments, e.g. file names, can contain whitespace.
+    pub fn from_args(args: &str) -> CliResult<Self> {
+        ScaleCommand::try_parse_from(iter::once("scale".to_owned()).chain(split_args(args)?))
+            .map_err(|err| format!("Invalid scale `{args}`\n{err}").into())
+    }
+
+    fn to_scl(&self) -> CliResult<Option<Scl>> {
+        match self {
+            ScaleCommand::WithRefNote { scl, .. } | ScaleCommand::UseKbmFile { scl, .. } => {
+                scl.to_scl(None).map(Some)
+            }
+            ScaleCommand::UseTuningSpec { tuning_spec } => {
+                tuning_spec.to_tuning_spec()?.to_scl().map(Some)
+            }
+            ScaleCommand::UseScaleFile { .. }
+            | ScaleCommand::UseTuningFile { .. }
+            | ScaleCommand::Piano(_)
+            | ScaleCommand::ReadStdin => Ok(None),
+        }
+    }
+
     pub fn run(&self, app: &mut App) -> CliResult {
         let scale = self.to_scale(app)?;
+        let layout = self.to_scl()?.as_ref().and_then(best_layout).map(|layout| {
+            let layers = layout.get_layers();
+            (layout, layers)
+        });
 
         let items = scale
             .keys
             .iter()
             .filter_map(|&piano_key| {
-                scale
-                    .tuning
-                    .maybe_pitch_of(piano_key)
-                    .map(|pitch| ScaleItemDto {
+                scale.tuning.maybe_pitch_of(piano_key).map(|pitch| {
+                    let degree = scale.degrees.get(&piano_key).copied();
+                    let layout_item =
+                        degree
+                            .zip(layout.as_ref())
+                            .map(|(degree, (layout, layers))| {
+                                let index = math::i32_rem_u(degree, layout.pergen().period());
+                                (
+                                    layout.get_note_name(index),
+                                    LayerDto::from(layers[usize::from(index)]),
+                                )
+                            });
+
+                    ScaleItemDto {
                         key_midi_number: piano_key.midi_number(),
                         pitch_in_hz: pitch.as_hz(),
-                    })
+                        degree,
+                        note_name: layout_item.as_ref().map(|(note_name, _)| note_name.clone()),
+                        color_layer: layout_item.map(|(_, color_layer)| color_layer),
+                    }
+                })
             })
             .collect();
 
@@ -201,78 +491,196 @@ impl ScaleCommand {
             items,
         };
 
-        let dto = TuneDto::Scale(dump);
-
-        serde_yaml::to_writer(&mut app.output, &dto)
-            .handle_error::<CliError>("Could not write scale file")
+        app.write_dto(&TuneDto::Scale(dump))
     }
 }
 
+/// Finds the isomorphic layout for the average step size of the given scale, as used by the virtual keyboard of microwave.
+fn best_layout(scl: &Scl) -> Option<IsomorphicLayout> {
+    let period = if scl.period().is_negligible() {
+        Ratio::octave()
+    } else {
+        scl.period()
+    };
+    IsomorphicLayout::find_by_step_size(period.divided_into_equal_steps(scl.num_items()))
+        .into_iter()
+        .next()
+}
+
 impl DumpOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
         let scale = self.scale.to_scale(app)?;
+        let table = ScaleTable::new(&scale, self.limit.odd_limit);
 
-        let mut printer = ScaleTablePrinter {
-            app,
-            root_key: scale.origin,
-            root_pitch: scale.tuning.maybe_pitch_of(scale.origin),
-            odd_limit: self.limit.odd_limit,
-        };
-
-        printer.print_table_header()?;
-        for (source_key, pitch) in scale
+        let items = scale
             .keys
             .iter()
             .flat_map(|&key| scale.tuning.maybe_pitch_of(key).map(|pitch| (key, pitch)))
-        {
-            let approximation = pitch.find_in_tuning(());
-            let (letter, octave) = approximation.approx_value.letter_and_octave();
-
-            printer.print_table_row(
-                source_key,
-                pitch,
-                approximation.approx_value.midi_number(),
-                format!("{:>6} {:>2}", letter, octave.octave_number()),
-                approximation.deviation,
-            )?;
+            .map(|(source_key, pitch)| {
+                let approximation = pitch.find_in_tuning(());
+                let (letter, octave) = approximation.approx_value.letter_and_octave();
+
+                ScaleTableItemDto {
+                    target_note_name: Some(format!("{letter}{}", octave.octave_number())),
+                    ..table.item(
+                        source_key,
+                        pitch,
+                        approximation.approx_value.midi_number(),
+                        approximation.deviation,
+                    )
+                }
+            })
+            .collect();
+
+        let scl = match self.analyze {
+            true => Some(
+                self.scale
+                    .to_scl()?
+                    .ok_or_else(|| "Scale analysis requires an scl-based scale".to_owned())?,
+            ),
+            false => None,
+        };
+
+        let dto = table.into_dto(items);
+
+        if app.format != OutputFormat::Text {
+            return app.write_dto(&ScaleTableDto {
+                analysis: scl.as_ref().map(analysis_dto),
+                ..dto
+            });
         }
+
+        print_scale_table(app, &dto)?;
+        if let Some(scl) = scl {
+            print_analysis(app, &scl)?;
+        }
+
         Ok(())
     }
 }
 
+fn analysis_dto(scl: &Scl) -> SclAnalysisDto {
+    let analysis = scl.analyze();
+    let size_dto = |size: &tune::scala::IntervalSize| IntervalSizeDto {
+        size_in_cents: size.size.as_cents(),
+        count: size.count,
+    };
+
+    SclAnalysisDto {
+        constant_structure: analysis.is_constant_structure(),
+        proper: analysis.is_proper(),
+        strictly_proper: analysis.is_strictly_proper(),
+        myhill_property: analysis.has_myhill_property(),
+        distributionally_even: analysis.is_distributionally_even(),
+        mos: analysis.is_mos(),
+        interval_classes: analysis
+            .interval_classes()
+            .iter()
+            .map(|interval_class| IntervalClassDto {
+                num_steps: interval_class.num_steps,
+                sizes: interval_class.sizes.iter().map(size_dto).collect(),
+            })
+            .collect(),
+        interval_vector: analysis.interval_vector().iter().map(size_dto).collect(),
+    }
+}
+
+fn print_analysis(app: &mut App, scl: &Scl) -> CliResult {
+    let analysis = scl.analyze();
+    let format_bool = |value| if value { "yes" } else { "no" };
+
+    app.writeln("")?;
+    app.writeln(format_args!(
+        "  {analysis:-^81}",
+        analysis = "Scale Analysis"
+    ))?;
+    app.writeln(format_args!(
+        "Constant structure: {}",
+        format_bool(analysis.is_constant_structure())
+    ))?;
+    app.writeln(format_args!(
+        "Proper: {}",
+        format_bool(analysis.is_proper())
+    ))?;
+    app.writeln(format_args!(
+        "Strictly proper: {}",
+        format_bool(analysis.is_strictly_proper())
+    ))?;
+    app.writeln(format_args!(
+        "Myhill property: {}",
+        format_bool(analysis.has_myhill_property())
+    ))?;
+    app.writeln(format_args!(
+        "Distributionally even: {}",
+        format_bool(analysis.is_distributionally_even())
+    ))?;
+    app.writeln(format_args!("MOS: {}", format_bool(analysis.is_mos())))?;
+
+    app.writeln("Interval classes:")?;
+    for interval_class in analysis.interval_classes() {
+        app.write(format_args!("{:>5} steps:", interval_class.num_steps))?;
+        for size in &interval_class.sizes {
+            app.write(format_args!(
+                " {:>9.3}¢ ({}x)",
+                size.size.as_cents(),
+                size.count
+            ))?;
+        }
+        app.writeln("")?;
+    }
+
+    app.writeln("Interval vector:")?;
+    for size in analysis.interval_vector() {
+        app.writeln(format_args!(
+            "{:>11.3}¢ ({}x)",
+            size.size.as_cents(),
+            size.count
+        ))?;
+    }
+
+    Ok(())
+}
+
 impl DiffOptions {
     pub fn run(&self, app: &mut App) -> CliResult {
         let source_scale = self.source_scale.source_scale(app)?;
-        let (target_scl, target_kbm_root) = self.source_scale.target_tuning()?;
+        let (target_scl, target_kbm) = self.source_scale.target_tuning()?;
+        let table = ScaleTable::new(&source_scale, self.limit.odd_limit);
 
-        let mut printer = ScaleTablePrinter {
-            app,
-            root_pitch: source_scale.tuning.maybe_pitch_of(source_scale.origin),
-            root_key: source_scale.origin,
-            odd_limit: self.limit.odd_limit,
-        };
+        let items = source_scale
+            .keys
+            .iter()
+            .flat_map(|&key| {
+                source_scale
+                    .tuning
+                    .maybe_pitch_of(key)
+                    .map(|pitch| (key, pitch))
+            })
+            .map(|(source_key, pitch)| {
+                let approximation = find_mapped_key(&target_scl, &target_kbm, pitch)?;
+                let index = target_kbm
+                    .kbm_root()
+                    .ref_key
+                    .num_keys_before(approximation.approx_value);
+
+                Ok(ScaleTableItemDto {
+                    target_index: Some(index),
+                    ..table.item(
+                        source_key,
+                        pitch,
+                        approximation.approx_value.midi_number(),
+                        approximation.deviation,
+                    )
+                })
+            })
+            .collect::<CliResult<_>>()?;
 
-        printer.print_table_header()?;
-        for (source_key, pitch) in source_scale.keys.iter().flat_map(|&key| {
-            source_scale
-                .tuning
-                .maybe_pitch_of(key)
-                .map(|pitch| (key, pitch))
-        }) {
-            let approximation = (&target_scl, target_kbm_root).find_by_pitch(pitch);
-            let index = target_kbm_root
-                .ref_key
-                .num_keys_before(approximation.approx_value);
-
-            printer.print_table_row(
-                source_key,
-                pitch,
-                approximation.approx_value.midi_number(),
-                format!("IDX {index:>5}"),
-                approximation.deviation,
-            )?;
+        let dto = table.into_dto(items);
+
+        match app.format {
+            OutputFormat::Text => Ok(print_scale_table(app, &dto)?),
+            _ => app.write_dto(&dto),
         }
-        Ok(())
     }
 }
 
@@ -287,66 +695,258 @@ impl SourceScaleCommand {
         }
     }
 
-    pub fn target_tuning(&self) -> CliResult<(Scl, KbmRoot)> {
+    pub fn target_tuning(&self) -> CliResult<(Scl, Kbm)> {
         let target_scale = match self {
             SourceScaleCommand::UseScaleFile { target_scale, .. } => target_scale,
             SourceScaleCommand::ReadStdin { target_scale } => target_scale,
         };
 
-        let TargetScaleCommand::WithRefNote { kbm_root, scl } = target_scale;
-        Ok((scl.to_scl(None)?, kbm_root.to_kbm_root()))
+        target_scale.to_tuning()
+    }
+}
+
+impl TargetScaleCommand {
+    /// Parses the arguments of a target scale given as a single string, e.g. `ref-note 62 steps 1:12:2`.
+    ///
+    /// See [`ScaleCommand::from_args`] for how the arguments are split.
+    pub fn from_args(args: &str) -> CliResult<Self> {
+        TargetScaleCommand::try_parse_from(
+            iter::once("target-scale".to_owned()).chain(split_args(args)?),
+        )
+        .map_err(|err| format!("Invalid target scale `{args}`\n{err}").into())
     }
+
+    pub fn to_tuning(&self) -> CliResult<(Scl, Kbm)> {
+        match self {
+            TargetScaleCommand::WithRefNote { kbm_root, scl } => {
+                Ok((scl.to_scl(None)?, kbm_root.to_kbm_root().to_kbm()))
+            }
+            TargetScaleCommand::UseTuningSpec { tuning_spec } => {
+                let tuning_spec = tuning_spec.to_tuning_spec()?;
+                Ok((tuning_spec.to_scl()?, tuning_spec.to_kbm()?))
+            }
+        }
+    }
+}
+
+/// Finds the key of `kbm` whose pitch is closest to `pitch`, taking the key mapping of `kbm` into account.
+///
+/// The range of `kbm` is ignored s.t. pitches beyond the keyboard can still be located.
+pub(crate) fn find_mapped_key(
+    scl: &Scl,
+    kbm: &Kbm,
+    pitch: Pitch,
+) -> CliResult<Approximation<PianoKey>> {
+    let kbm_root = kbm.kbm_root();
+    let tuning = (scl, kbm_root);
+    let approximation: Approximation<i32> = tuning.find_by_pitch(pitch);
+
+    // An empty key mapping is linear
+    if kbm.num_items() == 0 {
+        return Ok(Approximation {
+            approx_value: kbm_root.ref_key.plus_steps(approximation.approx_value),
+            deviation: approximation.deviation,
+        });
+    }
+
+    let num_items = i32::from(kbm.num_items());
+    let formal_octave = i32::from(kbm.formal_octave());
+
+    kbm.key_mapping()
+        .iter()
+        .zip(0..)
+        .filter_map(|(mapped_degree, index)| mapped_degree.map(|degree| (index, i32::from(degree))))
+        .flat_map(|(index, mapped_degree)| {
+            // The mapped degrees closest to the approximated degree are in one of two neighboring formal octaves
+            let num_octaves = match formal_octave {
+                0 => 0,
+                formal_octave => {
+                    (approximation.approx_value - mapped_degree).div_euclid(formal_octave)
+                }
+            };
+            [num_octaves, num_octaves + formal_octave.signum()].map(|num_octaves| {
+                let degree = mapped_degree + num_octaves * formal_octave;
+                let key_degree = index + num_octaves * num_items;
+                let deviation = match degree == approximation.approx_value {
+                    true => approximation.deviation,
+                    false => Ratio::between_pitches(tuning.pitch_of(degree), pitch),
+                };
+                (key_degree, deviation)
+            })
+        })
+        .min_by(|(key_degree_a, deviation_a), (key_degree_b, deviation_b)| {
+            deviation_a
+                .abs()
+                .total_cmp(&deviation_b.abs())
+                .then(key_degree_a.abs().cmp(&key_degree_b.abs()))
+        })
+        .map(|(key_degree, deviation)| Approximation {
+            approx_value: kbm_root.ref_key.plus_steps(key_degree),
+            deviation,
+        })
+        .ok_or_else(|| {
+            "The target keyboard mapping does not contain any mapped keys"
+                .to_owned()
+                .into()
+        })
 }
 
-struct ScaleTablePrinter<'a, 'b> {
-    app: &'a mut App<'b>,
+fn parse_ratio(ratio: &str) -> CliResult<Ratio> {
+    ratio
+        .parse()
+        .map_err(|err| format!("Invalid ratio `{ratio}`: {err}").into())
+}
+
+struct ScaleTable<'a> {
     root_key: PianoKey,
     root_pitch: Option<Pitch>,
+    degrees: &'a HashMap<PianoKey, i32>,
     odd_limit: u16,
 }
 
-impl ScaleTablePrinter<'_, '_> {
-    fn print_table_header(&mut self) -> io::Result<()> {
-        self.app.writeln(format_args!(
-            "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28}",
-            source = "Source Scale",
-            pitch = "Pitch",
-            target = "Target Scale"
-        ))
+impl<'a> ScaleTable<'a> {
+    fn new(scale: &'a Scale, odd_limit: u16) -> Self {
+        Self {
+            root_key: scale.origin,
+            root_pitch: scale.tuning.maybe_pitch_of(scale.origin),
+            degrees: &scale.degrees,
+            odd_limit,
+        }
     }
 
-    fn print_table_row(
-        &mut self,
+    fn item(
+        &self,
         source_key: PianoKey,
         pitch: Pitch,
         target_midi: i32,
-        target_index: String,
         deviation: Ratio,
-    ) -> io::Result<()> {
-        let source_index = self.root_key.num_keys_before(source_key);
-        if source_index == 0 {
-            self.app.write(format_args!("> "))?;
+    ) -> ScaleTableItemDto {
+        let nearest_fraction = Ratio::between_pitches(self.root_pitch.unwrap_or(pitch), pitch)
+            .nearest_fraction(self.odd_limit);
+
+        ScaleTableItemDto {
+            key_midi_number: source_key.midi_number(),
+            index: self.root_key.num_keys_before(source_key),
+            degree: self.degrees.get(&source_key).copied(),
+            pitch_in_hz: pitch.as_hz(),
+            nearest_fraction: FractionDto {
+                numer: nearest_fraction.numer,
+                denom: nearest_fraction.denom,
+                deviation_in_cents: nearest_fraction.deviation.as_cents(),
+                num_octaves: nearest_fraction.num_octaves,
+            },
+            target_key_midi_number: target_midi,
+            target_note_name: None,
+            target_index: None,
+            deviation_in_cents: deviation.as_cents(),
+        }
+    }
+
+    fn into_dto(self, items: Vec<ScaleTableItemDto>) -> ScaleTableDto {
+        ScaleTableDto {
+            root_key_midi_number: self.root_key.midi_number(),
+            root_pitch_in_hz: self.root_pitch.map(Pitch::as_hz),
+            items,
+            analysis: None,
+        }
+    }
+}
+
+fn print_scale_table(app: &mut App, dto: &ScaleTableDto) -> io::Result<()> {
+    app.writeln(format_args!(
+        "  {source:-^33} ‖ {pitch:-^14} ‖ {target:-^28}",
+        source = "Source Scale",
+        pitch = "Pitch",
+        target = "Target Scale"
+    ))?;
+
+    for item in &dto.items {
+        if item.index == 0 {
+            app.write(format_args!("> "))?;
         } else {
-            self.app.write(format_args!("  "))?;
+            app.write(format_args!("  "))?;
         }
 
-        let nearest_fraction = Ratio::between_pitches(self.root_pitch.unwrap_or(pitch), pitch)
-            .nearest_fraction(self.odd_limit);
+        let target_column = match item.target_index {
+            Some(index) => format!("IDX {index:>5}"),
+            None => {
+                let (letter, octave) =
+                    Note::from_midi_number(item.target_key_midi_number).letter_and_octave();
+                format!("{:>6} {:>2}", letter, octave.octave_number())
+            }
+        };
 
-        self.app.writeln(format_args!(
+        app.writeln(format_args!(
             "{source_midi:>3} | IDX {source_index:>4} | \
              {numer:>2}/{denom:<2} {fract_deviation:>+4.0}¢ {fract_octaves:>+3}o ‖ \
-             {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_index} | {deviation:>+8.3}¢",
-            source_midi = source_key.midi_number(),
-            source_index = source_index,
-            pitch = pitch.as_hz(),
-            numer = nearest_fraction.numer,
-            denom = nearest_fraction.denom,
-            fract_deviation = nearest_fraction.deviation.as_cents(),
-            fract_octaves = nearest_fraction.num_octaves,
-            target_midi = target_midi,
-            target_index = target_index,
-            deviation = deviation.as_cents(),
-        ))
+             {pitch:>11.3} Hz ‖ {target_midi:>4} | {target_column} | {deviation:>+8.3}¢",
+            source_midi = item.key_midi_number,
+            source_index = item.index,
+            pitch = item.pitch_in_hz,
+            numer = item.nearest_fraction.numer,
+            denom = item.nearest_fraction.denom,
+            fract_deviation = item.nearest_fraction.deviation_in_cents,
+            fract_octaves = item.nearest_fraction.num_octaves,
+            target_midi = item.target_key_midi_number,
+            deviation = item.deviation_in_cents,
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Splits `args` at unquoted whitespace. Single and double quotes group arguments and a backslash escapes the next character, except inside single quotes.
+fn split_args(args: &str) -> CliResult<Vec<String>> {
+    let mut result = Vec::new();
+    let mut curr_arg = None;
+    let mut quote = None;
+
+    let mut chars = args.chars();
+    while let Some(char) = chars.next() {
+        match (quote, char) {
+            (Some(open_quote), char) if char == open_quote => quote = None,
+            (None, '\'' | '"') => {
+                quote = Some(char);
+                curr_arg.get_or_insert_with(String::new);
+            }
+            (None | Some('"'), '\\') => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| format!("Trailing backslash in `{args}`"))?;
+                curr_arg.get_or_insert_with(String::new).push(escaped);
+            }
+            (None, char) if char.is_whitespace() => result.extend(curr_arg.take()),
+            (_, char) => curr_arg.get_or_insert_with(String::new).push(char),
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in `{args}`").into());
+    }
+    result.extend(curr_arg);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_args_like_a_shell() {
+        let split = |args| split_args(args).ok();
+        let expected = |args: &[&str]| Some(args.iter().map(|&arg| arg.to_owned()).collect());
+
+        assert_eq!(
+            split("  ref-note 62\tsteps  1:12:2 "),
+            expected(&["ref-note", "62", "steps", "1:12:2"])
+        );
+        assert_eq!(
+            split(r#"scl-file "my scale.scl" 'it''s' a\ b "" "\"q\"""#),
+            expected(&["scl-file", "my scale.scl", "its", "a b", "", "\"q\""])
+        );
+        assert_eq!(split(r"'C:\scales'"), expected(&[r"C:\scales"]));
+        assert_eq!(split("\"unterminated"), None);
+        assert_eq!(split("trailing\\"), None);
     }
 }
@@ -12,3 +12,6 @@ pub mod portable {
 pub mod scala {
     pub use crate::scala::*;
 }
+pub mod spec {
+    pub use crate::spec::*;
+}
@@ -0,0 +1,344 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use tune::{
+    key::PianoKey,
+    note::{Note, NoteLetter, Octave},
+    pitch::{Pitch, Ratio},
+    scala::{Kbm, KbmRoot, Scl},
+};
+
+use crate::{error::ResultExt, scala::SclCommand, CliResult};
+
+/// A tuning given as a single string, e.g. `31edo@A4=432Hz` or `file:foo.scl + kbm:bar.kbm`.
+///
+/// The syntax is `<scl> [@ <ref-note>[=<pitch>]] [+ kbm:<file>]` where `<scl>` is one of
+///
+/// - `<n>edo` or `<n>ed<ratio>`, e.g. `31edo` or `13ed3`
+/// - `steps <ratio>,<ratio>,...`, e.g. `steps 9/8,10/9,16/15`
+/// - `rank2 <generator> <num-pos> [<num-neg>] [per <period>]`, e.g. `rank2 3/2 5 1 per 2`
+/// - `harm [utonal] <segment-start> [<segment-size>] [neji <divisions>]`, e.g. `harm 8 16`
+/// - `hist <temperament> [rot <semitones>]`, e.g. `hist werckmeister3`
+/// - `file:<scl-file>`
+///
+/// The reference note is a MIDI number or a note name like `A4`, `C#4` or `Bb3` and defaults to C4.
+/// A keyboard mapping file and a reference note cannot be combined.
+#[derive(Clone)]
+pub struct TuningSpec {
+    source: String,
+    scl: SclCommand,
+    kbm: KbmSpec,
+}
+
+#[derive(Clone)]
+enum KbmSpec {
+    WithRefNote(KbmRoot),
+    UseKbmFile(PathBuf),
+}
+
+impl TuningSpec {
+    pub fn to_scl(&self) -> CliResult<Scl> {
+        self.scl.to_scl(None)
+    }
+
+    pub fn to_kbm(&self) -> CliResult<Kbm> {
+        match &self.kbm {
+            &KbmSpec::WithRefNote(kbm_root) => Kbm::builder(kbm_root)
+                .range(PianoKey::from_midi_number(21)..PianoKey::from_midi_number(109))
+                .build()
+                .handle_error("Could not create keyboard mapping"),
+            KbmSpec::UseKbmFile(kbm_file_location) => {
+                Ok(crate::scala::import_kbm_file(kbm_file_location)?)
+            }
+        }
+    }
+}
+
+impl Display for TuningSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for TuningSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_tuning_spec(s).map_err(|err| format!("Invalid tuning `{s}`: {err}"))
+    }
+}
+
+fn parse_tuning_spec(s: &str) -> Result<TuningSpec, String> {
+    let (scl_and_ref_note, kbm_file_location) = match s.rsplit_once('+') {
+        Some((scl_and_ref_note, kbm)) if kbm.trim().starts_with("kbm:") => {
+            let kbm_file_location = kbm.trim()["kbm:".len()..].trim();
+            (scl_and_ref_note, Some(PathBuf::from(kbm_file_location)))
+        }
+        _ => (s, None),
+    };
+
+    let (scl, ref_note) = match scl_and_ref_note.rsplit_once('@') {
+        Some((scl, ref_note)) => (scl, Some(parse_ref_note(ref_note.trim())?)),
+        None => (scl_and_ref_note, None),
+    };
+
+    let kbm = match (ref_note, kbm_file_location) {
+        (None, None) => KbmSpec::WithRefNote(NoteLetter::C.in_octave(4).into()),
+        (Some(kbm_root), None) => KbmSpec::WithRefNote(kbm_root),
+        (None, Some(kbm_file_location)) => KbmSpec::UseKbmFile(kbm_file_location),
+        (Some(_), Some(_)) => {
+            return Err("A reference note cannot be combined with a kbm file".to_owned())
+        }
+    };
+
+    Ok(TuningSpec {
+        source: s.trim().to_owned(),
+        scl: parse_scl(scl.trim())?,
+        kbm,
+    })
+}
+
+fn parse_scl(s: &str) -> Result<SclCommand, String> {
+    if let Some(scl_file_location) = s.strip_prefix("file:") {
+        return Ok(SclCommand::UseSclFile {
+            scl_file_location: PathBuf::from(scl_file_location.trim()),
+        });
+    }
+
+    let mut tokens = s.split_whitespace();
+    let scl_type = tokens.next().ok_or("Missing scale")?;
+    let mut args = Args {
+        positional: Vec::new(),
+        keywords: Vec::new(),
+    };
+    while let Some(token) = tokens.next() {
+        match token {
+            "per" | "neji" | "rot" => args.keywords.push((
+                token,
+                tokens
+                    .next()
+                    .ok_or_else(|| format!("Missing value for `{token}`"))?,
+            )),
+            "utonal" => args.keywords.push((token, "")),
+            _ => args.positional.push(token),
+        }
+    }
+
+    let scl = match scl_type {
+        "steps" => SclCommand::Steps {
+            items: args
+                .positional
+                .iter()
+                .flat_map(|arg| arg.split(','))
+                .map(str::parse)
+                .collect::<Result<_, _>>()?,
+        },
+        "rank2" => SclCommand::Rank2Temperament {
+            generator: args.required(0, "generator")?,
+            num_pos_generations: args.required(1, "number of positive generations")?,
+            num_neg_generations: args.optional(2)?.unwrap_or(0),
+            period: args.keyword("per")?.unwrap_or_else(Ratio::octave),
+        },
+        "harm" => SclCommand::HarmonicSeries {
+            utonal: args.flag("utonal"),
+            segment_start: args.required(0, "segment start")?,
+            segment_size: args.optional(1)?,
+            neji_divisions: args.keyword("neji")?,
+        },
+        "hist" => SclCommand::Historical {
+            temperament: args.required(0, "temperament")?,
+            rotation: args.keyword("rot")?.unwrap_or(0),
+        },
+        edo => {
+            let (num_steps, interval) = edo
+                .split_once("ed")
+                .ok_or_else(|| format!("Unknown scale type `{edo}`"))?;
+            let num_steps = num_steps
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid number of steps `{num_steps}`"))?;
+            let interval = match interval {
+                "o" => "2",
+                interval => interval,
+            };
+            SclCommand::Steps {
+                items: vec![format!("1:{num_steps}:({interval})").parse()?],
+            }
+        }
+    };
+
+    args.check_all_used(scl_type)?;
+
+    Ok(scl)
+}
+
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    keywords: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Args<'a> {
+    fn required<T: FromStr>(&self, index: usize, name: &str) -> Result<T, String> {
+        self.optional(index)?
+            .ok_or_else(|| format!("Missing {name}"))
+    }
+
+    fn optional<T: FromStr>(&self, index: usize) -> Result<Option<T>, String> {
+        self.positional
+            .get(index)
+            .map(|&arg| parse_arg(arg))
+            .transpose()
+    }
+
+    fn keyword<T: FromStr>(&self, keyword: &str) -> Result<Option<T>, String> {
+        self.keywords
+            .iter()
+            .find(|&&(name, _)| name == keyword)
+            .map(|&(_, arg)| parse_arg(arg))
+            .transpose()
+    }
+
+    fn flag(&self, keyword: &str) -> bool {
+        self.keywords.iter().any(|&(name, _)| name == keyword)
+    }
+
+    fn check_all_used(&self, scl_type: &str) -> Result<(), String> {
+        let (max_positional, allowed_keywords): (_, &[_]) = match scl_type {
+            "steps" => (usize::MAX, &[]),
+            "rank2" => (3, &["per"]),
+            "harm" => (2, &["utonal", "neji"]),
+            "hist" => (1, &["rot"]),
+            _ => (0, &[]),
+        };
+        if let Some(arg) = self.positional.get(max_positional) {
+            return Err(format!("Unexpected argument `{arg}`"));
+        }
+        if let Some((keyword, _)) = self
+            .keywords
+            .iter()
+            .find(|(keyword, _)| !allowed_keywords.contains(keyword))
+        {
+            return Err(format!("Unexpected keyword `{keyword}`"));
+        }
+        Ok(())
+    }
+}
+
+fn parse_arg<T: FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse().map_err(|_| format!("Invalid argument `{arg}`"))
+}
+
+fn parse_ref_note(s: &str) -> Result<KbmRoot, String> {
+    let (note, pitch) = match s.split_once('=') {
+        Some((note, pitch)) => (note.trim(), Some(pitch.trim())),
+        None => (s, None),
+    };
+
+    let note = parse_note(note)?;
+    Ok(match pitch {
+        Some(pitch) => note.at_pitch(pitch.parse::<Pitch>()?).into(),
+        None => note.into(),
+    })
+}
+
+fn parse_note(s: &str) -> Result<Note, String> {
+    if let Ok(midi_number) = s.parse::<i32>() {
+        return Ok(Note::from_midi_number(midi_number));
+    }
+
+    const INVALID_NOTE: &str =
+        "Invalid note. Should be a MIDI number or a note name, e.g. 69 or A4";
+
+    let mut chars = s.chars();
+    let letter = match chars.next() {
+        Some('C') => NoteLetter::C,
+        Some('D') => NoteLetter::D,
+        Some('E') => NoteLetter::E,
+        Some('F') => NoteLetter::F,
+        Some('G') => NoteLetter::G,
+        Some('A') => NoteLetter::A,
+        Some('B') => NoteLetter::B,
+        _ => return Err(INVALID_NOTE.to_owned()),
+    };
+    let rest = chars.as_str();
+    let (alteration, octave) = match rest.strip_prefix('#') {
+        Some(octave) => (1, octave),
+        None => match rest.strip_prefix('b') {
+            Some(octave) => (-1, octave),
+            None => (0, rest),
+        },
+    };
+    let octave = octave.parse::<i16>().map_err(|_| INVALID_NOTE)?;
+
+    Ok(letter
+        .in_octave(Octave::from_octave_number(octave))
+        .plus_semitones(alteration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_note_names() {
+        assert_eq!(parse_note("69"), Ok(Note::from_midi_number(69)));
+        assert_eq!(parse_note("A4"), Ok(Note::from_midi_number(69)));
+        assert_eq!(parse_note("C#4"), Ok(Note::from_midi_number(61)));
+        assert_eq!(parse_note("Bb3"), Ok(Note::from_midi_number(58)));
+        assert_eq!(parse_note("C-1"), Ok(Note::from_midi_number(0)));
+        assert!(parse_note("H4").is_err());
+        assert!(parse_note("A").is_err());
+    }
+
+    #[test]
+    fn parse_tuning_specs() {
+        let (scl, kbm) = scl_and_kbm("31edo@A4=432Hz");
+        assert_eq!(scl.num_items(), 1);
+        assert_eq!(kbm.kbm_root().ref_key.midi_number(), 69);
+        assert!((kbm.kbm_root().ref_pitch.as_hz() - 432.0).abs() < 1e-9);
+
+        let (scl, kbm) = scl_and_kbm("harm 8 16 @ C4");
+        assert_eq!(scl.num_items(), 16);
+        assert_eq!(kbm.kbm_root().ref_key.midi_number(), 60);
+
+        let (scl, _) = scl_and_kbm("rank2 3/2 5 1 per 2");
+        assert_eq!(scl.num_items(), 7);
+
+        let (scl, kbm) = scl_and_kbm("hist werckmeister3 rot 2 @ D4");
+        assert_eq!(scl.num_items(), 12);
+        assert_eq!(kbm.kbm_root().ref_key.midi_number(), 62);
+
+        let (scl, kbm) = scl_and_kbm("13ed3");
+        assert!((scl.period().as_float().powi(13) - 3.0).abs() < 1e-9);
+        assert_eq!(kbm.kbm_root().ref_key.midi_number(), 60);
+
+        assert!("file:foo.scl + kbm:bar.kbm".parse::<TuningSpec>().is_ok());
+    }
+
+    fn scl_and_kbm(spec: &str) -> (Scl, Kbm) {
+        let spec = spec.parse::<TuningSpec>().unwrap();
+        match (spec.to_scl(), spec.to_kbm()) {
+            (Ok(scl), Ok(kbm)) => (scl, kbm),
+            _ => panic!("Could not create tuning `{spec}`"),
+        }
+    }
+
+    #[test]
+    fn reject_invalid_tuning_specs() {
+        for (spec, err) in [
+            ("", "Invalid tuning ``: Missing scale"),
+            ("foo", "Invalid tuning `foo`: Unknown scale type `foo`"),
+            ("rank2 3/2", "Invalid tuning `rank2 3/2`: Missing number of positive generations"),
+            ("harm 8 16 32", "Invalid tuning `harm 8 16 32`: Unexpected argument `32`"),
+            ("hist young per 2", "Invalid tuning `hist young per 2`: Unexpected keyword `per`"),
+            (
+                "12edo @ A4 + kbm:foo.kbm",
+                "Invalid tuning `12edo @ A4 + kbm:foo.kbm`: A reference note cannot be combined with a kbm file",
+            ),
+        ] {
+            assert_eq!(spec.parse::<TuningSpec>().err().as_deref(), Some(err));
+        }
+    }
+}
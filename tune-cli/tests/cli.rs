@@ -1,8 +1,16 @@
 use std::{
-    env, fs,
+    env,
+    fmt::Write,
+    fs::{self, File},
+    path::{Path, PathBuf},
     process::{Command, Output, Stdio},
 };
 
+use tune::{
+    midi::ChannelMessageType,
+    smf::{Smf, SmfEvent, SmfMessage, SmfTrack},
+};
+
 macro_rules! check_output {
     ($file_name:literal, $actual:expr) => {
         check_output(&$actual, include_str!($file_name), $file_name);
@@ -21,6 +29,14 @@ fn call_cli(args: &[&str]) -> Output {
         .unwrap()
 }
 
+fn call_cli_in_dir(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tune"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
 fn call_cli_piped(first_args: &[&str], second_args: &[&str]) -> Output {
     let first_command = Command::new(env!("CARGO_BIN_EXE_tune"))
         .args(first_args)
@@ -65,6 +81,304 @@ fn dump_7_edo_with_root() {
     check_output!("snapshots/dump_7_edo_with_root.stdout", output.stdout);
 }
 
+#[test]
+fn dump_analysis_of_pythagorean_major() {
+    let output = call_cli(&[
+        "dump",
+        "--analyze",
+        "ref-note",
+        "62",
+        "--lo-key",
+        "62",
+        "--up-key",
+        "64",
+        "rank2",
+        "3/2",
+        "5",
+        "1",
+    ]);
+    check_output!(
+        "snapshots/README_dump_analysis_of_pythagorean_major.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_piano_treble_with_inharmonicity() {
+    let output = call_cli(&["dump", "piano", "69", "--lo-key", "96", "--up-key", "109"]);
+    check_output!(
+        "snapshots/README_dump_piano_treble_with_inharmonicity.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_piano_with_measured_curve() {
+    let output = call_cli(&[
+        "dump",
+        "piano",
+        "69@432Hz",
+        "--lo-key",
+        "21",
+        "--up-key",
+        "25",
+        "--curve",
+        "21:-30,69:0,108:39",
+    ]);
+    check_output!(
+        "snapshots/dump_piano_with_measured_curve.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_split_tuning_with_override() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_split_tuning.yaml"),
+        "\
+Override:
+  items:
+    - key_midi_number: 66
+      pitch_in_hz: 440.0
+  tuning:
+    Split:
+      key_midi_number: 60
+      lower:
+        Scale: ref-note 60 --lo-key 56 --up-key 60 steps 1:19:2
+      upper:
+        Scale: ref-note 60 --lo-key 60 --up-key 66 harm 4
+",
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(&dir, &["dump", "tuning-file", "tune_split_tuning.yaml"]);
+    check_output!(
+        "snapshots/README_dump_split_tuning_with_override.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_stretched_and_snapped_tuning() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_snapped_tuning.yaml"),
+        "\
+Snap:
+  reference: ref-note 69 steps 1:24:2
+  tuning:
+    Stretch:
+      origin_in_hz: 440.0
+      stretch: 200c
+      tuning:
+        Transpose:
+          ratio: 3/2
+          tuning:
+            Scale: ref-note 62 --lo-key 62 --up-key 67 steps 1:12:2
+",
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(&dir, &["dump", "tuning-file", "tune_snapped_tuning.yaml"]);
+    check_output!(
+        "snapshots/dump_stretched_and_snapped_tuning.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_tuning_with_quoted_scl_file() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune quoted.scl"),
+        "\
+Pentatonic
+5
+9/8
+5/4
+3/2
+5/3
+2/1
+",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tune_quoted_tuning.yaml"),
+        "\
+Scale: ref-note 62 --lo-key 62 --up-key 68 scl-file \"tune quoted.scl\"
+",
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(&dir, &["dump", "tuning-file", "tune_quoted_tuning.yaml"]);
+    check_output!(
+        "snapshots/dump_tuning_with_quoted_scl_file.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_tuning_spec_with_scl_and_kbm_file() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_spec.scl"),
+        "\
+Pentatonic
+5
+9/8
+5/4
+3/2
+5/3
+2/1
+",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tune_spec.kbm"),
+        "\
+0
+60
+66
+60
+60
+256.000
+0
+",
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "dump",
+            "spec",
+            "file:tune_spec.scl",
+            "+",
+            "kbm:tune_spec.kbm",
+        ],
+    );
+    check_output!(
+        "snapshots/dump_tuning_spec_with_scl_and_kbm_file.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dump_tune_files_created_with_format_option() {
+    let dir = env::temp_dir();
+    let scl = call_cli(&["--format", "yaml", "scl", "rank2", "3/2", "5", "1"]);
+    fs::write(dir.join("tune_rank2.yml"), scl.stdout).unwrap();
+    let kbm = call_cli(&[
+        "--format",
+        "json",
+        "kbm",
+        "ref-note",
+        "62",
+        "--lo-key",
+        "60",
+        "--up-key",
+        "67",
+        "--key-map",
+        "0,x,1,2,3,4,5",
+        "--octave",
+        "7",
+    ]);
+    fs::write(dir.join("tune_kbm.json"), kbm.stdout).unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "dump",
+            "kbm-file",
+            "tune_kbm.json",
+            "scl-file",
+            "tune_rank2.yml",
+        ],
+    );
+    check_output!(
+        "snapshots/dump_tune_files_created_with_format_option.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn diff_against_tuning_spec() {
+    let output = call_cli_piped(
+        &[
+            "scale",
+            "spec",
+            "hist",
+            "quarter-comma",
+            "rot",
+            "2",
+            "@",
+            "D4",
+        ],
+        &["diff", "stdin", "spec", "31edo@D4"],
+    );
+    check_output!("snapshots/diff_against_tuning_spec.stdout", output.stdout);
+}
+
+#[test]
+fn diff_against_tuning_spec_with_key_mapping() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_diff_major.scl"),
+        "\
+Major
+7
+9/8
+5/4
+4/3
+3/2
+5/3
+15/8
+2/1
+",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tune_diff_white_keys.kbm"),
+        "\
+12
+0
+127
+60
+60
+264.000
+7
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+",
+    )
+    .unwrap();
+
+    let scl_arg = format!("file:{}", dir.join("tune_diff_major.scl").display());
+    let kbm_arg = format!("kbm:{}", dir.join("tune_diff_white_keys.kbm").display());
+
+    let output = call_cli_piped(
+        &[
+            "scale", "ref-note", "60", "--lo-key", "60", "--up-key", "73", "steps", "1:12:2",
+        ],
+        &["diff", "stdin", "spec", &scl_arg, "+", &kbm_arg],
+    );
+    check_output!(
+        "snapshots/diff_against_tuning_spec_with_key_mapping.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn diff_quarter_comma_and_31_edo() {
     let output = call_cli_piped(
@@ -80,6 +394,116 @@ fn diff_quarter_comma_and_31_edo() {
     );
 }
 
+#[test]
+fn diff_quarter_comma_and_31_edo_as_csv() {
+    let output = call_cli_piped(
+        &[
+            "scale", "ref-note", "62", "--lo-key", "61", "--up-key", "71", "rank2", "1:4:5", "5",
+            "1",
+        ],
+        &[
+            "--format", "csv", "diff", "stdin", "ref-note", "62", "steps", "1:31:2",
+        ],
+    );
+    check_output!(
+        "snapshots/README_diff_quarter_comma_and_31_edo_as_csv.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn chords_of_quarter_comma_meantone() {
+    let output = call_cli(&["chords", "ref-note", "62", "rank2", "1:4:5", "5", "1"]);
+    check_output!(
+        "snapshots/README_chords_of_quarter_comma_meantone.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn chords_of_22_edo() {
+    let output = call_cli(&[
+        "chords",
+        "--chord",
+        "4:5:6:7",
+        "--chord",
+        "8:9:10:11",
+        "--tol",
+        "15c",
+        "spec",
+        "22edo",
+    ]);
+    check_output!("snapshots/chords_of_22_edo.stdout", output.stdout);
+}
+
+#[test]
+fn chords_of_22_edo_as_json() {
+    let output = call_cli(&[
+        "--format", "json", "chords", "--chord", "4:5:6:7", "--tol", "15c", "spec", "22edo",
+    ]);
+    check_output!("snapshots/chords_of_22_edo_as_json.stdout", output.stdout);
+}
+
+#[test]
+fn chords_in_val_of_31_edo() {
+    let output = call_cli(&["chords", "--val", "1:31:2"]);
+    check_output!(
+        "snapshots/README_chords_in_val_of_31_edo.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn chords_in_val_of_12_edo_as_csv() {
+    let output = call_cli(&[
+        "--format",
+        "csv",
+        "chords",
+        "--chord",
+        "4:5:6:7",
+        "--chord",
+        "8:9:10:11",
+        "--val",
+        "1:12:2",
+    ]);
+    check_output!(
+        "snapshots/chords_in_val_of_12_edo_as_csv.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dissonance_around_fifth() {
+    let output = call_cli(&["dissonance", "--lo", "680c", "--hi", "720c", "--step", "5c"]);
+    check_output!(
+        "snapshots/README_dissonance_around_fifth.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn dissonance_of_inharmonic_spectrum() {
+    let output = call_cli(&[
+        "dissonance",
+        "--lo",
+        "0c",
+        "--hi",
+        "1200c",
+        "--step",
+        "100c",
+        "--he-lim",
+        "1000",
+        "--spectrum",
+        "1,2.76@0.5,5.4@0.25,8.93@0.125",
+        "--fund",
+        "440Hz",
+    ]);
+    check_output!(
+        "snapshots/dissonance_of_inharmonic_spectrum.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn diff_quarter_comma_and_31_edo_with_shift() {
     let output = call_cli_piped(
@@ -106,6 +530,43 @@ fn mts_of_19_edo() {
     check_output!("snapshots/mts_of_19_edo.stderr", output.stderr);
 }
 
+#[test]
+fn mts_of_7_edo_as_json() {
+    let output = call_cli(&[
+        "--format", "json", "mts", "full-rt", "ref-note", "62", "steps", "1:7:2",
+    ]);
+    check_output!("snapshots/mts_of_7_edo_as_json.stdout", output.stdout);
+}
+
+#[test]
+fn octave_tuning_of_31_edo_as_csv() {
+    let output = call_cli(&[
+        "--format",
+        "csv",
+        "mts",
+        "octave-1",
+        "--lo-chan",
+        "9",
+        "ref-note",
+        "62",
+        "steps",
+        "1:31:2",
+    ]);
+    check_output!(
+        "snapshots/octave_tuning_of_31_edo_as_csv.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn bulk_dump_of_19_edo() {
+    let output = call_cli(&[
+        "mts", "bulk", "--name", "19-EDO", "ref-note", "62", "steps", "1:19:2",
+    ]);
+    check_output!("snapshots/bulk_dump_of_19_edo.stdout", output.stdout);
+    check_output!("snapshots/bulk_dump_of_19_edo.stderr", output.stderr);
+}
+
 #[test]
 fn octave_tuning_of_31_edo() {
     let output = call_cli(&[
@@ -124,6 +585,28 @@ fn octave_tuning_of_31_edo() {
     check_output!("snapshots/octave_tuning_of_31_edo.stderr", output.stderr);
 }
 
+#[test]
+fn octave_tuning_of_werckmeister3() {
+    let output = call_cli(&[
+        "mts",
+        "octave-1",
+        "ref-note",
+        "62",
+        "hist",
+        "werckmeister3",
+        "--rot",
+        "2",
+    ]);
+    check_output!(
+        "snapshots/octave_tuning_of_werckmeister3.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/octave_tuning_of_werckmeister3.stderr",
+        output.stderr
+    );
+}
+
 #[test]
 fn octave_tuning_of_13_edt() {
     let output = call_cli(&[
@@ -162,6 +645,12 @@ fn analysis_of_7_edo() {
     check_output!("snapshots/analysis_of_7_edo.stdout", output.stdout);
 }
 
+#[test]
+fn analysis_of_7_edo_as_json() {
+    let output = call_cli(&["--format", "json", "est", "1:7:2"]);
+    check_output!("snapshots/analysis_of_7_edo_as_json.stdout", output.stdout);
+}
+
 #[test]
 fn analysis_of_13_edo() {
     let output = call_cli(&["est", "1:13:2"]);
@@ -198,6 +687,15 @@ fn moses_from_700_cents_generator() {
     );
 }
 
+#[test]
+fn moses_from_700_cents_generator_as_csv() {
+    let output = call_cli(&["--format", "csv", "mos", "find", "700c"]);
+    check_output!(
+        "snapshots/README_moses_from_700_cents_generator_as_csv.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn moses_from_lowest_ratios() {
     let output = call_cli(&["mos", "find", "--per", "2", "3"]);
@@ -243,6 +741,15 @@ fn generators_for_6l4s_edt() {
     check_output!("snapshots/generators_for_6l4s.stdout", output.stdout);
 }
 
+#[test]
+fn generators_for_5l2s_as_yaml() {
+    let output = call_cli(&["--format", "yaml", "mos", "gen", "5", "2"]);
+    check_output!(
+        "snapshots/generators_for_5l2s_as_yaml.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn create_scl() {
     let output = call_cli(&[
@@ -261,6 +768,24 @@ fn create_scl() {
     check_output!("snapshots/README_create_scl.stdout", output.stdout);
 }
 
+#[test]
+fn create_rank2_scl_as_yaml() {
+    let output = call_cli(&["--format", "yaml", "scl", "rank2", "3/2", "5", "1"]);
+    check_output!(
+        "snapshots/README_create_rank2_scl_as_yaml.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn create_scl_with_exact_pitch_values_as_json() {
+    let output = call_cli(&["--format", "json", "scl", "steps", "9/8,5/4,701.955c,2"]);
+    check_output!(
+        "snapshots/create_scl_with_exact_pitch_values_as_json.stdout",
+        output.stdout
+    );
+}
+
 #[test]
 fn create_harmonics_scale() {
     let output = call_cli(&["scl", "harm", "-u", "37", "74", "--neji=13"]);
@@ -270,6 +795,99 @@ fn create_harmonics_scale() {
     );
 }
 
+#[test]
+fn create_historical_scale() {
+    let output = call_cli(&["scl", "hist", "kirnberger3"]);
+    check_output!(
+        "snapshots/README_create_historical_scale.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn create_spectrum_scale() {
+    let output = call_cli(&["scl", "spectrum", "1,2.76,5.4,8.93", "--per", "2.76"]);
+    check_output!(
+        "snapshots/README_create_spectrum_scale.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn transform_scale_to_mode() {
+    let dir = env::temp_dir();
+    fs::write(dir.join("tune_ji_major.scl"), JI_MAJOR).unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &["scl", "transform", "tune_ji_major.scl", "mode", "5"],
+    );
+    check_output!(
+        "snapshots/README_transform_scale_to_mode.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn transform_scale_by_merging_and_subsetting() {
+    let dir = env::temp_dir();
+    fs::write(dir.join("tune_ji_major_to_merge.scl"), JI_MAJOR).unwrap();
+    fs::write(
+        dir.join("tune_septimal.scl"),
+        "\
+Septimal tetrad
+3
+5/4
+3/2
+7/4
+",
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "scl",
+            "transform",
+            "tune_ji_major_to_merge.scl",
+            "merge",
+            "tune_septimal.scl",
+            "--tol",
+            "5c",
+        ],
+    );
+    check_output!("snapshots/transform_scale_by_merging.stdout", output.stdout);
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "scl",
+            "--name",
+            "Pentatonic",
+            "transform",
+            "tune_ji_major_to_merge.scl",
+            "subset",
+            "1,2,4,5",
+        ],
+    );
+    check_output!(
+        "snapshots/transform_scale_by_subsetting.stdout",
+        output.stdout
+    );
+}
+
+const JI_MAJOR: &str = "\
+Ptolemy's intense diatonic
+7
+9/8
+5/4
+4/3
+3/2
+5/3
+15/8
+2
+";
+
 #[test]
 fn create_kbm_root() {
     let output = call_cli(&["kbm", "ref-note", "62"]);
@@ -295,3 +913,603 @@ fn crate_kbm() {
     ]);
     check_output!("snapshots/README_create_kbm.stdout", output.stdout);
 }
+
+#[test]
+fn create_kbm_as_yaml() {
+    let output = call_cli(&[
+        "--format",
+        "yaml",
+        "kbm",
+        "ref-note",
+        "62",
+        "--key-map",
+        "0,x,1",
+        "--octave",
+        "2",
+    ]);
+    check_output!("snapshots/create_kbm_as_yaml.stdout", output.stdout);
+}
+
+#[test]
+fn retune_16_edo_ahead_of_time() {
+    let input_file = create_retune_input_file("tune_retune_16_edo_input.mid", None);
+    let output_file = env::temp_dir().join("tune_retune_16_edo_output.mid");
+
+    let output = call_cli(&[
+        "retune",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "aot",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+
+    let output_smf = Smf::read(File::open(&output_file).unwrap()).unwrap();
+
+    check_output!(
+        "snapshots/retune_16_edo_ahead_of_time.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/retune_16_edo_ahead_of_time.events",
+        format_smf_events(&output_smf).as_bytes()
+    );
+}
+
+#[test]
+fn analyze_midi_16_edo() {
+    let input_file = create_16_edo_pitch_bend_file("tune_analyze_midi_16_edo.mid");
+
+    let output = call_cli(&["analyze-midi", input_file.to_str().unwrap()]);
+    check_output!("snapshots/analyze_midi_16_edo.stdout", output.stdout);
+}
+
+#[test]
+fn analyze_midi_16_edo_against_scale() {
+    let input_file = create_16_edo_pitch_bend_file("tune_analyze_midi_16_edo_against_scale.mid");
+
+    let output = call_cli(&[
+        "analyze-midi",
+        input_file.to_str().unwrap(),
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+    check_output!(
+        "snapshots/analyze_midi_16_edo_against_scale.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn analyze_midi_16_edo_as_json() {
+    let input_file = create_16_edo_pitch_bend_file("tune_analyze_midi_16_edo_as_json.mid");
+
+    let output = call_cli(&[
+        "--format",
+        "json",
+        "analyze-midi",
+        input_file.to_str().unwrap(),
+    ]);
+    check_output!(
+        "snapshots/analyze_midi_16_edo_as_json.stdout",
+        output.stdout
+    );
+}
+
+#[test]
+fn retune_16_edo_just_in_time_with_release_time() {
+    let input_file = create_retune_input_file("tune_retune_16_edo_jit_input.mid", None);
+    let output_file = env::temp_dir().join("tune_retune_16_edo_jit_output.mid");
+
+    call_cli(&[
+        "retune",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "jit",
+        "--release-time",
+        "1",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+
+    let output_smf = Smf::read(File::open(&output_file).unwrap()).unwrap();
+
+    check_output!(
+        "snapshots/retune_16_edo_just_in_time_with_release_time.events",
+        format_smf_events(&output_smf).as_bytes()
+    );
+}
+
+#[test]
+fn retune_16_edo_adaptive() {
+    let input_file = create_retune_input_file("tune_retune_16_edo_adaptive_input.mid", None);
+    let output_file = env::temp_dir().join("tune_retune_16_edo_adaptive_output.mid");
+
+    call_cli(&[
+        "retune",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "adaptive",
+        "--max-drift",
+        "30",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+
+    let output_smf = Smf::read(File::open(&output_file).unwrap()).unwrap();
+
+    check_output!(
+        "snapshots/retune_16_edo_adaptive.events",
+        format_smf_events(&output_smf).as_bytes()
+    );
+}
+
+#[test]
+fn retune_with_overlapping_channels_is_not_supported() {
+    let input_file = create_retune_input_file("tune_retune_overlapping_input.mid", Some(1));
+    let output_file = env::temp_dir().join("tune_retune_overlapping_output.mid");
+
+    let output = call_cli(&[
+        "retune",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "--in-chans",
+        "1",
+        "aot",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+
+    check_output!(
+        "snapshots/retune_with_overlapping_channels_is_not_supported.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn retune_as_json_is_not_supported() {
+    let input_file = create_retune_input_file("tune_retune_as_json_input.mid", None);
+    let output_file = env::temp_dir().join("tune_retune_as_json_output.mid");
+
+    let output = call_cli(&[
+        "--format",
+        "json",
+        "retune",
+        input_file.to_str().unwrap(),
+        output_file.to_str().unwrap(),
+        "aot",
+        "pitch-bend",
+        "ref-note",
+        "62",
+        "steps",
+        "1:16:2",
+    ]);
+
+    check_output!(
+        "snapshots/retune_as_json_is_not_supported.stderr",
+        output.stderr
+    );
+}
+
+fn create_retune_input_file(file_name: &str, drone_channel: Option<u8>) -> PathBuf {
+    let file_location = env::temp_dir().join(file_name);
+
+    let note = |ticks, channel, message_type: ChannelMessageType| SmfEvent {
+        ticks,
+        message: SmfMessage::Channel(message_type.in_channel(channel).unwrap()),
+    };
+    let note_on = |key| ChannelMessageType::NoteOn { key, velocity: 100 };
+    let note_off = |key| ChannelMessageType::NoteOff { key, velocity: 64 };
+
+    let mut tracks = vec![
+        SmfTrack {
+            events: vec![SmfEvent {
+                ticks: 0,
+                message: SmfMessage::Tempo(600_000),
+            }],
+        },
+        SmfTrack {
+            events: vec![
+                note(0, 0, note_on(62)),
+                note(48, 0, note_on(63)),
+                note(96, 0, note_off(62)),
+                note(96, 0, note_off(63)),
+                note(96, 0, note_on(64)),
+                note(192, 0, note_off(64)),
+            ],
+        },
+    ];
+    if let Some(drone_channel) = drone_channel {
+        tracks.push(SmfTrack {
+            events: vec![
+                note(0, drone_channel, note_on(50)),
+                note(192, drone_channel, note_off(50)),
+            ],
+        });
+    }
+
+    Smf {
+        ticks_per_quarter: 96,
+        tracks,
+    }
+    .write(File::create(&file_location).unwrap())
+    .unwrap();
+
+    file_location
+}
+
+fn format_smf_events(smf: &Smf) -> String {
+    let mut events = format!("ticks_per_quarter: {}\n", smf.ticks_per_quarter);
+    for (track_index, track) in smf.tracks.iter().enumerate() {
+        for event in &track.events {
+            writeln!(
+                events,
+                "track {track_index} | {:>4} | {:?}",
+                event.ticks, event.message
+            )
+            .unwrap();
+        }
+    }
+    events
+}
+
+fn create_16_edo_pitch_bend_file(file_name: &str) -> PathBuf {
+    let file_location = env::temp_dir().join(file_name);
+
+    let mut events = Vec::new();
+    for (channel, ticks, key, pitch_bend) in [
+        (0, 0, 62, 0),
+        (1, 96, 63, -1024),
+        (2, 192, 63, 2048),
+        (3, 288, 64, 1024),
+        (0, 384, 62, 0),
+    ] {
+        let message = |message_type: ChannelMessageType| {
+            SmfMessage::Channel(message_type.in_channel(channel).unwrap())
+        };
+        events.extend([
+            SmfEvent {
+                ticks,
+                message: message(ChannelMessageType::PitchBendChange { value: pitch_bend }),
+            },
+            SmfEvent {
+                ticks,
+                message: message(ChannelMessageType::NoteOn { key, velocity: 100 }),
+            },
+            SmfEvent {
+                ticks: ticks + 96,
+                message: message(ChannelMessageType::NoteOff { key, velocity: 64 }),
+            },
+        ]);
+    }
+
+    Smf {
+        ticks_per_quarter: 96,
+        tracks: vec![SmfTrack { events }],
+    }
+    .write(File::create(&file_location).unwrap())
+    .unwrap();
+
+    file_location
+}
+
+#[test]
+fn live_16_edo_from_smf_to_json_file() {
+    let dir = env::temp_dir();
+    create_16_edo_pitch_bend_file("tune_live_16_edo_input.mid");
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_16_edo_input.mid",
+            "--midi-out",
+            "file:tune_live_16_edo_output.json",
+            "--out-chans",
+            "2",
+            "jit",
+            "fine-tuning",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_16_edo_from_smf_to_json_file.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/live_16_edo_from_smf_to_json_file.json",
+        fs::read(dir.join("tune_live_16_edo_output.json")).unwrap()
+    );
+}
+
+#[test]
+fn live_from_json_to_smf_file() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_live_json_input.json"),
+        r#"[
+            { "time_in_secs": 0.0, "message": [144, 62, 100] },
+            { "time_in_secs": 0.5, "message": [128, 62, 64] }
+        ]"#,
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_json_input.json",
+            "--midi-out",
+            "file:tune_live_json_output.mid",
+            "aot",
+            "octave-1",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    let output_smf = Smf::read(File::open(dir.join("tune_live_json_output.mid")).unwrap()).unwrap();
+
+    check_output!("snapshots/live_from_json_to_smf_file.stdout", output.stdout);
+    check_output!(
+        "snapshots/live_from_json_to_smf_file.events",
+        format_smf_events(&output_smf).as_bytes()
+    );
+}
+
+#[test]
+fn live_from_unsorted_json_file() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_live_unsorted_input.json"),
+        r#"[
+            { "time_in_secs": 0.5, "message": [128, 62, 64] },
+            { "time_in_secs": 0.0, "message": [144, 62, 100] }
+        ]"#,
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_unsorted_input.json",
+            "--midi-out",
+            "file:tune_live_unsorted_output.mid",
+            "aot",
+            "octave-1",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    let output_smf =
+        Smf::read(File::open(dir.join("tune_live_unsorted_output.mid")).unwrap()).unwrap();
+
+    check_output!(
+        "snapshots/live_from_unsorted_json_file.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/live_from_unsorted_json_file.events",
+        format_smf_events(&output_smf).as_bytes()
+    );
+}
+
+#[test]
+fn live_from_json_file_with_negative_time() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_live_negative_time_input.json"),
+        r#"[{ "time_in_secs": -1.0, "message": [144, 62, 100] }]"#,
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_negative_time_input.json",
+            "--midi-out",
+            "file:tune_live_negative_time_output.mid",
+            "aot",
+            "octave-1",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_from_json_file_with_negative_time.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn live_mpe_with_member_channels_overlapping_the_manager_channel() {
+    let dir = env::temp_dir();
+    fs::write(dir.join("tune_live_mpe_input.json"), "[]").unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_mpe_input.json",
+            "--midi-out",
+            "file:tune_live_mpe_output.json",
+            "jit",
+            "mpe",
+            "ref-note",
+            "62",
+            "steps",
+            "1:17:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_mpe_with_member_channels_overlapping_the_manager_channel.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn live_with_pitch_bend_range_out_of_range() {
+    let dir = env::temp_dir();
+    fs::write(dir.join("tune_live_bend_range_input.json"), "[]").unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_bend_range_input.json",
+            "--midi-out",
+            "file:tune_live_bend_range_output.json",
+            "--bend-range",
+            "0",
+            "jit",
+            "pitch-bend",
+            "ref-note",
+            "62",
+            "steps",
+            "1:17:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_with_pitch_bend_range_out_of_range.stderr",
+        output.stderr
+    );
+}
+
+#[test]
+fn live_split_keyboard_with_pipeline() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_live_pipeline.yaml"),
+        "\
+controllers:
+  1: 74
+zones:
+  - keys: { to: 59 }
+    transpose: -2
+  - keys: { from: 60 }
+    velocity: { exponent: 2.0, min: 20, max: 100 }
+    scale: ref-note 62 steps 1:22:2
+",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tune_live_pipeline_input.json"),
+        r#"[
+            { "time_in_secs": 0.0, "message": [176, 1, 90] },
+            { "time_in_secs": 0.0, "message": [144, 55, 64] },
+            { "time_in_secs": 0.0, "message": [144, 67, 64] },
+            { "time_in_secs": 0.5, "message": [128, 55, 64] },
+            { "time_in_secs": 0.5, "message": [144, 67, 0] }
+        ]"#,
+    )
+    .unwrap();
+
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_pipeline_input.json",
+            "--pipeline",
+            "tune_live_pipeline.yaml",
+            "--midi-out",
+            "file:tune_live_pipeline_output.json",
+            "--out-chans",
+            "2",
+            "jit",
+            "pitch-bend",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_split_keyboard_with_pipeline.stdout",
+        output.stdout
+    );
+    check_output!(
+        "snapshots/live_split_keyboard_with_pipeline.json",
+        fs::read(dir.join("tune_live_pipeline_output.json")).unwrap()
+    );
+}
+
+#[test]
+fn live_aot_split_keyboard_with_pipeline() {
+    let dir = env::temp_dir();
+    fs::write(
+        dir.join("tune_live_aot_pipeline.yaml"),
+        "\
+zones:
+  - keys: { to: 59 }
+  - keys: { from: 60 }
+    scale: ref-note 62 steps 1:22:2
+",
+    )
+    .unwrap();
+    fs::write(dir.join("tune_live_aot_pipeline_input.json"), "[]").unwrap();
+
+    // Only the keys reachable through the zones are tuned
+    let output = call_cli_in_dir(
+        &dir,
+        &[
+            "live",
+            "--midi-in",
+            "file:tune_live_aot_pipeline_input.json",
+            "--pipeline",
+            "tune_live_aot_pipeline.yaml",
+            "--midi-out",
+            "file:tune_live_aot_pipeline_output.json",
+            "--out-chans",
+            "16",
+            "aot",
+            "full",
+            "ref-note",
+            "62",
+            "steps",
+            "1:16:2",
+        ],
+    );
+
+    check_output!(
+        "snapshots/live_aot_split_keyboard_with_pipeline.stdout",
+        output.stdout
+    );
+}